@@ -0,0 +1,180 @@
+use axum::extract::rejection::{JsonRejection, QueryRejection};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+
+/// REST handler failure, sent as `{ "error": kind, "message": ... }`
+#[derive(Debug)]
+pub enum ApiError {
+    /// 404: nothing known under the requested key
+    NotFound(String),
+    /// 400: malformed query string, body or parameter
+    BadRequest(String),
+    /// 503: an upstream (source, Upbit feed) has nothing to give right now
+    Unavailable(String),
+    /// 500: a bug or a failed background task
+    Internal(String),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Unavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            ApiError::NotFound(_) => "not_found",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Unavailable(_) => "unavailable",
+            ApiError::Internal(_) => "internal",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            ApiError::NotFound(m) | ApiError::BadRequest(m) | ApiError::Unavailable(m) | ApiError::Internal(m) => m,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if let ApiError::Internal(message) = &self {
+            tracing::error!("❌ API internal error: {}", message);
+        }
+        let body = serde_json::json!({
+            "error": self.kind(),
+            "message": self.message(),
+        });
+        (self.status(), axum::Json(body)).into_response()
+    }
+}
+
+/// Bad `?query` strings answer with the JSON body instead of axum's plain text
+impl From<QueryRejection> for ApiError {
+    fn from(rejection: QueryRejection) -> Self {
+        ApiError::BadRequest(rejection.body_text())
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        ApiError::BadRequest(rejection.body_text())
+    }
+}
+
+impl From<tokio::task::JoinError> for ApiError {
+    fn from(e: tokio::task::JoinError) -> Self {
+        ApiError::Internal(format!("background task failed: {}", e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::{Path, Query};
+    use axum::routing::get;
+    use axum::Json;
+
+    #[derive(serde::Deserialize)]
+    struct Search {
+        limit: usize,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Item {
+        name: String,
+    }
+
+    /// One route per way a handler fails, served on an ephemeral port
+    async fn failing_api() -> String {
+        let router = axum::Router::new()
+            .route("/items/:id", get(|Path(id): Path<String>| async move {
+                Err::<Json<()>, _>(ApiError::NotFound(format!("no item {id}")))
+            }))
+            .route("/search", get(|query: Result<Query<Search>, QueryRejection>| async move {
+                let Query(search) = query?;
+                Ok::<_, ApiError>(Json(format!("found {}", search.limit)))
+            }))
+            .route("/items", axum::routing::post(|body: Result<Json<Item>, JsonRejection>| async move {
+                let Json(item) = body?;
+                Ok::<_, ApiError>(Json(format!("created {}", item.name)))
+            }))
+            .route("/upstream", get(|| async {
+                Err::<Json<()>, _>(ApiError::Unavailable("every source failed".to_string()))
+            }))
+            .route("/task", get(|| async {
+                tokio::spawn(async { panic!("boom") }).await?;
+                Ok::<_, ApiError>(Json("done"))
+            }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    async fn send(req: reqwest::RequestBuilder) -> (u16, serde_json::Value) {
+        let response = req.send().await.unwrap();
+        let status = response.status().as_u16();
+        (status, response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn each_variant_maps_to_its_status_and_json_body() {
+        let base = failing_api().await;
+        let client = reqwest::Client::new();
+
+        let (status, body) = send(client.get(format!("{base}/items/42"))).await;
+        assert_eq!(status, 404);
+        assert_eq!(body, serde_json::json!({ "error": "not_found", "message": "no item 42" }));
+
+        let (status, body) = send(client.get(format!("{base}/upstream"))).await;
+        assert_eq!(status, 503);
+        assert_eq!(body, serde_json::json!({ "error": "unavailable", "message": "every source failed" }));
+
+        let (status, body) = send(client.get(format!("{base}/task"))).await;
+        assert_eq!(status, 500);
+        assert_eq!(body["error"], "internal");
+        assert!(body["message"].as_str().unwrap().starts_with("background task failed"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn rejected_queries_and_bodies_are_json_bad_requests() {
+        let base = failing_api().await;
+        let client = reqwest::Client::new();
+
+        let (status, body) = send(client.get(format!("{base}/search?limit=ten"))).await;
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "bad_request");
+        assert!(body["message"].as_str().unwrap().contains("query string"), "{body}");
+        assert_eq!(send(client.get(format!("{base}/search?limit=10"))).await, (200, serde_json::json!("found 10")));
+
+        let (status, body) = send(client.post(format!("{base}/items")).body(r#"{"name": 1}"#)
+            .header("content-type", "application/json")).await;
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "bad_request");
+        assert!(body["message"].as_str().unwrap().contains("name"), "{body}");
+
+        // Missing content type is a rejection too, not axum's plain-text 415
+        let (status, body) = send(client.post(format!("{base}/items")).body(r#"{"name": "x"}"#)).await;
+        assert_eq!((status, body["error"].as_str()), (400, Some("bad_request")));
+    }
+
+    #[test]
+    fn kinds_and_statuses_line_up() {
+        let cases = [
+            (ApiError::NotFound("a".to_string()), StatusCode::NOT_FOUND, "not_found"),
+            (ApiError::BadRequest("b".to_string()), StatusCode::BAD_REQUEST, "bad_request"),
+            (ApiError::Unavailable("c".to_string()), StatusCode::SERVICE_UNAVAILABLE, "unavailable"),
+            (ApiError::Internal("d".to_string()), StatusCode::INTERNAL_SERVER_ERROR, "internal"),
+        ];
+        for (error, status, kind) in cases {
+            assert_eq!((error.status(), error.kind()), (status, kind), "{error:?}");
+            assert_eq!(error.into_response().status(), status);
+        }
+    }
+}
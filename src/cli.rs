@@ -0,0 +1,157 @@
+use std::path::PathBuf;
+use clap::{Parser, Subcommand};
+use crate::services::aggregate::PriceAggregation;
+
+/// 업비트 KRW 코인 DEX 풀 수집 / 아비트라지 탐지
+#[derive(Debug, Parser)]
+#[command(name = "dex-gatherer", version)]
+pub struct Cli {
+    /// Configuration file (missing = environment only)
+    #[arg(long, global = true, env = "DEX_CONFIG", default_value = "config.toml")]
+    pub config: PathBuf,
+
+    /// Defaults to `serve`
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Collector + API server
+    Serve,
+    /// Price monitor over saved pools (data/pools)
+    Monitor {
+        /// Per-symbol aggregation: mean | median | vwap
+        #[arg(long)]
+        aggregation: Option<PriceAggregation>,
+    },
+    /// Upbit vs DEX gap monitor
+    Gap {
+        /// Gap threshold in percent
+        #[arg(short, long, default_value_t = 1.0)]
+        threshold: f64,
+        /// Symbols checked per cycle (0 = every KRW pair)
+        #[arg(long, visible_alias = "gap-limit", default_value_t = 0)]
+        max_symbols: usize,
+        /// Pairs per symbol (0 = no cap)
+        #[arg(long, default_value_t = 5)]
+        max_pairs: usize,
+        /// DexScreener searches per second (0 = unlimited)
+        #[arg(long, default_value_t = 5.0, value_parser = parse_rps)]
+        rps: f64,
+        /// Directory for the daily gap history files
+        #[arg(long, default_value = "./data/gaps")]
+        gap_out: PathBuf,
+    },
+    /// Query a few sources for one symbol and print what they return
+    Debug {
+        symbol: String,
+    },
+    /// Source connectivity self-test (exit code 1 if every primary source fails)
+    Selftest,
+    /// Save the first response of each source as a parser fixture (tests/fixtures)
+    CaptureFixtures,
+    /// Convert a JSON snapshot (.json / .json.gz) to CSV next to it
+    ExportCsv {
+        path: PathBuf,
+    },
+}
+
+fn parse_rps(value: &str) -> Result<f64, String> {
+    let rps: f64 = value.parse().map_err(|e| format!("{e}"))?;
+    crate::sources::ratelimit::check_rps(rps)?;
+    Ok(rps)
+}
+
+/// Flag spellings from before the subcommands existed, e.g. `--gap -t 1.0`
+const LEGACY_FLAGS: &[(&str, &str)] = &[
+    ("--monitor", "monitor"),
+    ("-m", "monitor"),
+    ("--gap", "gap"),
+    ("-g", "gap"),
+    ("--selftest", "selftest"),
+    ("--capture-fixtures", "capture-fixtures"),
+    ("--export-csv", "export-csv"),
+];
+
+impl Cli {
+    /// Parse the process arguments, accepting the legacy mode flags
+    pub fn from_env() -> Self {
+        Self::parse_from(rewrite_legacy(std::env::args().collect()))
+    }
+}
+
+/// Replace the first legacy mode flag with its subcommand, in place
+fn rewrite_legacy(mut args: Vec<String>) -> Vec<String> {
+    if let Some((i, name)) = args.iter().enumerate().skip(1).find_map(|(i, arg)| {
+        LEGACY_FLAGS.iter().find(|(flag, _)| flag == arg).map(|(_, name)| (i, *name))
+    }) {
+        args[i] = name.to_string();
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn parse(args: &[&str]) -> Result<Cli, clap::Error> {
+        let args = std::iter::once("dex-gatherer").chain(args.iter().copied()).map(String::from).collect();
+        Cli::try_parse_from(rewrite_legacy(args))
+    }
+
+    #[test]
+    fn no_subcommand_means_serve() {
+        let cli = parse(&[]).unwrap();
+        assert!(cli.command.is_none());
+        assert!(matches!(parse(&["serve"]).unwrap().command, Some(Command::Serve)));
+    }
+
+    #[test]
+    fn gap_options_are_typed() {
+        let cli = parse(&["gap", "-t", "2.5", "--max-symbols", "30", "--rps", "0"]).unwrap();
+        let Some(Command::Gap { threshold, max_symbols, max_pairs, rps, gap_out }) = cli.command else {
+            panic!("expected gap, got {:?}", cli.command);
+        };
+        assert_eq!((threshold, max_symbols, max_pairs, rps), (2.5, 30, 5, 0.0));
+        assert_eq!(gap_out, PathBuf::from("./data/gaps"));
+
+        // Every KRW pair unless capped
+        let cli = parse(&["gap"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Gap { max_symbols: 0, .. })));
+
+        // Old spelling of --max-symbols
+        let cli = parse(&["gap", "--gap-limit", "7"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Gap { max_symbols: 7, .. })));
+
+        assert!(parse(&["gap", "--rps=-1"]).is_err());
+        assert!(parse(&["gap", "--rps", "1e-300"]).is_err(), "would overflow the limiter interval");
+        assert!(parse(&["gap", "--rps", "NaN"]).is_err());
+        assert!(parse(&["gap", "-t"]).is_err(), "-t needs a value");
+        assert!(parse(&["gap", "-t", "lots"]).is_err());
+    }
+
+    #[test]
+    fn legacy_flags_map_to_subcommands() {
+        let cli = parse(&["--gap", "-t", "1.5"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Gap { threshold, .. }) if threshold == 1.5));
+        assert!(matches!(parse(&["-m"]).unwrap().command, Some(Command::Monitor { aggregation: None })));
+        assert!(matches!(parse(&["--selftest"]).unwrap().command, Some(Command::Selftest)));
+        let cli = parse(&["--export-csv", "snap.json"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::ExportCsv { path }) if path == Path::new("snap.json")));
+    }
+
+    #[test]
+    fn debug_and_global_config() {
+        let cli = parse(&["debug", "ETH", "--config", "/etc/dex.toml"]).unwrap();
+        assert_eq!(cli.config, PathBuf::from("/etc/dex.toml"));
+        assert!(matches!(cli.command, Some(Command::Debug { symbol }) if symbol == "ETH"));
+        assert!(parse(&["debug"]).is_err(), "symbol is required");
+
+        let cli = parse(&["monitor", "--aggregation", "median"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Monitor { aggregation: Some(PriceAggregation::Median) })));
+        assert!(parse(&["monitor", "--aggregation", "mode"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+    }
+}
@@ -1,5 +1,12 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::fs;
+use std::path::Path;
+use crate::services::discovery::SymbolSource;
+use crate::services::ladder::LadderRung;
+use crate::services::matcher::MatchMode;
+use crate::sources::quote::QuoteAsset;
+use crate::sources::ratelimit::check_rps;
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct Config {
@@ -8,21 +15,105 @@ pub struct Config {
     pub server: ServerConfig,
     #[serde(default)]
     pub storage: StorageConfig,
+    #[serde(default)]
+    pub debug: DebugConfig,
+    #[serde(default)]
+    pub dexguru: DexGuruConfig,
+    #[serde(default)]
+    pub uniswap_v3: UniswapV3Config,
+    #[serde(default)]
+    pub api_keys: ApiKeysConfig,
+    #[serde(default)]
+    pub sources: SourcesConfig,
+    #[serde(default)]
+    pub notify: NotifyConfig,
+    #[serde(default)]
+    pub quote: QuoteConfig,
+    #[serde(default)]
+    pub costs: CostConfig,
+    #[serde(default)]
+    pub collector: CollectorConfig,
+    #[serde(default)]
+    pub concurrency: ConcurrencyConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub live_history: LiveHistoryConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub gap: GapConfig,
+    #[serde(default)]
+    pub upbit: UpbitConfig,
+    #[serde(default)]
+    pub binance: BinanceConfig,
+    #[serde(default)]
+    pub matching: MatchingConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct ArbitrageConfig {
     pub threshold: f64,
+    /// Seconds between /ws updates
     pub update_interval: u64,
+    /// Seconds between /ws pings
+    #[serde(default = "default_ws_heartbeat")]
+    pub ws_heartbeat_secs: u64,
+    /// Named alert thresholds; empty = single rung at `threshold`
+    #[serde(default)]
+    pub ladder: Vec<LadderRung>,
+    /// POST target of the `page` destination (`webhook` uses `[notify].webhook_url`)
+    #[serde(default)]
+    pub page_url: Option<String>,
+    /// Suppress alerts whose estimated realized profit (USD) is below this
+    #[serde(default)]
+    pub min_profit_usd: f64,
+    /// Don't re-emit the same opportunity within this many seconds (0 = off)
+    #[serde(default = "default_alert_cooldown")]
+    pub cooldown_secs: u64,
+    /// symbol -> threshold overriding `threshold` / the lowest ladder rung
+    #[serde(default)]
+    pub per_symbol: HashMap<String, f64>,
+    /// Min return correlation of a DEX-DEX pair over recent cycles (absent = off)
+    #[serde(default)]
+    pub min_correlation: Option<f64>,
+    /// Cycles of price history kept for the correlation check
+    #[serde(default = "default_correlation_window")]
+    pub correlation_window: usize,
+    /// Ignore pools fetched more than this many seconds ago (0 = off)
+    #[serde(default)]
+    pub max_age_secs: u64,
+    /// Drop pools priced more than this factor above/below their symbol's
+    /// median before detection (0 = off; needs 3+ pools per symbol)
+    #[serde(default = "default_outlier_factor")]
+    pub outlier_factor: f64,
 }
 
+fn default_alert_cooldown() -> u64 { 300 }
+fn default_outlier_factor() -> f64 { 10.0 }
+fn default_ws_heartbeat() -> u64 { 10 }
+fn default_correlation_window() -> usize { 20 }
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct FilterConfig {
     pub min_lp: f64,
     pub min_volume: f64,
-    pub min_tx_count: u32,
+    /// Let pools without LP/volume data (aggregator quotes) through
+    #[serde(default = "default_allow_zero_liquidity")]
+    pub allow_zero_liquidity: bool,
+    /// chain -> threshold overrides (top-level values are the defaults)
+    #[serde(default)]
+    pub per_chain: HashMap<String, ChainFilter>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ChainFilter {
+    pub min_lp: Option<f64>,
+    pub min_volume: Option<f64>,
 }
 
+fn default_allow_zero_liquidity() -> bool { true }
+
 #[derive(Debug, Deserialize, Clone)]
 pub struct ServerConfig {
     pub host: String,
@@ -33,26 +124,919 @@ pub struct ServerConfig {
 pub struct StorageConfig {
     #[serde(default = "default_enabled")]
     pub enabled: bool,
+    #[serde(default)]
+    pub backend: StorageBackendKind,
+    /// Database file for the sqlite backend
+    #[serde(default = "default_sqlite_path")]
+    pub sqlite_path: String,
     #[serde(default = "default_data_dir")]
     pub data_dir: String,
+    /// PoolData fields written to disk (empty = all fields)
+    #[serde(default)]
+    pub fields: Vec<String>,
+    /// Persist detected alerts every cycle
+    #[serde(default)]
+    pub save_alerts: bool,
+    #[serde(default)]
+    pub alerts_format: AlertFileFormat,
+    /// Also write each snapshot as CSV
+    #[serde(default)]
+    pub csv_snapshots: bool,
+    /// Gzip JSON snapshots (full_*.json.gz)
+    #[serde(default)]
+    pub compress: bool,
+    /// Delete snapshots older than this many days (0 = keep forever)
+    #[serde(default)]
+    pub max_snapshot_age_days: u64,
+    /// Keep at most this many snapshots (0 = unlimited)
+    #[serde(default)]
+    pub max_snapshot_files: usize,
+}
+
+/// `json` = dated files under `data_dir`, `sqlite` = one database (needs the `sqlite` feature)
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackendKind {
+    #[default]
+    Json,
+    Sqlite,
+}
+
+/// `json` = one `alerts_<timestamp>.json` per cycle, `ndjson` = append to a daily file
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertFileFormat {
+    Json,
+    #[default]
+    Ndjson,
 }
 
 fn default_enabled() -> bool { true }
 fn default_data_dir() -> String { "./data".to_string() }
+fn default_sqlite_path() -> String { "./data/pools.db".to_string() }
 
 impl Default for StorageConfig {
     fn default() -> Self {
         Self {
             enabled: true,
+            backend: StorageBackendKind::default(),
+            sqlite_path: default_sqlite_path(),
             data_dir: "./data".to_string(),
+            fields: Vec::new(),
+            save_alerts: false,
+            alerts_format: AlertFileFormat::default(),
+            csv_snapshots: false,
+            compress: false,
+            max_snapshot_age_days: 0,
+            max_snapshot_files: 0,
         }
     }
 }
 
+#[derive(Debug, Deserialize, Clone)]
+pub struct DebugConfig {
+    /// Directory for raw bodies that failed to parse (disabled when unset)
+    #[serde(default)]
+    pub deadletter_dir: Option<String>,
+    #[serde(default = "default_deadletter_max_files")]
+    pub deadletter_max_files: usize,
+}
+
+fn default_deadletter_max_files() -> usize { 200 }
+
+impl Default for DebugConfig {
+    fn default() -> Self {
+        Self {
+            deadletter_dir: None,
+            deadletter_max_files: default_deadletter_max_files(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct DexGuruConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Chain IDs to query (1 = Ethereum, 56 = BSC, 137 = Polygon)
+    #[serde(default = "default_dexguru_chains")]
+    pub chains: Vec<u32>,
+    /// Aggregators to query per chain ("0x", "1inch")
+    #[serde(default = "default_dexguru_aggregators")]
+    pub aggregators: Vec<String>,
+}
+
+fn default_dexguru_chains() -> Vec<u32> { vec![1, 56, 137] }
+fn default_dexguru_aggregators() -> Vec<String> { vec!["0x".to_string(), "1inch".to_string()] }
+
+impl Default for DexGuruConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chains: default_dexguru_chains(),
+            aggregators: default_dexguru_aggregators(),
+        }
+    }
+}
+
+/// Uniswap V3 subgraphs (The Graph gateway, needs `[api_keys] thegraph`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct UniswapV3Config {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Chains to query: ethereum | arbitrum | base | polygon
+    #[serde(default = "default_uniswap_v3_chains")]
+    pub chains: Vec<String>,
+    /// Chain -> full subgraph URL, replacing the gateway default for that chain
+    #[serde(default)]
+    pub subgraph_urls: HashMap<String, String>,
+}
+
+fn default_uniswap_v3_chains() -> Vec<String> {
+    ["ethereum", "arbitrum", "base", "polygon"].map(String::from).to_vec()
+}
+
+impl Default for UniswapV3Config {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            chains: default_uniswap_v3_chains(),
+            subgraph_urls: HashMap::new(),
+        }
+    }
+}
+
+/// Stablecoin the aggregator sources quote against
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct QuoteConfig {
+    /// "usdc" | "usdt"
+    #[serde(default)]
+    pub default: QuoteAsset,
+    /// chain name or id -> asset overriding `default`
+    #[serde(default)]
+    pub per_chain: HashMap<String, QuoteAsset>,
+}
+
+/// Source name (case-insensitive) -> enabled; unlisted sources stay enabled
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(transparent)]
+pub struct SourcesConfig {
+    flags: HashMap<String, bool>,
+}
+
+impl SourcesConfig {
+    pub fn is_enabled(&self, name: &str) -> bool {
+        self.flags.iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .is_none_or(|(_, enabled)| *enabled)
+    }
+
+    /// Explicitly set to true (for sources that are off by default)
+    pub fn is_opted_in(&self, name: &str) -> bool {
+        self.flags.iter().any(|(key, enabled)| *enabled && key.eq_ignore_ascii_case(name))
+    }
+
+    /// Log keys that don't match any source (likely typos)
+    pub fn warn_unknown(&self, known: &[&str]) {
+        for key in self.flags.keys() {
+            if !known.iter().any(|name| name.eq_ignore_ascii_case(key)) {
+                tracing::warn!("⚠️ Unknown source in [sources]: {} (known: {})", key, known.join(", "));
+            }
+        }
+    }
+}
+
+/// Keys for sources that reject anonymous requests; blank = not configured
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct ApiKeysConfig {
+    #[serde(default)]
+    pub oneinch: Option<String>,
+    #[serde(default)]
+    pub zerox: Option<String>,
+    /// The Graph gateway key (Uniswap V3 subgraphs)
+    #[serde(default)]
+    pub thegraph: Option<String>,
+}
+
+impl ApiKeysConfig {
+    fn non_empty(key: &Option<String>) -> Option<&str> {
+        key.as_deref().map(str::trim).filter(|k| !k.is_empty())
+    }
+
+    pub fn oneinch(&self) -> Option<&str> {
+        Self::non_empty(&self.oneinch)
+    }
+
+    pub fn zerox(&self) -> Option<&str> {
+        Self::non_empty(&self.zerox)
+    }
+
+    pub fn thegraph(&self) -> Option<&str> {
+        Self::non_empty(&self.thegraph)
+    }
+}
+
+/// Push notifications for new (deduplicated) alerts
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct NotifyConfig {
+    /// Minimum net spread (fraction, 0.02 = 2%); 0 = every alert that passed the cooldown
+    #[serde(default)]
+    pub threshold: f64,
+    /// Receives each alert as a JSON POST; when a ladder rung routes to `webhook`,
+    /// the ladder decides which alerts are sent here instead of `threshold`
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    #[serde(default)]
+    pub telegram_bot_token: Option<String>,
+    /// Numeric ids (e.g. -1001234567890) or "@channel"
+    #[serde(default, deserialize_with = "string_or_int")]
+    pub telegram_chat_id: Option<String>,
+}
+
+impl NotifyConfig {
+    pub fn webhook_url(&self) -> Option<String> {
+        self.webhook_url.as_deref().map(str::trim).filter(|u| !u.is_empty()).map(str::to_string)
+    }
+}
+
+/// Accept `123`, `-100123` or `"@name"` for ids
+fn string_or_int<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Id {
+        Int(i64),
+        Str(String),
+    }
+    Ok(Option::<Id>::deserialize(deserializer)?.map(|id| match id {
+        Id::Int(n) => n.to_string(),
+        Id::Str(s) => s,
+    }))
+}
+
+/// Trading cost assumptions (fractions, USD)
+#[derive(Debug, Deserialize, Clone)]
+pub struct CostConfig {
+    /// Swap fee when the pool's fee tier is unknown (0.003 = 0.3%)
+    #[serde(default = "default_fee")]
+    pub default_fee: f64,
+    /// Assumed price impact for pools without LP data (aggregator quotes)
+    #[serde(default = "default_zero_lp_slippage")]
+    pub zero_lp_slippage: f64,
+    #[serde(default = "default_gas_usd")]
+    pub default_gas_usd: f64,
+    /// chain -> gas cost of one swap in USD
+    #[serde(default = "default_chain_gas")]
+    pub gas_usd: HashMap<String, f64>,
+    /// Trade size used to express gas as a fraction of the spread in alerts
+    #[serde(default = "default_reference_trade")]
+    pub reference_trade_usd: f64,
+}
+
+fn default_fee() -> f64 { 0.003 }
+fn default_zero_lp_slippage() -> f64 { 0.005 }
+fn default_gas_usd() -> f64 { 1.0 }
+fn default_reference_trade() -> f64 { 1000.0 }
+fn default_chain_gas() -> HashMap<String, f64> {
+    [
+        ("ethereum", 15.0),
+        ("bsc", 0.3),
+        ("polygon", 0.05),
+        ("arbitrum", 0.3),
+        ("optimism", 0.2),
+        ("base", 0.1),
+        ("avalanche", 0.5),
+    ]
+    .into_iter()
+    .map(|(chain, gas)| (chain.to_string(), gas))
+    .collect()
+}
+
+impl Default for CostConfig {
+    fn default() -> Self {
+        Self {
+            default_fee: default_fee(),
+            zero_lp_slippage: default_zero_lp_slippage(),
+            default_gas_usd: default_gas_usd(),
+            gas_usd: default_chain_gas(),
+            reference_trade_usd: default_reference_trade(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CollectorConfig {
+    /// Symbol universe: "upbit", "dex" or "both"
+    #[serde(default)]
+    pub symbol_source: SymbolSource,
+    /// Max number of DEX-discovered symbols
+    #[serde(default = "default_discovery_limit")]
+    pub discovery_limit: usize,
+    /// Minimum pool liquidity (USD) for a discovered symbol
+    #[serde(default = "default_discovery_min_liquidity")]
+    pub discovery_min_liquidity: f64,
+    /// Trending-pool pages to scan during discovery
+    #[serde(default = "default_discovery_pages")]
+    pub discovery_pages: u32,
+    /// Suppress repeated new-pool alerts for the same symbol within this window
+    #[serde(default = "default_new_pool_cooldown")]
+    pub new_pool_cooldown_secs: u64,
+    /// chain -> preferred source order; the first listed source wins when
+    /// several sources report the same pool
+    #[serde(default)]
+    pub source_priority: HashMap<String, Vec<String>>,
+    /// Only collect these symbols (empty = all)
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Never collect these symbols
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Probe every source once before starting (report only, never exits)
+    #[serde(default)]
+    pub startup_selftest: bool,
+    /// Attempts per source request on network errors / rate limits
+    #[serde(default = "default_retry_attempts")]
+    pub retry_attempts: u32,
+    #[serde(default = "default_retry_base_ms")]
+    pub retry_base_ms: u64,
+    /// Budget per source call for one symbol, rate-limit waits and retries included
+    #[serde(default = "default_fetch_timeout")]
+    pub fetch_timeout_secs: u64,
+    /// source name -> max requests per second (unlisted = unlimited)
+    #[serde(default = "default_rate_limits")]
+    pub rate_limits: HashMap<String, f64>,
+}
+
+fn default_discovery_limit() -> usize { 50 }
+fn default_discovery_min_liquidity() -> f64 { 100_000.0 }
+fn default_discovery_pages() -> u32 { 3 }
+fn default_new_pool_cooldown() -> u64 { 600 }
+fn default_retry_attempts() -> u32 { 3 }
+fn default_retry_base_ms() -> u64 { 500 }
+fn default_fetch_timeout() -> u64 { 30 }
+fn default_rate_limits() -> HashMap<String, f64> {
+    HashMap::from([
+        ("GeckoTerminal".to_string(), 2.0),
+        ("DexScreener".to_string(), 5.0),
+    ])
+}
+
+impl Default for CollectorConfig {
+    fn default() -> Self {
+        Self {
+            symbol_source: SymbolSource::default(),
+            discovery_limit: default_discovery_limit(),
+            discovery_min_liquidity: default_discovery_min_liquidity(),
+            discovery_pages: default_discovery_pages(),
+            new_pool_cooldown_secs: default_new_pool_cooldown(),
+            source_priority: HashMap::new(),
+            include: Vec::new(),
+            exclude: Vec::new(),
+            startup_selftest: false,
+            retry_attempts: default_retry_attempts(),
+            retry_base_ms: default_retry_base_ms(),
+            fetch_timeout_secs: default_fetch_timeout(),
+            rate_limits: default_rate_limits(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct CacheConfig {
+    #[serde(default = "default_cache_ttl")]
+    pub ttl_seconds: u64,
+    #[serde(default = "default_cleanup_interval")]
+    pub cleanup_interval: u64,
+    /// Hard cap on cached pools (0 = unbounded)
+    #[serde(default)]
+    pub max_entries: usize,
+    /// Snapshot file written on shutdown and restored on startup
+    #[serde(default)]
+    pub persist_path: Option<String>,
+}
+
+fn default_cache_ttl() -> u64 { 120 }
+fn default_cleanup_interval() -> u64 { 60 }
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl_seconds: default_cache_ttl(),
+            cleanup_interval: default_cleanup_interval(),
+            max_entries: 0,
+            persist_path: None,
+        }
+    }
+}
+
+/// In-memory per-symbol VWAP series (`/history/live/:symbol`)
+#[derive(Debug, Deserialize, Clone)]
+pub struct LiveHistoryConfig {
+    /// Points kept per symbol (one per collection cycle)
+    #[serde(default = "default_live_history_points")]
+    pub max_points: usize,
+    /// Symbols tracked at once; the least recently updated is evicted beyond this
+    #[serde(default = "default_live_history_symbols")]
+    pub max_symbols: usize,
+}
+
+fn default_live_history_points() -> usize { 1440 }
+fn default_live_history_symbols() -> usize { 1000 }
+
+impl Default for LiveHistoryConfig {
+    fn default() -> Self {
+        Self {
+            max_points: default_live_history_points(),
+            max_symbols: default_live_history_symbols(),
+        }
+    }
+}
+
+/// In-flight request limits
+#[derive(Debug, Deserialize, Clone)]
+pub struct ConcurrencyConfig {
+    /// Symbol fetches in flight across all sources (shared semaphore)
+    #[serde(default = "default_symbol_concurrency")]
+    pub symbol_concurrency: usize,
+    /// Symbols one source fetches at a time
+    #[serde(default = "default_source_concurrency")]
+    pub source_concurrency: usize,
+    /// Concurrent DexScreener lookups in `monitor` mode
+    #[serde(default = "default_price_monitor_concurrency")]
+    pub price_monitor_concurrency: usize,
+}
+
+fn default_symbol_concurrency() -> usize { 20 }
+fn default_source_concurrency() -> usize { 20 }
+fn default_price_monitor_concurrency() -> usize { 50 }
+
+impl Default for ConcurrencyConfig {
+    fn default() -> Self {
+        Self {
+            symbol_concurrency: default_symbol_concurrency(),
+            source_concurrency: default_source_concurrency(),
+            price_monitor_concurrency: default_price_monitor_concurrency(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MetricsConfig {
+    /// Gap histogram reset window in seconds (0 = never reset)
+    #[serde(default = "default_gap_window")]
+    pub gap_window_secs: u64,
+}
+
+fn default_gap_window() -> u64 { 3600 }
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { gap_window_secs: default_gap_window() }
+    }
+}
+
+/// Caps of the `/gaps` endpoint (Upbit vs DexScreener), 0 = no cap
+#[derive(Debug, Deserialize, Clone)]
+pub struct GapConfig {
+    /// Symbols searched per request
+    #[serde(default = "default_gap_max_symbols")]
+    pub max_symbols: usize,
+    /// Pairs read per DexScreener search
+    #[serde(default = "default_gap_max_pairs")]
+    pub max_pairs: usize,
+    /// DexScreener searches per second (0 = unlimited)
+    #[serde(default = "default_gap_rps")]
+    pub rps: f64,
+}
+
+fn default_gap_max_symbols() -> usize { 50 }
+fn default_gap_max_pairs() -> usize { 3 }
+fn default_gap_rps() -> f64 { 5.0 }
+
+impl Default for GapConfig {
+    fn default() -> Self {
+        Self {
+            max_symbols: default_gap_max_symbols(),
+            max_pairs: default_gap_max_pairs(),
+            rps: default_gap_rps(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct UpbitConfig {
+    /// Use the KRW-USDT market price as the KRW/USD rate when available
+    #[serde(default = "default_enabled")]
+    pub use_usdt_rate: bool,
+    /// Fallback KRW/USD rate
+    #[serde(default = "default_krw_usd_rate")]
+    pub krw_usd_rate: f64,
+    /// Max wait for the first websocket ticker before starting anyway
+    #[serde(default = "default_ready_timeout")]
+    pub ready_timeout_secs: u64,
+    /// Timeout for the market list request
+    #[serde(default = "default_market_timeout")]
+    pub market_timeout_secs: u64,
+    /// USD-base FX endpoint refreshing the fallback rate (empty = static `krw_usd_rate`)
+    #[serde(default = "default_fx_url")]
+    pub fx_url: Option<String>,
+    #[serde(default = "default_fx_refresh")]
+    pub fx_refresh_secs: u64,
+    /// On-disk cache of the KRW market list (empty = off)
+    #[serde(default = "default_market_cache_path")]
+    pub market_cache_path: Option<String>,
+    #[serde(default = "default_market_cache_ttl")]
+    pub market_cache_ttl_secs: u64,
+    /// Attempts at the market list (exponential backoff between them)
+    #[serde(default = "default_market_retries")]
+    pub market_retries: u32,
+    /// Reconnect the websocket after this many seconds without a ticker
+    #[serde(default = "default_stale_secs")]
+    pub stale_secs: u64,
+}
+
+fn default_fx_url() -> Option<String> { Some("https://open.er-api.com/v6/latest/USD".to_string()) }
+fn default_fx_refresh() -> u64 { 3600 }
+fn default_market_cache_path() -> Option<String> { Some("data/upbit_markets.json".to_string()) }
+fn default_market_cache_ttl() -> u64 { 3600 }
+fn default_market_retries() -> u32 { 4 }
+fn default_stale_secs() -> u64 { 30 }
+
+fn default_krw_usd_rate() -> f64 { 1400.0 }
+fn default_ready_timeout() -> u64 { 10 }
+fn default_market_timeout() -> u64 { 10 }
+
+impl Default for UpbitConfig {
+    fn default() -> Self {
+        Self {
+            use_usdt_rate: true,
+            krw_usd_rate: default_krw_usd_rate(),
+            ready_timeout_secs: default_ready_timeout(),
+            market_timeout_secs: default_market_timeout(),
+            fx_url: default_fx_url(),
+            fx_refresh_secs: default_fx_refresh(),
+            market_cache_path: default_market_cache_path(),
+            market_cache_ttl_secs: default_market_cache_ttl(),
+            market_retries: default_market_retries(),
+            stale_secs: default_stale_secs(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct BinanceConfig {
+    /// Add Binance USDT spot prices to the DEX-CEX path
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_binance_rest_url")]
+    pub rest_url: String,
+    #[serde(default = "default_binance_ws_url")]
+    pub ws_url: String,
+    /// Live miniTicker stream; otherwise the REST snapshot is polled
+    #[serde(default = "default_enabled")]
+    pub use_websocket: bool,
+    /// REST polling interval when the websocket is off
+    #[serde(default = "default_binance_refresh")]
+    pub refresh_secs: u64,
+    #[serde(default = "default_market_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_binance_rest_url() -> String { "https://api.binance.com".to_string() }
+fn default_binance_ws_url() -> String { "wss://stream.binance.com:9443".to_string() }
+fn default_binance_refresh() -> u64 { 30 }
+
+impl Default for BinanceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rest_url: default_binance_rest_url(),
+            ws_url: default_binance_ws_url(),
+            use_websocket: true,
+            refresh_secs: default_binance_refresh(),
+            timeout_secs: default_market_timeout(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct MatchingConfig {
+    /// "exact", "alias" or "address"
+    #[serde(default)]
+    pub mode: MatchMode,
+    /// alias -> canonical symbol
+    #[serde(default = "default_aliases")]
+    pub aliases: HashMap<String, String>,
+    /// pool address -> canonical symbol ("address" mode)
+    #[serde(default)]
+    pub addresses: HashMap<String, String>,
+}
+
+fn default_aliases() -> HashMap<String, String> {
+    [
+        ("WETH", "ETH"),
+        ("WBTC", "BTC"),
+        ("WBNB", "BNB"),
+        ("WAVAX", "AVAX"),
+        ("WPOL", "POL"),
+    ]
+    .into_iter()
+    .map(|(alias, canonical)| (alias.to_string(), canonical.to_string()))
+    .collect()
+}
+
+impl Default for MatchingConfig {
+    fn default() -> Self {
+        Self {
+            mode: MatchMode::default(),
+            aliases: default_aliases(),
+            addresses: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file exists but couldn't be read
+    Io(std::path::PathBuf, std::io::Error),
+    /// Not valid TOML, or doesn't match the config schema
+    Parse(String),
+    /// Parsed fine but a value is out of range
+    Invalid { key: &'static str, reason: String },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "{}: {}", path.display(), e),
+            ConfigError::Parse(e) => write!(f, "Config parse error: {}", e),
+            ConfigError::Invalid { key, reason } => write!(f, "Invalid config `{}`: {}", key, reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn invalid(key: &'static str, reason: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid { key, reason: reason.into() }
+}
+
+/// Env override prefix: `DEX_SERVER__PORT=8080` -> `[server] port = 8080`
+const ENV_PREFIX: &str = "DEX_";
+
 impl Config {
-    pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string("config.toml")?;
-        let config: Config = toml::from_str(&content)?;
+    /// `path` (missing file = empty) with `DEX_<SECTION>__<KEY>` env vars layered on top
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        Self::load_with_env(path, std::env::vars())
+    }
+
+    /// `load` with the environment given explicitly
+    fn load_with_env(path: &Path, vars: impl Iterator<Item = (String, String)>) -> Result<Self, ConfigError> {
+        let mut value = match fs::read_to_string(path) {
+            Ok(content) => content.parse::<toml::Table>()
+                .map_err(|e| ConfigError::Parse(format!("{}: {}", path.display(), e)))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                tracing::warn!("⚠️ {} not found, using environment only", path.display());
+                toml::Table::new()
+            }
+            Err(e) => return Err(ConfigError::Io(path.to_path_buf(), e)),
+        };
+
+        let overridden = apply_env_overrides(&mut value, vars);
+        if !overridden.is_empty() {
+            tracing::info!("✓ Config overrides from environment: {}", overridden.join(", "));
+        }
+
+        let config: Config = toml::Value::Table(value).try_into()
+            .map_err(|e: toml::de::Error| ConfigError::Parse(e.to_string()))?;
+        config.validate()?;
         Ok(config)
     }
+
+    /// Reject values that parse but make no sense at runtime
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let threshold = self.arbitrage.threshold;
+        if !(threshold > 0.0 && threshold <= 1.0) {
+            return Err(invalid("arbitrage.threshold", format!("{threshold} is not in (0, 1] (0.01 = 1%)")));
+        }
+        // tokio `interval` panics on a zero period
+        if self.arbitrage.update_interval == 0 {
+            return Err(invalid("arbitrage.update_interval", "must be > 0"));
+        }
+        if self.arbitrage.ws_heartbeat_secs == 0 {
+            return Err(invalid("arbitrage.ws_heartbeat_secs", "must be > 0"));
+        }
+        let notify = self.notify.threshold;
+        if !(0.0..=1.0).contains(&notify) {
+            return Err(invalid("notify.threshold", format!("{notify} is not in [0, 1] (0.01 = 1%)")));
+        }
+        let factor = self.arbitrage.outlier_factor;
+        if !(factor == 0.0 || factor > 1.0) {
+            return Err(invalid("arbitrage.outlier_factor", format!("{factor} must be 0 (off) or > 1")));
+        }
+        for (key, value) in [
+            ("concurrency.symbol_concurrency", self.concurrency.symbol_concurrency),
+            ("concurrency.source_concurrency", self.concurrency.source_concurrency),
+            ("concurrency.price_monitor_concurrency", self.concurrency.price_monitor_concurrency),
+        ] {
+            if value == 0 {
+                return Err(invalid(key, "must be >= 1"));
+            }
+        }
+        if self.server.port == 0 {
+            return Err(invalid("server.port", "must be > 0"));
+        }
+        if self.collector.fetch_timeout_secs == 0 {
+            return Err(invalid("collector.fetch_timeout_secs", "must be > 0"));
+        }
+        for (source, rps) in &self.collector.rate_limits {
+            check_rps(*rps).map_err(|e| invalid("collector.rate_limits", format!("{source}: {e}")))?;
+        }
+        check_rps(self.gap.rps).map_err(|e| invalid("gap.rps", e))?;
+
+        let non_negative = |key: &'static str, value: f64| {
+            if value >= 0.0 { Ok(()) } else { Err(invalid(key, format!("{value} is negative"))) }
+        };
+        non_negative("filter.min_lp", self.filter.min_lp)?;
+        non_negative("filter.min_volume", self.filter.min_volume)?;
+        for (chain, rule) in &self.filter.per_chain {
+            let check = |key: &'static str, value: Option<f64>| match value {
+                Some(v) if v.is_nan() || v < 0.0 => Err(invalid(key, format!("{v} is negative (chain {chain})"))),
+                _ => Ok(()),
+            };
+            check("filter.per_chain.min_lp", rule.min_lp)?;
+            check("filter.per_chain.min_volume", rule.min_volume)?;
+        }
+        Ok(())
+    }
+}
+
+/// Set `section.key` for every `DEX_SECTION__KEY` var; returns the dotted keys set.
+/// Values are read as TOML (numbers, bools, arrays) and fall back to plain strings.
+fn apply_env_overrides(root: &mut toml::Table, vars: impl Iterator<Item = (String, String)>) -> Vec<String> {
+    let mut applied = Vec::new();
+    for (name, raw) in vars {
+        let Some(rest) = name.strip_prefix(ENV_PREFIX) else { continue };
+        let path: Vec<String> = rest.split("__").map(|s| s.to_lowercase()).collect();
+        // Every top-level entry is a section, so a key needs at least two parts
+        if path.len() < 2 || path.iter().any(|s| s.is_empty()) {
+            continue;
+        }
+
+        let value = format!("v = {raw}").parse::<toml::Table>().ok()
+            .and_then(|mut t| t.remove("v"))
+            .unwrap_or_else(|| toml::Value::String(raw.clone()));
+
+        let (key, sections) = path.split_last().expect("len >= 2");
+        let mut table = &mut *root;
+        for section in sections {
+            let entry = table.entry(section.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+            if !entry.is_table() {
+                *entry = toml::Value::Table(toml::Table::new());
+            }
+            table = entry.as_table_mut().expect("just made a table");
+        }
+        table.insert(key.clone(), value);
+        applied.push(path.join("."));
+    }
+    applied.sort();
+    applied
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    const BASE: &str = r#"
+        [arbitrage]
+        threshold = 0.01
+        update_interval = 30
+        [filter]
+        min_lp = 10000
+        min_volume = 1000
+        [server]
+        host = "0.0.0.0"
+        port = 8080
+    "#;
+
+    fn write_config(label: &str, content: &str) -> std::path::PathBuf {
+        let path = temp_dir(label).join("config.toml");
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    fn env(vars: &[(&str, &str)]) -> impl Iterator<Item = (String, String)> {
+        vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>().into_iter()
+    }
+
+    #[test]
+    fn env_vars_override_file_values() {
+        let path = write_config("config-env", BASE);
+        let config = Config::load_with_env(&path, env(&[
+            ("DEX_SERVER__PORT", "9090"),
+            ("DEX_ARBITRAGE__THRESHOLD", "0.02"),
+            ("DEX_SERVER__HOST", "127.0.0.1"),
+            ("DEX_STORAGE__ENABLED", "true"),
+            ("DEX_COLLECTOR__RATE_LIMITS__GECKOTERMINAL", "2"),
+            // Not ours / not a section key: ignored
+            ("PATH", "/usr/bin"),
+            ("DEX_CONFIG", "/etc/dex.toml"),
+        ])).unwrap();
+
+        assert_eq!(config.server.port, 9090);
+        assert_eq!(config.arbitrage.threshold, 0.02);
+        assert_eq!(config.server.host, "127.0.0.1", "non-TOML values are taken as strings");
+        assert!(config.storage.enabled);
+        assert_eq!(config.collector.rate_limits.get("geckoterminal"), Some(&2.0));
+        // Untouched keys keep the file's value
+        assert_eq!(config.filter.min_lp, 10_000.0);
+        assert_eq!(config.arbitrage.update_interval, 30);
+    }
+
+    #[test]
+    fn missing_file_with_env_only_config_loads() {
+        let path = temp_dir("config-env-only").join("absent.toml");
+        let config = Config::load_with_env(&path, env(&[
+            ("DEX_ARBITRAGE__THRESHOLD", "0.01"),
+            ("DEX_ARBITRAGE__UPDATE_INTERVAL", "15"),
+            ("DEX_FILTER__MIN_LP", "0"),
+            ("DEX_FILTER__MIN_VOLUME", "0"),
+            ("DEX_SERVER__HOST", "0.0.0.0"),
+            ("DEX_SERVER__PORT", "3000"),
+        ])).unwrap();
+        assert_eq!((config.server.port, config.arbitrage.update_interval), (3000, 15));
+
+        // Nothing at all: the required sections are missing
+        assert!(matches!(Config::load_with_env(&path, env(&[])), Err(ConfigError::Parse(_))));
+    }
+
+    #[test]
+    fn env_overrides_are_validated() {
+        let path = write_config("config-env-invalid", BASE);
+        let err = Config::load_with_env(&path, env(&[("DEX_SERVER__PORT", "0")])).unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid { key: "server.port", .. }), "{err}");
+    }
+
+    fn base() -> Config {
+        toml::from_str(BASE).unwrap()
+    }
+
+    #[test]
+    fn valid_config_passes() {
+        base().validate().unwrap();
+        let mut edge = base();
+        edge.arbitrage.threshold = 1.0;
+        edge.filter.min_lp = 0.0;
+        edge.filter.min_volume = 0.0;
+        edge.validate().unwrap();
+    }
+
+    /// Expected failing key, and the edit that breaks a valid config
+    type InvalidCase = (&'static str, fn(&mut Config));
+
+    #[test]
+    fn each_invalid_value_is_named() {
+        let cases: Vec<InvalidCase> = vec![
+            ("arbitrage.threshold", |c| c.arbitrage.threshold = 0.0),
+            ("arbitrage.threshold", |c| c.arbitrage.threshold = 1.5),
+            ("arbitrage.threshold", |c| c.arbitrage.threshold = f64::NAN),
+            ("arbitrage.update_interval", |c| c.arbitrage.update_interval = 0),
+            ("arbitrage.ws_heartbeat_secs", |c| c.arbitrage.ws_heartbeat_secs = 0),
+            ("notify.threshold", |c| c.notify.threshold = -0.1),
+            ("arbitrage.outlier_factor", |c| c.arbitrage.outlier_factor = 0.5),
+            ("concurrency.symbol_concurrency", |c| c.concurrency.symbol_concurrency = 0),
+            ("concurrency.source_concurrency", |c| c.concurrency.source_concurrency = 0),
+            ("concurrency.price_monitor_concurrency", |c| c.concurrency.price_monitor_concurrency = 0),
+            ("server.port", |c| c.server.port = 0),
+            ("collector.fetch_timeout_secs", |c| c.collector.fetch_timeout_secs = 0),
+            ("collector.rate_limits", |c| { c.collector.rate_limits.insert("GeckoTerminal".to_string(), -2.0); }),
+            ("collector.rate_limits", |c| { c.collector.rate_limits.insert("DexScreener".to_string(), 1e-300); }),
+            ("gap.rps", |c| c.gap.rps = f64::NAN),
+            ("gap.rps", |c| c.gap.rps = -5.0),
+            ("filter.min_lp", |c| c.filter.min_lp = -1.0),
+            ("filter.min_volume", |c| c.filter.min_volume = -0.01),
+            ("filter.per_chain.min_lp", |c| {
+                c.filter.per_chain.insert("bsc".to_string(), ChainFilter { min_lp: Some(-5.0), min_volume: None });
+            }),
+        ];
+        for (expected, break_it) in cases {
+            let mut config = base();
+            break_it(&mut config);
+            match config.validate() {
+                Err(ConfigError::Invalid { key, .. }) => assert_eq!(key, expected),
+                other => panic!("{expected}: expected Invalid, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn load_reports_invalid_and_unparsable_files() {
+        let path = write_config("config-invalid", &BASE.replace("threshold = 0.01", "threshold = 0"));
+        let err = Config::load_with_env(&path, env(&[])).unwrap_err();
+        assert!(matches!(err, ConfigError::Invalid { key: "arbitrage.threshold", .. }));
+        assert!(err.to_string().contains("arbitrage.threshold"), "{err}");
+
+        let path = write_config("config-garbage", "[arbitrage\nthreshold =");
+        assert!(matches!(Config::load_with_env(&path, env(&[])), Err(ConfigError::Parse(_))));
+    }
 }
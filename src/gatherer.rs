@@ -0,0 +1,171 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::config::Config;
+use crate::models::{ArbitrageAlert, PoolData};
+use crate::services::{ArbitrageDetector, PoolCache, PoolCollector, PoolFilter};
+use crate::services::comovement::PriceHistory;
+use crate::services::ladder::AlertLadder;
+use crate::services::matcher::SymbolMatcher;
+use crate::services::reconcile::SourcePriority;
+use crate::services::simulator::CostModel;
+use crate::sources::{self, CexSource, PoolSource};
+
+/// Process-wide source settings: rate limits, quote tokens, retry policy, deadletter capture.
+/// Call once, before the first request.
+pub fn init_sources(config: &Config) {
+    sources::ratelimit::init(&config.collector.rate_limits);
+    sources::quote::init(sources::quote::QuotePreference::new(config.quote.default, &config.quote.per_chain));
+    sources::set_retry_policy(sources::RetryPolicy {
+        max_attempts: config.collector.retry_attempts.max(1),
+        base_delay: Duration::from_millis(config.collector.retry_base_ms),
+        ..Default::default()
+    });
+
+    if let Some(ref dir) = config.debug.deadletter_dir {
+        sources::http::init_deadletter(dir, config.debug.deadletter_max_files);
+        tracing::info!("✓ Deadletter capture enabled -> {}", dir);
+    }
+}
+
+/// Pool cache sized by `[cache]`, restored from `persist_path` when set
+pub fn build_cache(config: &Config) -> Arc<PoolCache> {
+    let cache = Arc::new(match config.cache.max_entries {
+        0 => PoolCache::new(config.cache.ttl_seconds),
+        max => PoolCache::with_capacity(config.cache.ttl_seconds, max),
+    });
+    if config.cache.max_entries > 0 {
+        tracing::info!("✓ Pool cache capped at {} entries", cache.capacity());
+    }
+    if let Some(ref path) = config.cache.persist_path {
+        let restored = cache.load_from(Path::new(path));
+        if restored > 0 {
+            tracing::info!("✓ Restored {} cached pools from {}", restored, path);
+        }
+    }
+    cache
+}
+
+/// Collector with all configured sources and rules
+pub fn build_collector(config: &Config, cache: Arc<PoolCache>) -> PoolCollector {
+    let filter = PoolFilter::new(&config.filter);
+    configure_collector(PoolCollector::new_with_config(cache, filter, config), config)
+}
+
+/// `[collector]` rules (cooldown, priority, include/exclude)
+fn configure_collector(mut collector: PoolCollector, config: &Config) -> PoolCollector {
+    collector.set_new_pool_cooldown(config.collector.new_pool_cooldown_secs);
+    collector.set_source_priority(SourcePriority::new(&config.collector.source_priority));
+    collector.set_symbol_rules(&config.collector.include, &config.collector.exclude);
+    collector.set_concurrency(config.concurrency.symbol_concurrency, config.concurrency.source_concurrency);
+    collector.set_fetch_timeout(Duration::from_secs(config.collector.fetch_timeout_secs));
+    collector
+}
+
+/// Detector at the ladder's lowest threshold with costs and `[arbitrage]` rules
+pub fn build_detector(config: &Config) -> ArbitrageDetector {
+    let ladder = AlertLadder::from_config(&config.arbitrage);
+    let mut detector = ArbitrageDetector::with_costs(ladder.min_threshold(), CostModel::new(&config.costs));
+    detector.set_min_profit(config.arbitrage.min_profit_usd);
+    detector.set_matcher(SymbolMatcher::new(&config.matching));
+    detector.set_cooldown(config.arbitrage.cooldown_secs);
+    detector.set_per_symbol_thresholds(&config.arbitrage.per_symbol);
+    detector.set_max_age(config.arbitrage.max_age_secs);
+    detector.set_outlier_factor(config.arbitrage.outlier_factor);
+    if let Some(min_correlation) = config.arbitrage.min_correlation {
+        detector.set_comovement(PriceHistory::new(config.arbitrage.correlation_window, min_correlation));
+    }
+    detector
+}
+
+/// Collector + cache + detector wired from a [`Config`], without the server,
+/// Upbit feed or storage.
+///
+/// Call [`init_sources`] first: rate limits, quote tokens, retry policy and
+/// deadletter capture are process-wide and not set up by the constructors.
+///
+/// ```no_run
+/// use dex_gatherer::{gatherer, Config, DexGatherer};
+///
+/// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+/// let config = Config::load(std::path::Path::new("config.toml"))?;
+/// gatherer::init_sources(&config);
+/// let gatherer = DexGatherer::new(&config);
+/// let pools = gatherer.collect_once(&["ETH".to_string()]).await;
+/// println!("{} pools, {} alerts", pools.len(), gatherer.detect().len());
+/// # Ok(())
+/// # }
+/// ```
+pub struct DexGatherer {
+    cache: Arc<PoolCache>,
+    collector: Arc<PoolCollector>,
+    detector: Arc<ArbitrageDetector>,
+    ladder: AlertLadder,
+    /// DEX-CEX comparison targets (none = DEX-DEX only)
+    exchanges: Vec<Arc<dyn CexSource>>,
+}
+
+impl DexGatherer {
+    pub fn new(config: &Config) -> Self {
+        let cache = build_cache(config);
+        let collector = build_collector(config, cache.clone());
+        Self::from_parts(config, cache, collector)
+    }
+
+    /// Same wiring, but polling only `sources` instead of the configured ones
+    pub fn with_sources(config: &Config, sources: Vec<Arc<dyn PoolSource>>) -> Self {
+        let cache = build_cache(config);
+        let collector = PoolCollector::with_sources(sources, cache.clone(), PoolFilter::new(&config.filter));
+        Self::from_parts(config, cache, configure_collector(collector, config))
+    }
+
+    fn from_parts(config: &Config, cache: Arc<PoolCache>, collector: PoolCollector) -> Self {
+        let collector = Arc::new(collector);
+        Self {
+            cache,
+            collector,
+            detector: Arc::new(build_detector(config)),
+            ladder: AlertLadder::from_config(&config.arbitrage),
+            exchanges: Vec::new(),
+        }
+    }
+
+    /// Also detect DEX-CEX gaps against these exchanges
+    pub fn set_exchanges(&mut self, exchanges: Vec<Arc<dyn CexSource>>) {
+        self.exchanges = exchanges;
+    }
+
+    /// One collection cycle; returns the cached pools of `symbols` afterwards
+    pub async fn collect_once(&self, symbols: &[String]) -> Vec<PoolData> {
+        self.collector.collect_all(symbols).await;
+        self.cache.get_all()
+            .into_iter()
+            .filter(|pool| symbols.iter().any(|s| s.eq_ignore_ascii_case(&pool.symbol)))
+            .map(|pool| (*pool).clone())
+            .collect()
+    }
+
+    /// Current opportunities over the cache, tagged with their ladder rung
+    pub fn detect(&self) -> Vec<ArbitrageAlert> {
+        let pools = self.cache.get_all();
+        let mut alerts = self.detector.detect_dex_dex(&pools);
+        alerts.extend(self.detector.detect_dex_cex(&pools, &self.exchanges));
+        self.ladder.tag(alerts)
+    }
+
+    pub fn cache(&self) -> &Arc<PoolCache> {
+        &self.cache
+    }
+
+    pub fn collector(&self) -> &Arc<PoolCollector> {
+        &self.collector
+    }
+
+    pub fn detector(&self) -> &Arc<ArbitrageDetector> {
+        &self.detector
+    }
+
+    pub fn ladder(&self) -> &AlertLadder {
+        &self.ladder
+    }
+}
@@ -0,0 +1,17 @@
+//! DEX pool collection and arbitrage detection for Upbit KRW listings.
+//!
+//! The server binary is one consumer; [`DexGatherer`] is the entry point for
+//! embedding the collector elsewhere.
+
+pub mod config;
+pub mod gatherer;
+pub mod models;
+pub mod services;
+pub mod sources;
+
+#[cfg(test)]
+mod test_util;
+
+pub use config::Config;
+pub use gatherer::DexGatherer;
+pub use models::{ArbitrageAlert, PoolData};
@@ -1,36 +1,94 @@
-mod config;
-mod models;
-mod sources;
-mod services;
+mod api_error;
+mod cli;
+
+use dex_gatherer::{config, gatherer, models, services, sources};
 
 use std::sync::Arc;
 use std::path::Path;
-use crate::sources::PoolSource;
-use std::sync::atomic::Ordering;
+use std::collections::HashSet;
+use crate::sources::{CexSource, PoolSource};
+use std::sync::atomic::{AtomicI64, AtomicUsize, Ordering};
 use axum::{
     Router, 
-    routing::get,
-    extract::{State, ws::{WebSocket, WebSocketUpgrade, Message}},
+    routing::{get, post},
+    extract::{Path as UrlPath, Query, State, ws::{WebSocket, WebSocketUpgrade, Message}},
+    extract::rejection::{JsonRejection, QueryRejection},
     response::IntoResponse,
 };
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use tokio::time::{interval, Duration};
 use futures::{SinkExt, StreamExt};
+use futures::stream::SplitSink;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 
+use api_error::ApiError;
+use cli::Command;
 use config::Config;
+use dex_gatherer::DexGatherer;
 use services::{PoolCollector, ArbitrageDetector, PoolCache, PoolFilter, PriceMonitor};
+use services::discovery::{SymbolDiscovery, SymbolSource, merge_symbols};
+use services::ladder::{AlertDestination, AlertLadder, AlertRouter};
+use services::metrics::GapHistogram;
+use services::live_history::LivePriceHistory;
+use services::detector::AlertCooldown;
+use services::broadcast::{UpdateBroadcaster, WsUpdate};
+use services::gap_monitor::{GapMonitor, GapMonitorLimits};
+use services::notify::Notifications;
 use sources::upbit::UpbitClient;
+use sources::binance::BinanceClient;
 
 pub struct AppState {
     pub collector: Arc<PoolCollector>,
     pub detector: Arc<ArbitrageDetector>,
     pub cache: Arc<PoolCache>,
     pub upbit: Arc<UpbitClient>,
+    /// Every exchange on the DEX-CEX path (Upbit included)
+    pub exchanges: Vec<Arc<dyn CexSource>>,
     pub symbols: Vec<String>,
+    pub costs: services::simulator::CostModel,
+    pub ladder: AlertLadder,
+    pub gap_histogram: Arc<GapHistogram>,
+    /// Per-symbol VWAP of recent cycles
+    pub live_history: Arc<LivePriceHistory>,
+    pub alert_cooldown_secs: u64,
+    /// Currently connected /ws clients
+    pub ws_clients: AtomicUsize,
+    /// Cache snapshots + alerts fanned out to /ws connections
+    pub ws_updates: UpdateBroadcaster,
+    pub ws_update_interval: Duration,
+    pub ws_heartbeat: Duration,
+    pub shutdown: CancellationToken,
+    pub storage: Option<Arc<dyn services::StorageBackend>>,
+    /// Unix time the last collection cycle finished (0 = none yet)
+    pub last_collection: Arc<AtomicI64>,
+    pub started_at: i64,
+    /// Upbit counts as down after this long without a ticker
+    pub upbit_stale_secs: u64,
+    /// Result of the most recent collection cycle (None before the first)
+    pub last_cycle: Arc<parking_lot::RwLock<Option<LastCycle>>>,
+    /// Backs `/gaps`; every gap is returned (threshold 0)
+    pub gap_monitor: Arc<GapMonitor>,
+}
+
+/// One finished collection cycle (`/stats/last-cycle`)
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LastCycle {
+    #[serde(flatten)]
+    pub result: services::collector::CollectorResult,
+    /// Unix time the cycle finished
+    pub timestamp: i64,
+    pub duration_ms: u128,
+}
+
+impl models::timestamp::HasTimestamp for LastCycle {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
 }
 
-// main() 함수 바로 위에 추가
+/// `debug <symbol>`: raw results from a few sources for one symbol
 async fn debug_single_token(symbol: &str) {
     println!("\n🔍 테스트 중: {}\n", symbol);
     
@@ -40,129 +98,93 @@ async fn debug_single_token(symbol: &str) {
         Arc::new(sources::aggregators::MatchaSource::new()),
     ];
     
-    for source in sources {
-        print!("  {} ... ", source.name());
-        match source.fetch_pools(symbol).await {
-            Ok(pools) => {
-                println!("✅ {}개 풀", pools.len());
-                for pool in pools.iter().take(2) {
+    for report in services::selftest::debug_symbol(&sources, symbol, DEBUG_TIMEOUT, 2).await {
+        print!("  {} ... ", report.source);
+        match report.error {
+            None => {
+                println!("✅ {}개 풀 [{}ms]", report.pool_count, report.elapsed_ms);
+                for pool in &report.pools {
                     println!("    - ${:.4} @ {} (LP: ${})", 
                         pool.price_usd, pool.dex, pool.lp_reserve_usd);
                 }
             }
-            Err(e) => println!("❌ {}", e),
+            Some(e) => println!("❌ {}", e),
         }
     }
 }
 
-/// Gap monitor: Upbit vs DEX price comparison
-async fn run_gap_monitor(upbit: &UpbitClient, symbols: &[String], threshold: f64) {
-    use reqwest::Client;
-    
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
-        .build()
-        .unwrap();
-    
-    println!("\n🔍 갭 모니터링 시작 (30초 간격)");
-    println!("─────────────────────────────────────────────────────────");
-    
-    loop {
-        let start = std::time::Instant::now();
-        
-        // Fetch Upbit prices and convert to HashMap for lookup
-        let upbit_prices: std::collections::HashMap<String, f64> = upbit.get_all_prices()
-            .into_iter()
-            .map(|p| (p.symbol, p.price_usd))
-            .collect();
-        
-        // Fetch DEX prices from DexScreener
-        let mut gaps: Vec<(String, f64, f64, f64, String)> = Vec::new(); // (symbol, upbit, dex, gap%, source)
-        
-        for symbol in symbols.iter().take(50) { // Limit to 50 for speed
-            let url = format!("https://api.dexscreener.com/latest/dex/search?q={}", symbol);
-            
-            if let Ok(resp) = client.get(&url).send().await {
-                if resp.status().is_success() {
-                    if let Ok(data) = resp.json::<serde_json::Value>().await {
-                        if let Some(pairs) = data["pairs"].as_array() {
-                            for pair in pairs.iter().take(5) {
-                                let base_symbol = pair["baseToken"]["symbol"].as_str().unwrap_or("");
-                                let price_str = pair["priceUsd"].as_str().unwrap_or("0");
-                                let dex = pair["dexId"].as_str().unwrap_or("unknown");
-                                let chain = pair["chainId"].as_str().unwrap_or("unknown");
-                                
-                                if base_symbol.to_uppercase() == symbol.to_uppercase() {
-                                    if let Ok(dex_price) = price_str.parse::<f64>() {
-                                        if dex_price > 0.0 && dex_price < 1_000_000_000.0 {
-                                            // Get Upbit price
-                                            if let Some(upbit_price) = upbit_prices.get(symbol) {
-                                                let gap_pct = (*upbit_price - dex_price) / dex_price * 100.0;
-                                                
-                                                if gap_pct.abs() >= threshold * 100.0 {
-                                                    gaps.push((
-                                                        symbol.clone(),
-                                                        *upbit_price,
-                                                        dex_price,
-                                                        gap_pct,
-                                                        format!("{}:{}", dex, chain),
-                                                    ));
-                                                }
-                                            }
-                                            break; // One price per symbol is enough
-                                        }
-                                    }
-                                }
-                            }
+/// Per-source budget of `debug <symbol>` and `/sources/debug/:symbol`
+const DEBUG_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often the config file's mtime is checked for hot reload
+const CONFIG_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Re-apply the alert threshold and pool filter when the config file changes.
+/// Invalid edits are logged and the previous values kept; everything else
+/// (sources, ladder routing, ports, ...) still needs a restart.
+fn spawn_config_reload(path: std::path::PathBuf, state: Arc<AppState>, every: Duration) -> tokio::task::JoinHandle<()> {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    // Taken now, so an edit made before the task first runs still counts as a change
+    let mut last_modified = modified(&path);
+    tokio::spawn(async move {
+        let mut ticker = interval(every);
+        loop {
+            tokio::select! {
+                _ = state.shutdown.cancelled() => break,
+                _ = ticker.tick() => {
+                    let current = modified(&path);
+                    if current.is_none() || current == last_modified {
+                        continue;
+                    }
+                    last_modified = current;
+
+                    match Config::load(&path) {
+                        Ok(config) => {
+                            let threshold = AlertLadder::from_config(&config.arbitrage).min_threshold();
+                            state.detector.set_threshold(threshold);
+                            state.collector.set_filter(PoolFilter::new(&config.filter));
+                            tracing::info!(
+                                "🔄 Config reloaded: threshold {:.2}%, min_lp ${:.0}, min_volume ${:.0}",
+                                threshold * 100.0, config.filter.min_lp, config.filter.min_volume
+                            );
                         }
+                        Err(e) => tracing::warn!("⚠️ Config reload ignored, keeping previous values: {}", e),
                     }
                 }
             }
-            
-            // Small delay to avoid rate limiting
-            tokio::time::sleep(Duration::from_millis(50)).await;
-        }
-        
-        // Sort by gap percentage (descending by absolute value)
-        gaps.sort_by(|a, b| b.3.abs().partial_cmp(&a.3.abs()).unwrap());
-        
-        let elapsed = start.elapsed();
-        println!("\n⏱️  {} [{}개 갭 발견] ({:.2}초)",
-            chrono::Local::now().format("%H:%M:%S"),
-            gaps.len(),
-            elapsed.as_secs_f64()
-        );
-        
-        // Print gaps
-        if gaps.is_empty() {
-            println!("   갭 없음 (임계값 {:.1}% 이상)", threshold * 100.0);
-        } else {
-            println!("   {:8} {:>12} {:>12} {:>8} {}", "심볼", "업비트($)", "DEX($)", "갭(%)", "소스");
-            println!("   ──────── ──────────── ──────────── ──────── ─────────────");
-            for (symbol, upbit_price, dex_price, gap_pct, source) in gaps.iter().take(20) {
-                let arrow = if *gap_pct > 0.0 { "↗️" } else { "↘️" };
-                println!("   {:8} {:12.4} {:12.4} {:>+7.2}% {} {}",
-                    symbol, upbit_price, dex_price, gap_pct, arrow, source);
-            }
-        }
-        
-        // Wait for next interval (30 seconds)
-        let sleep_time = Duration::from_secs(30).saturating_sub(elapsed);
-        if sleep_time > Duration::ZERO {
-            tokio::time::sleep(sleep_time).await;
         }
-    }
+    })
 }
 
 #[tokio::main(worker_threads = 4)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let args: Vec<String> = std::env::args().collect();
+    let cli = cli::Cli::from_env();
+    let command = cli.command.unwrap_or(Command::Serve);
     
-    // Check for --monitor flag
-    if args.contains(&"--monitor".to_string()) || args.contains(&"-m".to_string()) {
+    // Convert a JSON snapshot to CSV and exit
+    if let Command::ExportCsv { path } = &command {
+        let (csv_path, count) = services::LocalStorage::export_csv(path)?;
+        println!("✓ {} 풀 -> {}", count, csv_path.display());
+        return Ok(());
+    }
+
+    if let Command::Debug { symbol } = &command {
+        debug_single_token(symbol).await;
+        return Ok(());
+    }
+
+    if let Command::Monitor { aggregation } = command {
         println!("\n🔄 DEX Price Monitor Mode\n");
         
         let mut monitor = PriceMonitor::new();
+        // Config is optional in monitor mode; only the lookup concurrency is read
+        match Config::load(&cli.config) {
+            Ok(config) => monitor.set_concurrency(config.concurrency.price_monitor_concurrency),
+            Err(e) => println!("⚠️ 설정 파일 없이 기본 동시성({})으로 실행: {}", monitor.concurrency(), e),
+        }
+        if let Some(method) = aggregation {
+            monitor.set_aggregation(method);
+        }
         let pools_path = Path::new("./data/pools");
         
         let loaded = monitor.load_pools(pools_path)?;
@@ -173,33 +195,43 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Ok(());
     }
     
-    // Check for --gap flag (Upbit vs DEX gap monitoring)
-    if args.contains(&"--gap".to_string()) || args.contains(&"-g".to_string()) {
+    // Upbit vs DEX gap monitoring
+    if let Command::Gap { threshold, max_symbols, max_pairs, rps, gap_out } = command {
         println!("\n📊 Gap Monitor Mode (Upbit vs DEX)\n");
-        
-        // Load pools from saved data
-        let mut monitor = PriceMonitor::new();
-        let pools_path = Path::new("./data/pools");
-        let loaded = monitor.load_pools(pools_path)?;
-        println!("✓ {} 풀 로드 완료", loaded);
-        
-        // Initialize Upbit
+
+        // Config is optional here; only the Upbit section is read
+        let upbit_config = Config::load(&cli.config).map(|c| c.upbit).unwrap_or_default();
+
+        // Initialize Upbit; gaps need live tickers, not just the market list
         println!("📡 Connecting to Upbit...");
-        let upbit = UpbitClient::new();
+        let upbit = Arc::new(UpbitClient::from_config(&upbit_config));
+        upbit.start_fx_refresh().await;
         let symbols = upbit.fetch_krw_coins().await?;
         println!("✓ {} KRW 페어 로드", symbols.len());
+        upbit.start_websocket(symbols.clone()).await?;
+        if upbit.wait_ready(Duration::from_secs(upbit_config.ready_timeout_secs)).await {
+            println!("✓ Upbit WebSocket 연결");
+        } else {
+            println!("⚠️ {}초 안에 업비트 시세 없음, 수신되는 대로 비교", upbit_config.ready_timeout_secs);
+        }
         
-        // Get threshold from args (default 1.0%)
-        let threshold = args.iter()
-            .position(|a| a == "--threshold" || a == "-t")
-            .and_then(|i| args.get(i + 1))
-            .and_then(|s| s.parse::<f64>().ok())
-            .unwrap_or(1.0) / 100.0;
-        
+        let threshold = threshold / 100.0;
         println!("✓ 갭 임계값: {:.1}%", threshold * 100.0);
+
+        let limits = GapMonitorLimits { max_symbols, max_pairs };
+        println!("✓ 심볼 최대 {}개, 심볼당 페어 최대 {}개 (0 = 제한 없음)",
+            limits.max_symbols, limits.max_pairs);
+        println!("✓ DexScreener 초당 최대 {}회 (0 = 제한 없음)", rps);
         
+        let mut storage = services::LocalStorage::new("./data");
+        storage.set_gaps_dir(&gap_out);
+        println!("✓ 갭 기록: {}", gap_out.display());
+
         // Run gap monitoring loop
-        run_gap_monitor(&upbit, &symbols, threshold).await;
+        let mut monitor = GapMonitor::new(symbols, threshold, limits);
+        monitor.set_storage(storage);
+        monitor.set_rate_limit(rps);
+        monitor.run(&upbit, Duration::from_secs(30)).await;
         return Ok(());
     }
 
@@ -215,42 +247,193 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n🚀 DEX Pool Monitor Starting...\n");
 
     // Load configuration
-    let config = Config::load()?;
+    let config = Config::load(&cli.config)?;
     tracing::info!("✓ Configuration loaded");
 
+    gatherer::init_sources(&config);
+
+    // Capture one raw response per source as parser fixtures
+    if matches!(command, Command::CaptureFixtures) {
+        let dir = "tests/fixtures";
+        sources::http::init_capture(dir)?;
+        let capture_collector = gatherer::build_collector(&config, Arc::new(PoolCache::new(config.cache.ttl_seconds)));
+        let report = services::selftest::run(capture_collector.sources(), Duration::from_secs(10)).await;
+
+        let captured = sources::http::captured_sources();
+        println!("\n📸 Fixture capture ({})", services::selftest::PROBE_SYMBOL);
+        for r in &report.results {
+            let path = sources::http::fixture_path(Path::new(dir), r.source);
+            if captured.iter().any(|s| s == r.source) {
+                println!("  ✅ {:14} -> {}", r.source, path.display());
+            } else {
+                println!("  ❌ {:14} no response captured ({:?})", r.source, r.status);
+            }
+        }
+        std::process::exit(if captured.is_empty() { 1 } else { 0 });
+    }
+
+    // Source connectivity self-test
+    let selftest_only = matches!(command, Command::Selftest);
+    if selftest_only || config.collector.startup_selftest {
+        let probe_collector = gatherer::build_collector(&config, Arc::new(PoolCache::new(config.cache.ttl_seconds)));
+        let report = services::selftest::run(probe_collector.sources(), Duration::from_secs(10)).await;
+        report.print();
+        if selftest_only {
+            std::process::exit(report.exit_code());
+        }
+        if report.exit_code() != 0 {
+            tracing::warn!("⚠️ All primary sources failed the self-test");
+        }
+    }
+
     // Initialize Upbit client
     println!("📡 Connecting to Upbit...");
-    let upbit = Arc::new(UpbitClient::new());
+    let upbit = Arc::new(UpbitClient::from_config(&config.upbit));
+    upbit.start_fx_refresh().await;
     let symbols = upbit.fetch_krw_coins().await?;
     tracing::info!("✓ Loaded {} KRW pairs", symbols.len());
 
     // Start Upbit WebSocket
     upbit.start_websocket(symbols.clone()).await?;
-    tokio::time::sleep(Duration::from_secs(2)).await;
-    tracing::info!("✓ Upbit WebSocket connected");
+    if upbit.wait_ready(Duration::from_secs(config.upbit.ready_timeout_secs)).await {
+        tracing::info!("✓ Upbit WebSocket connected");
+    } else {
+        tracing::warn!("⚠️ No Upbit ticker within {}s, continuing without CEX prices",
+            config.upbit.ready_timeout_secs);
+    }
+
+    // Optional Binance spot prices
+    let mut exchanges: Vec<Arc<dyn CexSource>> = vec![upbit.clone()];
+    if config.binance.enabled {
+        println!("📡 Connecting to Binance...");
+        let binance = Arc::new(BinanceClient::from_config(&config.binance));
+        match binance.fetch_symbols().await {
+            Ok(listed) => {
+                // Symbols Binance doesn't list are simply not subscribed
+                let tracked: Vec<String> = symbols.iter()
+                    .filter(|s| listed.binary_search(s).is_ok())
+                    .cloned()
+                    .collect();
+                tracing::info!("✓ Binance: {} USDT pairs, {} tracked", listed.len(), tracked.len());
+
+                if config.binance.use_websocket {
+                    if let Err(e) = binance.start_websocket(tracked).await {
+                        tracing::warn!("⚠️ Binance WebSocket failed, prices stay at the snapshot: {}", e);
+                    }
+                } else {
+                    let binance_clone = binance.clone();
+                    let refresh = Duration::from_secs(config.binance.refresh_secs.max(1));
+                    tokio::spawn(async move {
+                        let mut ticker = interval(refresh);
+                        ticker.tick().await;
+                        loop {
+                            ticker.tick().await;
+                            if let Err(e) = binance_clone.refresh().await {
+                                tracing::warn!("⚠️ Binance refresh failed: {}", e);
+                            }
+                        }
+                    });
+                }
+                exchanges.push(binance);
+            }
+            Err(e) => tracing::warn!("⚠️ Binance unavailable, continuing without it: {}", e),
+        }
+    }
+
+    // Symbol universe: Upbit KRW pairs and/or DEX-discovered tokens
+    let symbol_source = config.collector.symbol_source;
+    let symbols = if symbol_source == SymbolSource::Upbit {
+        symbols
+    } else {
+        let discovered = SymbolDiscovery::new(&config.collector).discover().await;
+        tracing::info!("✓ Discovered {} DEX symbols", discovered.len());
+        merge_symbols(&symbols, &discovered, symbol_source)
+    };
+    tracing::info!("✓ Monitoring {} symbols ({:?})", symbols.len(), symbol_source);
 
     // Initialize services
-    let cache = Arc::new(PoolCache::new(120));
-    let filter = PoolFilter::new(&config.filter);
-    let collector = Arc::new(PoolCollector::new(cache.clone(), filter));
-    let detector = Arc::new(ArbitrageDetector::new(config.arbitrage.threshold));
+    let mut dex = DexGatherer::new(&config);
+    dex.set_exchanges(exchanges.clone());
+    let cache = dex.cache().clone();
+    let collector = dex.collector().clone();
+    let detector = dex.detector().clone();
+    let ladder = dex.ladder().clone();
+    let alert_router = Arc::new(AlertRouter::new(ladder.clone(), &config.arbitrage, &config.notify));
+    let notifications = Arc::new(Notifications::from_config(&config.notify, &ladder));
+    if !notifications.is_empty() {
+        println!("✓ Notifications: {} (net ≥ {:.2}%)",
+            notifications.names().join(", "), config.notify.threshold * 100.0);
+    }
+    let gap_histogram = Arc::new(GapHistogram::new(config.metrics.gap_window_secs));
+    let live_history = Arc::new(LivePriceHistory::new(config.live_history.max_points, config.live_history.max_symbols));
 
     // Initialize storage
     let storage = if config.storage.enabled {
-        Some(Arc::new(services::LocalStorage::new(&config.storage.data_dir)))
+        Some(services::storage::open(&config.storage)?)
     } else {
         None
     };
 
+    // Cancelled on SIGINT/SIGTERM; background loops and /ws connections exit on it
+    let shutdown = CancellationToken::new();
+
     // Background: Pool collection with storage (1 minute cycle)
     println!("\n📥 Starting pool collection (1 min cycle)...\n");
     let collector_clone = collector.clone();
     let symbols_clone = symbols.clone();
     let storage_clone = storage.clone();
     let cache_clone2 = cache.clone();
-    tokio::spawn(async move {
+    let detector_clone = detector.clone();
+    let exchanges_clone = exchanges.clone();
+    let gap_histogram_clone = gap_histogram.clone();
+    let live_history_clone = live_history.clone();
+    let (save_alerts, alerts_format) = (config.storage.save_alerts, config.storage.alerts_format);
+    let collection_shutdown = shutdown.clone();
+    let last_collection = Arc::new(AtomicI64::new(0));
+    let last_collection_clone = last_collection.clone();
+    let last_cycle = Arc::new(parking_lot::RwLock::new(None));
+    let last_cycle_clone = last_cycle.clone();
+    let collection_task = tokio::spawn(async move {
         loop {
-            let result = collector_clone.collect_all(&symbols_clone).await;
+            let cycle_start = std::time::Instant::now();
+            let result = tokio::select! {
+                _ = collection_shutdown.cancelled() => break,
+                result = collector_clone.collect_all(&symbols_clone) => result,
+            };
+
+            let finished_at = chrono::Utc::now().timestamp();
+            last_collection_clone.store(finished_at, Ordering::Relaxed);
+            *last_cycle_clone.write() = Some(LastCycle {
+                result: result.clone(),
+                timestamp: finished_at,
+                duration_ms: cycle_start.elapsed().as_millis(),
+            });
+
+            // Detect and route alerts by ladder rung
+            let pools = cache_clone2.get_all();
+            detector_clone.record_prices(&pools);
+            gap_histogram_clone.observe_all(&detector_clone.symbol_gaps(&pools));
+            let fresh: Vec<Arc<models::PoolData>> = pools.iter()
+                .filter(|pool| !detector_clone.is_stale(pool, finished_at))
+                .cloned()
+                .collect();
+            live_history_clone.record(&services::aggregate::vwap(&fresh), finished_at);
+            let mut alerts = detector_clone.detect_dex_dex(&pools);
+            alerts.extend(detector_clone.detect_dex_cex(&pools, &exchanges_clone));
+            let alerts = alert_router.ladder().tag(alerts);
+            if let Some(storage) = storage_clone.as_ref().filter(|_| save_alerts) {
+                storage.save_alerts(&alerts, alerts_format);
+            }
+            let alerts = detector_clone.suppress_repeats(alerts);
+            // Slow or failing endpoints must not hold up the cycle
+            if !alerts.is_empty() {
+                let alert_router = alert_router.clone();
+                let notifications = notifications.clone();
+                tokio::spawn(async move {
+                    alert_router.dispatch(&alerts).await;
+                    notifications.send(&alerts).await;
+                });
+            }
             
             // Save to local storage
             if let Some(ref storage) = storage_clone {
@@ -258,8 +441,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                     .into_iter()
                     .map(|arc| (*arc).clone())
                     .collect();
-                storage.save_all_by_symbol(&pools);
-                storage.save_snapshot(&pools);
+                storage.save_cycle(&pools);
             }
             
             tracing::info!(
@@ -268,90 +450,494 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 result.successful,
                 result.successful + result.failed
             );
-            tokio::time::sleep(Duration::from_secs(60)).await; // 1 minute
+            tokio::select! {
+                _ = collection_shutdown.cancelled() => break,
+                _ = tokio::time::sleep(Duration::from_secs(60)) => {} // 1 minute
+            }
         }
     });
 
     // Background: Cache cleanup
     let cache_clone = cache.clone();
-    tokio::spawn(async move {
-        let mut ticker = interval(Duration::from_secs(60));
+    let cleanup_interval = config.cache.cleanup_interval.max(1);
+    let cleanup_shutdown = shutdown.clone();
+    let cleanup_task = tokio::spawn(async move {
+        let mut ticker = interval(Duration::from_secs(cleanup_interval));
         loop {
-            ticker.tick().await;
-            cache_clone.cleanup_if_needed();
+            tokio::select! {
+                _ = cleanup_shutdown.cancelled() => break,
+                _ = ticker.tick() => cache_clone.cleanup_if_needed(),
+            }
         }
     });
 
+    let gap_monitor = Arc::new(build_gap_monitor(&config, symbols.clone()));
+
     // Application state
     let state = Arc::new(AppState {
         collector,
         detector,
-        cache,
+        cache: cache.clone(),
         upbit,
+        exchanges,
         symbols,
+        costs: services::simulator::CostModel::new(&config.costs),
+        ladder,
+        gap_histogram,
+        live_history,
+        alert_cooldown_secs: config.arbitrage.cooldown_secs,
+        ws_clients: AtomicUsize::new(0),
+        ws_updates: UpdateBroadcaster::new(4),
+        ws_update_interval: Duration::from_secs(config.arbitrage.update_interval),
+        ws_heartbeat: Duration::from_secs(config.arbitrage.ws_heartbeat_secs),
+        shutdown: shutdown.clone(),
+        storage: storage.clone(),
+        last_collection,
+        started_at: chrono::Utc::now().timestamp(),
+        upbit_stale_secs: config.upbit.stale_secs,
+        last_cycle,
+        gap_monitor,
     });
 
-    // Router
-    let app = Router::new()
+    let reload_task = spawn_config_reload(cli.config.clone(), state.clone(), CONFIG_RELOAD_INTERVAL);
+
+    let ws_task = spawn_ws_updates(state.clone());
+
+    let addr = format!("{}:{}", config.server.host, config.server.port);
+
+    println!("\n✓ Server ready on http://{}\n", addr);
+
+    let listener = tokio::net::TcpListener::bind(&addr).await?;
+    let tasks = vec![collection_task, cleanup_task, ws_task, reload_task];
+    serve_until_shutdown(listener, state, shutdown_signal(), tasks, config.cache.persist_path.as_deref()).await?;
+
+    Ok(())
+}
+
+/// `/gaps` monitor over the Upbit symbols, capped by `[gap]`
+fn build_gap_monitor(config: &Config, symbols: Vec<String>) -> GapMonitor {
+    let limits = GapMonitorLimits { max_symbols: config.gap.max_symbols, max_pairs: config.gap.max_pairs };
+    let mut monitor = GapMonitor::new(symbols, 0.0, limits);
+    monitor.set_rate_limit(config.gap.rps);
+    monitor
+}
+
+/// Background: one /ws update per `update_interval` from the cache, shared by all clients
+fn spawn_ws_updates(state: Arc<AppState>) -> tokio::task::JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = interval(state.ws_update_interval);
+        loop {
+            tokio::select! {
+                _ = state.shutdown.cancelled() => break,
+                _ = ticker.tick() => {
+                    let pools = state.cache.get_all();
+                    // Arbitrage alerts (rungs routed to websocket only)
+                    let alerts = state.ladder.tag(state.detector.detect_dex_dex(&pools))
+                        .into_iter()
+                        .filter(|a| state.ladder.routes_to(a, AlertDestination::Websocket))
+                        .collect();
+                    state.ws_updates.publish(pools, alerts);
+                }
+            }
+        }
+    })
+}
+
+/// Serve the API until `signal` resolves, then let the background `tasks` finish their
+/// current step (they exit on `state.shutdown`), write a final snapshot and persist the cache
+async fn serve_until_shutdown(
+    listener: tokio::net::TcpListener,
+    state: Arc<AppState>,
+    signal: impl std::future::Future<Output = ()> + Send + 'static,
+    tasks: Vec<tokio::task::JoinHandle<()>>,
+    persist_path: Option<&str>,
+) -> std::io::Result<()> {
+    let shutdown = state.shutdown.clone();
+    let serve_shutdown = shutdown.clone();
+    axum::serve(listener, router(state.clone()))
+        .with_graceful_shutdown(async move {
+            signal.await;
+            serve_shutdown.cancel();
+        })
+        .await?;
+
+    // Let the background loops finish their current step
+    shutdown.cancel();
+    let _ = tokio::time::timeout(Duration::from_secs(10), futures::future::join_all(tasks)).await;
+
+    // Final snapshot so nothing collected since the last cycle is lost
+    if let Some(ref storage) = state.storage {
+        let pools: Vec<models::PoolData> = state.cache.get_all()
+            .into_iter()
+            .map(|arc| (*arc).clone())
+            .collect();
+        storage.save_snapshot(&pools);
+        tracing::info!("✓ Final snapshot saved ({} pools)", pools.len());
+    }
+
+    // Persist cache for the next start
+    if let Some(path) = persist_path {
+        match state.cache.dump_to(Path::new(path)) {
+            Ok(count) => tracing::info!("✓ Saved {} cached pools to {}", count, path),
+            Err(e) => tracing::warn!("⚠️ Failed to save cache to {}: {}", path, e),
+        }
+    }
+    Ok(())
+}
+
+/// REST + /ws routes, with the dashboard served from `frontend/`
+fn router(state: Arc<AppState>) -> Router {
+    Router::new()
         .route("/pools/cached", get(get_cached_pools))
+        .route("/pools/cached/count", get(get_cached_count))
+        .route("/pools/:symbol", get(get_symbol_pools))
+        .route("/prices/vwap", get(get_vwap_prices))
+        .route("/premium", get(get_premium))
+        .route("/history/:symbol", get(get_history))
+        .route("/history/live/:symbol", get(get_live_history))
         .route("/arbitrage", get(get_arbitrage))
         .route("/gaps", get(get_gaps))
+        .route("/freshness", get(get_freshness))
+        .route("/simulate", post(simulate_trade))
         .route("/health", get(health))
         .route("/stats", get(get_stats))
+        .route("/stats/sources", get(get_source_stats))
+        .route("/stats/last-cycle", get(get_last_cycle))
+        .route("/sources/debug/:symbol", get(debug_sources))
+        .route("/metrics", get(get_metrics))
         .route("/ws", get(ws_handler))
         .nest_service("/", tower_http::services::ServeDir::new("frontend"))
         .layer(CorsLayer::permissive())
-        .with_state(state);
+        .with_state(state)
+}
 
-    let addr = format!("{}:{}", config.server.host, config.server.port);
-    println!("\n✓ Server ready on http://{}\n", addr);
+/// Resolves on Ctrl+C or SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c().await.ok();
+    };
 
-    let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sig) => {
+                sig.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
-    Ok(())
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    println!("
+🛑 Shutting down...");
 }
 
 // REST Handlers
+#[derive(serde::Deserialize)]
+struct CachedPoolsQuery {
+    /// Drop pools older than the detector's max age
+    #[serde(default)]
+    exclude_stale: bool,
+    /// Case-insensitive exact matches
+    #[serde(default)]
+    chain: Option<String>,
+    #[serde(default)]
+    symbol: Option<String>,
+    #[serde(default)]
+    dex: Option<String>,
+    #[serde(default)]
+    min_lp: Option<f64>,
+    #[serde(default)]
+    limit: Option<usize>,
+    #[serde(default)]
+    offset: usize,
+}
+
+impl CachedPoolsQuery {
+    fn matches(&self, pool: &models::PoolData) -> bool {
+        let eq = |filter: &Option<String>, value: &str| {
+            filter.as_deref().is_none_or(|f| f.eq_ignore_ascii_case(value))
+        };
+        eq(&self.chain, &pool.chain)
+            && eq(&self.symbol, &pool.symbol)
+            && eq(&self.dex, &pool.dex)
+            && self.min_lp.is_none_or(|min| pool.lp_reserve_usd >= min)
+    }
+}
+
+#[derive(serde::Serialize)]
+struct CachedPoolsPage {
+    /// Matching pools before pagination
+    total: usize,
+    offset: usize,
+    limit: Option<usize>,
+    pools: Vec<CachedPool>,
+}
+
+#[derive(serde::Serialize)]
+struct CachedPool {
+    #[serde(flatten)]
+    pool: models::WithIsoTime<models::PoolData>,
+    stale: bool,
+}
+
 async fn get_cached_pools(
-    State(state): State<Arc<AppState>>
-) -> axum::Json<Vec<models::PoolData>> {
-    // Arc를 벗겨서 PoolData 직접 반환
-    let pools: Vec<models::PoolData> = state.cache.get_all()
+    State(state): State<Arc<AppState>>,
+    query: Result<Query<CachedPoolsQuery>, QueryRejection>,
+) -> Result<axum::Json<CachedPoolsPage>, ApiError> {
+    let Query(query) = query?;
+    let now = chrono::Utc::now().timestamp();
+    let mut matching: Vec<Arc<models::PoolData>> = state.cache.get_all()
+        .into_iter()
+        .filter(|pool| query.matches(pool))
+        .filter(|pool| !(query.exclude_stale && state.detector.is_stale(pool, now)))
+        .collect();
+    // Stable order so offsets page consistently: deepest LP first
+    matching.sort_by(|a, b| models::cmp_desc_nan_last(a.lp_reserve_usd, b.lp_reserve_usd)
+        .then_with(|| a.dedup_key().cmp(&b.dedup_key())));
+
+    let total = matching.len();
+    // Arc를 벗겨서 PoolData 직접 반환 (+ stale 표시), 필요한 페이지만 직렬화
+    let pools: Vec<CachedPool> = matching.into_iter()
+        .skip(query.offset)
+        .take(query.limit.unwrap_or(usize::MAX))
+        .map(|arc_pool| CachedPool {
+            stale: state.detector.is_stale(&arc_pool, now),
+            pool: (*arc_pool).clone().into(),
+        })
+        .collect();
+
+    Ok(axum::Json(CachedPoolsPage {
+        total,
+        offset: query.offset,
+        limit: query.limit,
+        pools,
+    }))
+}
+
+/// Cached pools of one symbol (case-insensitive), deepest LP first; empty when unknown
+async fn get_symbol_pools(
+    State(state): State<Arc<AppState>>,
+    UrlPath(symbol): UrlPath<String>,
+) -> axum::Json<Vec<models::WithIsoTime<models::PoolData>>> {
+    let mut pools: Vec<models::PoolData> = state.cache.get_all()
         .into_iter()
+        .filter(|pool| pool.symbol.eq_ignore_ascii_case(&symbol))
         .map(|arc_pool| (*arc_pool).clone())
         .collect();
-    axum::Json(pools)
+    pools.sort_by(|a, b| models::cmp_desc_nan_last(a.lp_reserve_usd, b.lp_reserve_usd));
+    axum::Json(models::with_iso(pools))
 }
 
-async fn get_arbitrage(
+/// Cached pools that are not stale
+fn fresh_pools(state: &AppState) -> Vec<Arc<models::PoolData>> {
+    let now = chrono::Utc::now().timestamp();
+    state.cache.get_all()
+        .into_iter()
+        .filter(|pool| !state.detector.is_stale(pool, now))
+        .collect()
+}
+
+/// `{symbol: price}` volume-weighted over the fresh cached pools
+async fn get_vwap_prices(
+    State(state): State<Arc<AppState>>,
+) -> axum::Json<std::collections::HashMap<String, f64>> {
+    axum::Json(services::aggregate::vwap(&fresh_pools(&state)))
+}
+
+/// Upbit USD price vs DEX VWAP per symbol, highest premium first; 503 until
+/// the Upbit feed has delivered prices
+async fn get_premium(
+    State(state): State<Arc<AppState>>,
+) -> Result<axum::Json<Vec<services::aggregate::Premium>>, ApiError> {
+    let upbit_prices: std::collections::HashMap<String, f64> = state.upbit.get_all_prices()
+        .into_iter()
+        .map(|p| (p.symbol, p.price_usd))
+        .collect();
+    if upbit_prices.is_empty() {
+        return Err(ApiError::Unavailable("no Upbit prices received yet".to_string()));
+    }
+    Ok(axum::Json(services::aggregate::premiums(&upbit_prices, &services::aggregate::vwap(&fresh_pools(&state)))))
+}
+
+/// Longest span `/history` reads in one request
+const MAX_HISTORY_DAYS: i64 = 366;
+
+#[derive(serde::Deserialize)]
+struct HistoryQuery {
+    /// YYYY-MM-DD, inclusive; both default to today (UTC)
+    #[serde(default)]
+    from: Option<String>,
+    #[serde(default)]
+    to: Option<String>,
+}
+
+/// Stored pools of a symbol over a date range, sorted by timestamp
+async fn get_history(
+    State(state): State<Arc<AppState>>,
+    UrlPath(symbol): UrlPath<String>,
+    query: Result<Query<HistoryQuery>, QueryRejection>,
+) -> Result<axum::Json<Vec<models::WithIsoTime<models::PoolData>>>, ApiError> {
+    let Query(query) = query?;
+    let bad_request = ApiError::BadRequest;
+    // The symbol names a file on disk: only plain tickers get that far
+    let symbol = symbol.to_uppercase();
+    if !services::storage::is_valid_symbol(&symbol) {
+        return Err(bad_request(format!("invalid symbol '{symbol}' (expected 1-20 letters or digits)")));
+    }
+    let today = chrono::Utc::now().date_naive();
+    let parse = |value: &Option<String>| match value {
+        Some(s) => chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+            .map_err(|_| bad_request(format!("invalid date '{s}' (expected YYYY-MM-DD)"))),
+        None => Ok(today),
+    };
+    let (from, to) = (parse(&query.from)?, parse(&query.to)?);
+    if from > to {
+        return Err(bad_request("'from' is after 'to'".to_string()));
+    }
+    if (to - from).num_days() >= MAX_HISTORY_DAYS {
+        return Err(bad_request(format!("range is limited to {MAX_HISTORY_DAYS} days")));
+    }
+
+    let Some(storage) = state.storage.as_ref() else {
+        return Ok(axum::Json(Vec::new()));
+    };
+    let storage = storage.clone();
+    // Files are read on the blocking pool so large ranges don't stall the runtime
+    let pools = tokio::task::spawn_blocking(move || storage.load_symbol_range(&symbol, from, to)).await?;
+    Ok(axum::Json(models::with_iso(pools)))
+}
+
+/// In-memory VWAP series of a symbol, oldest first; 404 when not tracked
+async fn get_live_history(
+    State(state): State<Arc<AppState>>,
+    UrlPath(symbol): UrlPath<String>,
+) -> Result<axum::Json<Vec<models::WithIsoTime<services::live_history::PricePoint>>>, ApiError> {
+    let points = state.live_history.get(&symbol);
+    if points.is_empty() {
+        return Err(ApiError::NotFound(format!("no live history for {}", symbol.to_uppercase())));
+    }
+    Ok(axum::Json(models::with_iso(points)))
+}
+
+/// Cache size without serializing the pools (cheap to poll)
+async fn get_cached_count(
     State(state): State<Arc<AppState>>
-) -> axum::Json<Vec<models::ArbitrageAlert>> {
+) -> axum::Json<serde_json::Value> {
+    axum::Json(serde_json::json!({
+        "count": state.cache.len(),
+        "generation": state.cache.generation(),
+    }))
+}
+
+#[derive(serde::Deserialize)]
+struct ArbitrageQuery {
+    /// Max alerts returned (unlimited when absent)
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+async fn get_arbitrage(
+    State(state): State<Arc<AppState>>,
+    query: Result<Query<ArbitrageQuery>, QueryRejection>,
+) -> Result<axum::Json<Vec<models::WithIsoTime<models::ArbitrageAlert>>>, ApiError> {
+    let Query(query) = query?;
     let pools = state.cache.get_all();
-    let cex_prices = state.upbit.get_all_prices();
     
     let mut alerts = state.detector.detect_dex_dex(&pools);
-    alerts.extend(state.detector.detect_dex_cex(&pools, &cex_prices));
+    alerts.extend(state.detector.detect_dex_cex(&pools, &state.exchanges));
+
+    // Best opportunities first
+    alerts.sort_by(|a, b| models::cmp_desc_nan_last(a.diff_pct, b.diff_pct));
+    if let Some(limit) = query.limit {
+        alerts.truncate(limit);
+    }
     
-    axum::Json(alerts)
+    Ok(axum::Json(models::with_iso(state.ladder.tag(alerts))))
 }
 
 async fn get_stats(
     State(state): State<Arc<AppState>>
 ) -> axum::Json<serde_json::Value> {
     let stats = state.collector.get_stats();
+    let cache_stats = state.cache.stats();
     
     axum::Json(serde_json::json!({
-        "cache_pools": state.cache.len(),
+        "cache_pools": cache_stats.entries,
+        "cache_hits": cache_stats.hits,
+        "cache_misses": cache_stats.misses,
+        "cache_generation": state.cache.generation(),
         "symbols": state.symbols.len(),
+        "active_sources": state.collector.active_sources(),
         "total_requests": stats.total_requests.load(Ordering::Relaxed),
         "successful": stats.successful.load(Ordering::Relaxed),
         "failed": stats.failed.load(Ordering::Relaxed),
         "pools_collected": stats.pools_collected.load(Ordering::Relaxed),
+        "outliers_dropped": state.detector.outliers_dropped(),
         "upbit_prices": state.upbit.get_all_prices().len(),
+        "krw_usd_rate": state.upbit.rate(),
+        "krw_usd_rate_from_market": state.upbit.rate_from_market(),
     }))
 }
 
+/// Latest collection cycle result; null before the first cycle finishes
+async fn get_last_cycle(
+    State(state): State<Arc<AppState>>
+) -> axum::Json<Option<models::WithIsoTime<LastCycle>>> {
+    axum::Json(state.last_cycle.read().clone().map(Into::into))
+}
+
+async fn get_source_stats(
+    State(state): State<Arc<AppState>>
+) -> axum::Json<Vec<services::collector::SourceStatSnapshot>> {
+    axum::Json(state.collector.get_stats().source_snapshots())
+}
+
+/// Live `fetch_pools` of every active source for one symbol (bypasses cache and
+/// filter); 503 when every source failed
+async fn debug_sources(
+    State(state): State<Arc<AppState>>,
+    UrlPath(symbol): UrlPath<String>,
+) -> Result<axum::Json<Vec<services::selftest::SourceDebug>>, ApiError> {
+    let results = services::selftest::debug_symbol(state.collector.sources(), &symbol, DEBUG_TIMEOUT, 3).await;
+    if results.is_empty() {
+        return Err(ApiError::Unavailable("no active sources".to_string()));
+    }
+    if results.iter().all(|r| r.error.is_some()) {
+        let errors: Vec<String> = results.iter()
+            .filter_map(|r| r.error.as_ref().map(|e| format!("{}: {}", r.source, e)))
+            .collect();
+        return Err(ApiError::Unavailable(format!("every source failed ({})", errors.join("; "))));
+    }
+    Ok(axum::Json(results))
+}
+
+async fn get_freshness(
+    State(state): State<Arc<AppState>>
+) -> axum::Json<services::freshness::FreshnessMatrix> {
+    let pools = state.cache.get_all();
+    let updates = state.collector.source_last_updates();
+    axum::Json(services::freshness::freshness_matrix(
+        &pools,
+        &updates,
+        chrono::Utc::now().timestamp(),
+    ))
+}
+
+async fn simulate_trade(
+    State(state): State<Arc<AppState>>,
+    req: Result<axum::Json<services::simulator::SimulationRequest>, JsonRejection>,
+) -> Result<axum::Json<services::simulator::SimulationResult>, ApiError> {
+    let axum::Json(req) = req?;
+    let pools = state.cache.get_all();
+    Ok(axum::Json(services::simulator::simulate(&state.costs, &pools, &req)))
+}
+
 /// Gap data response
 #[derive(serde::Serialize)]
 struct GapResponse {
@@ -368,70 +954,133 @@ struct GapResponse {
 
 async fn get_gaps(
     State(state): State<Arc<AppState>>
-) -> axum::Json<Vec<GapResponse>> {
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_secs(5))
-        .build()
-        .unwrap();
-    
-    // Get Upbit prices
+) -> Result<axum::Json<Vec<GapResponse>>, ApiError> {
     let upbit_prices: std::collections::HashMap<String, f64> = state.upbit.get_all_prices()
         .into_iter()
-        .map(|p| (p.symbol.clone(), p.price_usd))
+        .map(|p| (p.symbol, p.price_usd))
         .collect();
-    
-    let mut gaps = Vec::new();
-    
-    // Fetch top symbols for speed (limit to 50)
-    for symbol in state.symbols.iter().take(50) {
-        if let Some(upbit_price) = upbit_prices.get(symbol) {
-            if *upbit_price <= 0.0 { continue; }
-            
-            let url = format!("https://api.dexscreener.com/latest/dex/search?q={}", symbol);
-            
-            if let Ok(resp) = client.get(&url).send().await {
-                if resp.status().is_success() {
-                    if let Ok(data) = resp.json::<serde_json::Value>().await {
-                        if let Some(pairs) = data["pairs"].as_array() {
-                            for pair in pairs.iter().take(3) {
-                                let base_symbol = pair["baseToken"]["symbol"].as_str().unwrap_or("");
-                                let price_str = pair["priceUsd"].as_str().unwrap_or("0");
-                                let dex = pair["dexId"].as_str().unwrap_or("unknown").to_string();
-                                let chain = pair["chainId"].as_str().unwrap_or("unknown").to_string();
-                                
-                                if base_symbol.to_uppercase() == symbol.to_uppercase() {
-                                    if let Ok(dex_price) = price_str.parse::<f64>() {
-                                        if dex_price > 0.0 && dex_price < 1_000_000_000.0 {
-                                            let gap_percent = (*upbit_price - dex_price) / dex_price * 100.0;
-                                            
-                                            gaps.push(GapResponse {
-                                                symbol: symbol.clone(),
-                                                upbit_price: *upbit_price,
-                                                dex_price,
-                                                gap_percent,
-                                                dex,
-                                                chain,
-                                            });
-                                            break; // One per symbol
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+    if upbit_prices.is_empty() {
+        return Err(ApiError::Unavailable("no Upbit prices yet".to_string()));
+    }
+
+    let gaps = state.gap_monitor.check_prices(&upbit_prices).await
+        .map_err(|e| ApiError::Unavailable(format!("DexScreener search failed: {}", e)))?;
+    Ok(axum::Json(gaps.into_iter().map(|gap| {
+        let (dex, chain) = gap.source.split_once(':').unwrap_or((gap.source.as_str(), "unknown"));
+        GapResponse {
+            dex: dex.to_string(),
+            chain: chain.to_string(),
+            symbol: gap.symbol,
+            upbit_price: gap.upbit,
+            dex_price: gap.dex,
+            gap_percent: gap.gap_pct,
+        }
+    }).collect()))
+}
+
+async fn get_metrics(
+    State(state): State<Arc<AppState>>
+) -> impl IntoResponse {
+    let mut body = String::new();
+    let stats = state.collector.get_stats();
+
+    let counters = [
+        ("dex_collector_requests_total", "Source requests (symbol x source)", &stats.total_requests),
+        ("dex_collector_requests_successful_total", "Successful source requests", &stats.successful),
+        ("dex_collector_requests_failed_total", "Failed source requests", &stats.failed),
+        ("dex_collector_pools_collected_total", "Pools accepted into the cache", &stats.pools_collected),
+    ];
+    for (name, help, value) in counters {
+        body.push_str(&format!("# HELP {name} {help}\n# TYPE {name} counter\n{name} {}\n",
+            value.load(Ordering::Relaxed)));
+    }
+
+    body.push_str("# HELP dex_source_requests_total Source requests by outcome\n");
+    body.push_str("# TYPE dex_source_requests_total counter\n");
+    for s in stats.source_snapshots() {
+        body.push_str(&format!("dex_source_requests_total{{source=\"{}\",outcome=\"success\"}} {}\n", s.source, s.successful));
+        body.push_str(&format!("dex_source_requests_total{{source=\"{}\",outcome=\"failure\"}} {}\n", s.source, s.failed));
+    }
+
+    let gauges = [
+        ("dex_cache_pools", "Pools currently cached", state.cache.len()),
+        ("dex_ws_clients", "Connected WebSocket clients", state.ws_clients.load(Ordering::Relaxed)),
+    ];
+    for (name, help, value) in gauges {
+        body.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n"));
+    }
+
+    state.gap_histogram.render(&mut body, "dex_arbitrage_gap_percent");
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        body,
+    )
+}
+
+/// Collection counts as stalled once the last cycle (or startup) is this old;
+/// cycles run every 60s
+const COLLECTION_STALE_SECS: i64 = 300;
+
+#[derive(serde::Serialize)]
+struct HealthReport {
+    /// "ok" | "degraded"
+    status: &'static str,
+    upbit_connected: bool,
+    upbit_last_message_age_secs: Option<u64>,
+    /// None before the first cycle
+    last_collection_secs_ago: Option<i64>,
+    cache_pools: usize,
+    /// Subsystems that are down; "collection" makes the response 503
+    problems: Vec<&'static str>,
+}
+
+impl HealthReport {
+    fn evaluate(state: &AppState, now: i64) -> Self {
+        let upbit_age = state.upbit.last_message_age().map(|age| age.as_secs());
+        let upbit_connected = state.upbit.is_connected()
+            && upbit_age.is_some_and(|age| age <= state.upbit_stale_secs);
+        let last = state.last_collection.load(Ordering::Relaxed);
+        let last_collection_secs_ago = (last > 0).then(|| now - last);
+        let cache_pools = state.cache.len();
+
+        // Before the first cycle, startup counts as the last run
+        let collection_age = last_collection_secs_ago.unwrap_or(now - state.started_at);
+        let mut problems = Vec::new();
+        if collection_age > COLLECTION_STALE_SECS {
+            problems.push("collection");
+        }
+        if !upbit_connected {
+            problems.push("upbit");
+        }
+        if cache_pools == 0 && last_collection_secs_ago.is_some() {
+            problems.push("cache");
+        }
+
+        Self {
+            status: if problems.is_empty() { "ok" } else { "degraded" },
+            upbit_connected,
+            upbit_last_message_age_secs: upbit_age,
+            last_collection_secs_ago,
+            cache_pools,
+            problems,
+        }
+    }
+
+    fn http_status(&self) -> axum::http::StatusCode {
+        if self.problems.contains(&"collection") {
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            axum::http::StatusCode::OK
         }
     }
-    
-    // Sort by absolute gap (descending)
-    gaps.sort_by(|a, b| b.gap_percent.abs().partial_cmp(&a.gap_percent.abs()).unwrap());
-    
-    axum::Json(gaps)
 }
 
-async fn health() -> &'static str {
-    "OK"
+/// Subsystem liveness; 503 when collection has stalled
+async fn health(
+    State(state): State<Arc<AppState>>
+) -> (axum::http::StatusCode, axum::Json<HealthReport>) {
+    let report = HealthReport::evaluate(&state, chrono::Utc::now().timestamp());
+    (report.http_status(), axum::Json(report))
 }
 
 // WebSocket Handler
@@ -442,47 +1091,177 @@ async fn ws_handler(
     ws.on_upgrade(|socket| handle_socket(socket, state))
 }
 
+/// Counts a /ws connection for its lifetime
+struct WsClientGuard<'a>(&'a AtomicUsize);
+
+impl<'a> WsClientGuard<'a> {
+    fn new(clients: &'a AtomicUsize) -> Self {
+        clients.fetch_add(1, Ordering::Relaxed);
+        Self(clients)
+    }
+}
+
+impl Drop for WsClientGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Client -> server /ws messages
+#[derive(serde::Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum WsCommand {
+    Subscribe { symbols: Vec<String> },
+    /// Without `symbols` the whole subscription is dropped
+    Unsubscribe { #[serde(default)] symbols: Option<Vec<String>> },
+}
+
+/// Per-connection symbol filter; empty = everything
+#[derive(Default)]
+struct WsSubscription {
+    symbols: HashSet<String>,
+}
+
+impl WsSubscription {
+    /// Apply a client message; false if it wasn't a known command
+    fn handle(&mut self, text: &str) -> bool {
+        match serde_json::from_str::<WsCommand>(text) {
+            Ok(WsCommand::Subscribe { symbols }) => {
+                self.symbols.extend(symbols.iter().map(|s| s.trim().to_uppercase()));
+                true
+            }
+            Ok(WsCommand::Unsubscribe { symbols: Some(symbols) }) => {
+                for symbol in symbols {
+                    self.symbols.remove(&symbol.trim().to_uppercase());
+                }
+                true
+            }
+            Ok(WsCommand::Unsubscribe { symbols: None }) => {
+                self.symbols.clear();
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn matches(&self, symbol: &str) -> bool {
+        self.symbols.is_empty() || self.symbols.contains(&symbol.to_uppercase())
+    }
+}
+
+/// Pool messages go out in chunks of this many pools
+const WS_CHUNK: usize = 50;
+
+async fn send_text(sender: &mut SplitSink<WebSocket, Message>, msg: serde_json::Value) -> bool {
+    matches!(
+        tokio::time::timeout(Duration::from_secs(5), sender.send(Message::Text(msg.to_string()))).await,
+        Ok(Ok(_))
+    )
+}
+
+/// Send one broadcast update through this connection's filter: the full pool
+/// set when `full` (connect / resync / new subscription), otherwise only the
+/// delta. False once the client is gone.
+async fn send_update(
+    sender: &mut SplitSink<WebSocket, Message>,
+    update: &WsUpdate,
+    subscription: &WsSubscription,
+    cooldown: &AlertCooldown,
+    full: bool,
+) -> bool {
+    let source = if full { &update.pools } else { &update.updated };
+    let pools = models::with_iso(source.iter()
+        .map(|arc| arc.as_ref())
+        .filter(|p| subscription.matches(&p.symbol)));
+
+    if full {
+        for chunk in pools.chunks(WS_CHUNK) {
+            let msg = serde_json::json!({
+                "type": "pool_update",
+                "data": chunk,
+            });
+            if !send_text(sender, msg).await {
+                return false;
+            }
+        }
+    } else {
+        let removed: Vec<&str> = update.removed.iter()
+            .filter(|r| subscription.matches(&r.symbol))
+            .map(|r| r.key.as_str())
+            .collect();
+        // Removals ride on the first chunk
+        let mut chunks: Vec<&[models::WithIsoTime<&models::PoolData>]> = pools.chunks(WS_CHUNK).collect();
+        if chunks.is_empty() && !removed.is_empty() {
+            chunks.push(&[]);
+        }
+        for (i, chunk) in chunks.into_iter().enumerate() {
+            let msg = serde_json::json!({
+                "type": "pool_delta",
+                "updated": chunk,
+                "removed": if i == 0 { removed.as_slice() } else { &[] },
+            });
+            if !send_text(sender, msg).await {
+                return false;
+            }
+        }
+    }
+
+    let alerts: Vec<_> = update.alerts.iter()
+        .filter(|a| subscription.matches(&a.symbol))
+        .cloned()
+        .collect();
+    let alerts = cooldown.filter(alerts, chrono::Utc::now().timestamp());
+    
+    if !alerts.is_empty() {
+        let msg = serde_json::json!({
+            "type": "arb_alert",
+            "data": models::with_iso(&alerts),
+        });
+        let _ = sender.send(Message::Text(msg.to_string())).await;
+    }
+    true
+}
+
 async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
+    let _client = WsClientGuard::new(&state.ws_clients);
     let (mut sender, mut receiver) = socket.split();
-    let mut update_ticker = interval(Duration::from_secs(30));
-    let mut heartbeat_ticker = interval(Duration::from_secs(10));
+    let mut heartbeat_ticker = interval(state.ws_heartbeat);
+    // Per-connection so one client's alerts never suppress another's
+    let cooldown = AlertCooldown::new(state.alert_cooldown_secs);
+    let mut subscription = WsSubscription::default();
+
+    let (mut current, mut updates) = state.ws_updates.subscribe();
+    if let Some(update) = current.as_deref() {
+        if !send_update(&mut sender, update, &subscription, &cooldown, true).await {
+            return;
+        }
+    }
 
     loop {
         tokio::select! {
-            _ = update_ticker.tick() => {
-                let pools = state.cache.get_all();
-                
-                // Send in chunks - convert Arc to references for serialization
-                for chunk in pools.chunks(50) {
-                    let chunk_data: Vec<&models::PoolData> = chunk.iter()
-                        .map(|arc| arc.as_ref())
-                        .collect();
-                    
-                    let msg = serde_json::json!({
-                        "type": "pool_update",
-                        "data": chunk_data,
-                    });
-                    
-                    match tokio::time::timeout(
-                        Duration::from_secs(5),
-                        sender.send(Message::Text(msg.to_string()))
-                    ).await {
-                        Ok(Ok(_)) => {},
-                        _ => return,
+            update = updates.recv() => {
+                let (update, full) = match update {
+                    Ok(update) => (update, false),
+                    // Too slow to keep up: tell the client, drop the backlog and
+                    // resync with a full snapshot
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!("WebSocket client lagged, skipped {} updates", skipped);
+                        updates = updates.resubscribe();
+                        let resync = serde_json::json!({ "type": "resync", "skipped": skipped });
+                        if !send_text(&mut sender, resync).await {
+                            return;
+                        }
+                        match state.ws_updates.latest() {
+                            Some(update) => (update, true),
+                            None => continue,
+                        }
                     }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                };
+                if !send_update(&mut sender, &update, &subscription, &cooldown, full).await {
+                    return;
                 }
-
-                // Arbitrage alerts
-                let cex_prices = state.upbit.get_all_prices();
-                let alerts = state.detector.detect_dex_dex(&pools);
-                
-                if !alerts.is_empty() {
-                    let msg = serde_json::json!({
-                        "type": "arb_alert",
-                        "data": alerts,
-                    });
-                    let _ = sender.send(Message::Text(msg.to_string())).await;
-                }
+                current = Some(update);
             }
 
             _ = heartbeat_ticker.tick() => {
@@ -491,10 +1270,29 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
                 }
             }
 
+            _ = state.shutdown.cancelled() => {
+                let _ = sender.send(Message::Close(None)).await;
+                return;
+            }
+
             msg = receiver.next() => {
                 match msg {
                     Some(Ok(Message::Close(_))) | None => return,
                     Some(Ok(Message::Pong(_))) => {},
+                    Some(Ok(Message::Text(text))) if subscription.handle(&text) => {
+                        let mut symbols: Vec<&String> = subscription.symbols.iter().collect();
+                        symbols.sort();
+                        let ack = serde_json::json!({ "type": "subscribed", "symbols": symbols });
+                        if sender.send(Message::Text(ack.to_string())).await.is_err() {
+                            return;
+                        }
+                        // Re-send the current view filtered by the new subscription
+                        if let Some(update) = current.as_deref() {
+                            if !send_update(&mut sender, update, &subscription, &cooldown, true).await {
+                                return;
+                            }
+                        }
+                    }
                     _ => {}
                 }
             }
@@ -502,3 +1300,658 @@ async fn handle_socket(socket: WebSocket, state: Arc<AppState>) {
     }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    const TEST_CONFIG: &str = r#"
+        [arbitrage]
+        threshold = 0.01
+        update_interval = 1
+        [filter]
+        min_lp = 0
+        min_volume = 0
+        [server]
+        host = "127.0.0.1"
+        port = 8080
+    "#;
+
+    fn test_config() -> Config {
+        toml::from_str(TEST_CONFIG).expect("test config parses")
+    }
+
+    /// State over no sources, no storage and a disconnected Upbit client
+    fn test_state(config: &Config, ws_capacity: usize) -> AppState {
+        let dex = DexGatherer::with_sources(config, Vec::new());
+        AppState {
+            collector: dex.collector().clone(),
+            detector: dex.detector().clone(),
+            cache: dex.cache().clone(),
+            upbit: Arc::new(UpbitClient::from_config(&config.upbit)),
+            exchanges: Vec::new(),
+            symbols: Vec::new(),
+            costs: services::simulator::CostModel::new(&config.costs),
+            ladder: dex.ladder().clone(),
+            gap_histogram: Arc::new(GapHistogram::new(config.metrics.gap_window_secs)),
+            live_history: Arc::new(LivePriceHistory::new(config.live_history.max_points, config.live_history.max_symbols)),
+            alert_cooldown_secs: config.arbitrage.cooldown_secs,
+            ws_clients: AtomicUsize::new(0),
+            ws_updates: UpdateBroadcaster::new(ws_capacity),
+            ws_update_interval: Duration::from_secs(config.arbitrage.update_interval),
+            ws_heartbeat: Duration::from_secs(config.arbitrage.ws_heartbeat_secs),
+            shutdown: CancellationToken::new(),
+            storage: None,
+            last_collection: Arc::new(AtomicI64::new(0)),
+            started_at: chrono::Utc::now().timestamp(),
+            upbit_stale_secs: config.upbit.stale_secs,
+            last_cycle: Arc::new(parking_lot::RwLock::new(None)),
+            gap_monitor: Arc::new(build_gap_monitor(config, Vec::new())),
+        }
+    }
+
+    fn pool(symbol: &str, address: &str, price: f64) -> Arc<models::PoolData> {
+        Arc::new(models::PoolData::new(
+            symbol.to_string(), "ethereum".to_string(), "uniswap".to_string(),
+            address.to_string(), format!("{symbol}/USDC"), price,
+            1_000_000.0, 100_000.0, "test".to_string(),
+        ))
+    }
+
+    /// Serve the router on an ephemeral port
+    async fn serve(state: Arc<AppState>) -> std::net::SocketAddr {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(state)).await.unwrap() });
+        addr
+    }
+
+    /// Next text frame as JSON, skipping pings
+    async fn next_json<S>(ws: &mut S) -> serde_json::Value
+    where
+        S: futures::Stream<Item = Result<WsMessage, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        loop {
+            let msg = tokio::time::timeout(Duration::from_secs(5), ws.next()).await
+                .expect("frame within 5s")
+                .expect("socket open")
+                .unwrap();
+            if let WsMessage::Text(text) = msg {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn lagged_ws_client_gets_resync_then_snapshot() {
+        let state = Arc::new(test_state(&test_config(), 1));
+        state.ws_updates.publish(vec![pool("ETH", "0x1", 3000.0)], Vec::new());
+        let addr = serve(state.clone()).await;
+
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws")).await.unwrap();
+        // Sent after the handler subscribed, so the publishes below reach it
+        let initial = next_json(&mut ws).await;
+        assert_eq!(initial["type"], "pool_update");
+
+        // Several publishes without yielding overflow the 1-slot channel
+        for i in 0..5 {
+            state.ws_updates.publish(vec![pool("ETH", "0x1", 3000.0 + i as f64), pool("BTC", "0x2", 60_000.0)], Vec::new());
+        }
+
+        let resync = next_json(&mut ws).await;
+        assert_eq!(resync["type"], "resync");
+        assert!(resync["skipped"].as_u64().unwrap() >= 1);
+
+        let snapshot = next_json(&mut ws).await;
+        assert_eq!(snapshot["type"], "pool_update");
+        let pools = snapshot["data"].as_array().unwrap();
+        assert_eq!(pools.len(), 2, "full snapshot, not a delta");
+        let eth = pools.iter().find(|p| p["symbol"] == "ETH").unwrap();
+        assert_eq!(eth["price_usd"], 3004.0, "snapshot is the latest publish");
+    }
+
+    fn cache_pools(state: &AppState, pools: &[Arc<models::PoolData>]) {
+        state.cache.insert_many(pools.iter().map(|pool| (PoolCache::key_for(pool), (**pool).clone())));
+    }
+
+    /// GET `path`: (status, JSON body)
+    async fn get_json(addr: std::net::SocketAddr, path: &str) -> (u16, serde_json::Value) {
+        let response = reqwest::get(format!("http://{addr}{path}")).await.unwrap();
+        let status = response.status().as_u16();
+        (status, response.json().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn arbitrage_is_sorted_by_spread_and_capped() {
+        let state = Arc::new(test_state(&test_config(), 4));
+        // 5%, 20% and 10% spreads: all clear the mainnet fees and gas
+        cache_pools(&state, &[
+            pool("ETH", "0xe1", 1000.0), pool("ETH", "0xe2", 1050.0),
+            pool("BTC", "0xb1", 1000.0), pool("BTC", "0xb2", 1200.0),
+            pool("ARB", "0xa1", 1000.0), pool("ARB", "0xa2", 1100.0),
+        ]);
+        let addr = serve(state).await;
+
+        let (status, body) = get_json(addr, "/arbitrage").await;
+        assert_eq!(status, 200);
+        let symbols: Vec<&str> = body.as_array().unwrap().iter().map(|a| a["symbol"].as_str().unwrap()).collect();
+        assert_eq!(symbols, ["BTC", "ARB", "ETH"]);
+
+        let (_, body) = get_json(addr, "/arbitrage?limit=2").await;
+        let symbols: Vec<&str> = body.as_array().unwrap().iter().map(|a| a["symbol"].as_str().unwrap()).collect();
+        assert_eq!(symbols, ["BTC", "ARB"]);
+
+        let (_, body) = get_json(addr, "/arbitrage?limit=0").await;
+        assert!(body.as_array().unwrap().is_empty());
+
+        let (status, body) = get_json(addr, "/arbitrage?limit=many").await;
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn cached_count_tracks_size_and_generation() {
+        let state = Arc::new(test_state(&test_config(), 4));
+        let addr = serve(state.clone()).await;
+
+        let (status, empty) = get_json(addr, "/pools/cached/count").await;
+        assert_eq!(status, 200);
+        assert_eq!(empty["count"], 0);
+
+        cache_pools(&state, &[pool("ETH", "0x1", 3000.0), pool("BTC", "0x2", 60_000.0)]);
+        let (_, body) = get_json(addr, "/pools/cached/count").await;
+        assert_eq!(body["count"], state.cache.len());
+        assert_eq!(body["count"], 2);
+        let generation = body["generation"].as_u64().unwrap();
+        assert!(generation > empty["generation"].as_u64().unwrap());
+
+        cache_pools(&state, &[pool("SOL", "0x3", 150.0)]);
+        let (_, body) = get_json(addr, "/pools/cached/count").await;
+        assert_eq!(body["count"], 3);
+        assert!(body["generation"].as_u64().unwrap() > generation);
+    }
+
+    #[tokio::test]
+    async fn cached_pools_flag_and_exclude_stale_entries() {
+        let mut config = test_config();
+        config.arbitrage.max_age_secs = 60;
+        let state = Arc::new(test_state(&config, 4));
+        let fresh = pool("ETH", "0x1", 3000.0);
+        let expired = Arc::new(models::PoolData { timestamp: fresh.timestamp - 120, ..(*pool("BTC", "0x2", 60_000.0)).clone() });
+        cache_pools(&state, &[fresh, expired]);
+        let addr = serve(state).await;
+
+        let (_, page) = get_json(addr, "/pools/cached").await;
+        let stale: std::collections::HashMap<&str, bool> = page["pools"].as_array().unwrap().iter()
+            .map(|p| (p["symbol"].as_str().unwrap(), p["stale"].as_bool().unwrap()))
+            .collect();
+        assert_eq!(stale, std::collections::HashMap::from([("ETH", false), ("BTC", true)]));
+
+        let (_, page) = get_json(addr, "/pools/cached?exclude_stale=true").await;
+        assert_eq!(page["total"], 1);
+        assert_eq!(page["pools"][0]["symbol"], "ETH");
+    }
+
+    #[tokio::test]
+    async fn metrics_expose_counters_gauges_and_per_source_lines() {
+        let state = Arc::new(test_state(&test_config(), 4));
+        let stats = state.collector.get_stats();
+        stats.total_requests.store(7, Ordering::Relaxed);
+        stats.failed.store(2, Ordering::Relaxed);
+        stats.per_source.entry("geckoterminal").or_default().failed.store(2, Ordering::Relaxed);
+        cache_pools(&state, &[pool("ETH", "0x1", 3000.0)]);
+        let addr = serve(state).await;
+
+        let response = reqwest::get(format!("http://{addr}/metrics")).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.headers()["content-type"].to_str().unwrap().starts_with("text/plain"));
+        let body = response.text().await.unwrap();
+
+        for name in [
+            "dex_collector_requests_total",
+            "dex_collector_requests_successful_total",
+            "dex_collector_requests_failed_total",
+            "dex_collector_pools_collected_total",
+            "dex_source_requests_total",
+            "dex_cache_pools",
+            "dex_ws_clients",
+            "dex_arbitrage_gap_percent",
+        ] {
+            assert!(body.contains(&format!("# TYPE {name} ")), "missing {name}");
+        }
+        assert!(body.lines().any(|l| l == "dex_collector_requests_total 7"));
+        assert!(body.lines().any(|l| l == "dex_cache_pools 1"));
+        assert!(body.lines().any(|l| l == r#"dex_source_requests_total{source="geckoterminal",outcome="failure"} 2"#));
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_loops_and_writes_a_final_snapshot() {
+        let dir = std::env::temp_dir().join(format!("dex-gatherer-shutdown-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut state = test_state(&test_config(), 4);
+        state.storage = Some(Arc::new(services::LocalStorage::new(dir.to_str().unwrap())));
+        let state = Arc::new(state);
+        cache_pools(&state, &[pool("ETH", "0x1", 3000.0), pool("BTC", "0x2", 60_000.0)]);
+
+        // Stand-in for the collection loop: runs until the token fires
+        let loop_token = state.shutdown.clone();
+        let background = tokio::spawn(async move { loop_token.cancelled().await });
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let persist = dir.join("cache.json");
+        let server = tokio::spawn({
+            let state = state.clone();
+            let persist = persist.to_str().unwrap().to_string();
+            async move {
+                serve_until_shutdown(listener, state, async { stopped.await.ok(); }, vec![background], Some(&persist)).await
+            }
+        });
+
+        stop.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(5), server).await
+            .expect("server exits after the signal")
+            .unwrap()
+            .unwrap();
+
+        assert!(state.shutdown.is_cancelled());
+        let snapshots: Vec<_> = std::fs::read_dir(dir.join("snapshots")).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        assert_eq!(snapshots.len(), 1);
+        let name = snapshots[0].file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("full_") && name.ends_with(".json"), "{name}");
+        let saved: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&snapshots[0]).unwrap()).unwrap();
+        assert!(saved.to_string().contains("0x2"));
+        assert!(persist.exists(), "cache persisted for the next start");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    /// GET /pools/cached`query`: (total, pool addresses in page order)
+    async fn cached_page(addr: std::net::SocketAddr, query: &str) -> (u64, Vec<String>) {
+        let (status, page) = get_json(addr, &format!("/pools/cached{query}")).await;
+        assert_eq!(status, 200, "{query}: {page}");
+        let addresses = page["pools"].as_array().unwrap().iter()
+            .map(|p| p["pool_address"].as_str().unwrap().to_string())
+            .collect();
+        (page["total"].as_u64().unwrap(), addresses)
+    }
+
+    #[tokio::test]
+    async fn cached_pools_filter_and_paginate() {
+        let state = Arc::new(test_state(&test_config(), 4));
+        let with = |base: Arc<models::PoolData>, chain: &str, dex: &str, lp: f64| Arc::new(models::PoolData {
+            chain: chain.to_string(), dex: dex.to_string(), lp_reserve_usd: lp, ..(*base).clone()
+        });
+        cache_pools(&state, &[
+            with(pool("ETH", "0xe1", 3000.0), "ethereum", "uniswap", 500_000.0),
+            with(pool("ETH", "0xe2", 3001.0), "arbitrum", "camelot", 400_000.0),
+            with(pool("ETH", "0xe3", 3002.0), "Arbitrum", "uniswap", 300_000.0),
+            with(pool("BTC", "0xb1", 60_000.0), "ethereum", "curve", 200_000.0),
+            with(pool("BTC", "0xb2", 60_001.0), "bsc", "pancakeswap", 100_000.0),
+        ]);
+        let addr = serve(state).await;
+
+        // Deepest LP first
+        assert_eq!(cached_page(addr, "").await, (5, vec!["0xe1".into(), "0xe2".into(), "0xe3".into(), "0xb1".into(), "0xb2".into()]));
+
+        assert_eq!(cached_page(addr, "?chain=ARBITRUM").await, (2, vec!["0xe2".into(), "0xe3".into()]));
+        assert_eq!(cached_page(addr, "?symbol=btc").await, (2, vec!["0xb1".into(), "0xb2".into()]));
+        assert_eq!(cached_page(addr, "?dex=Uniswap").await, (2, vec!["0xe1".into(), "0xe3".into()]));
+        assert_eq!(cached_page(addr, "?min_lp=300000").await.0, 3);
+        assert_eq!(cached_page(addr, "?symbol=eth&dex=uniswap&min_lp=400000").await, (1, vec!["0xe1".into()]));
+        // Exact match, not substring
+        assert_eq!(cached_page(addr, "?dex=uni").await.0, 0);
+
+        // Pagination: total counts all matches, the page is the window
+        assert_eq!(cached_page(addr, "?limit=2").await, (5, vec!["0xe1".into(), "0xe2".into()]));
+        assert_eq!(cached_page(addr, "?limit=2&offset=2").await, (5, vec!["0xe3".into(), "0xb1".into()]));
+        assert_eq!(cached_page(addr, "?limit=2&offset=4").await, (5, vec!["0xb2".into()]));
+        assert_eq!(cached_page(addr, "?offset=10").await, (5, vec![]));
+        assert_eq!(cached_page(addr, "?chain=ethereum&limit=1&offset=1").await, (2, vec!["0xb1".into()]));
+
+        let (_, page) = get_json(addr, "/pools/cached?limit=2&offset=2").await;
+        assert_eq!((page["offset"].as_u64(), page["limit"].as_u64()), (Some(2), Some(2)));
+
+        let (status, body) = get_json(addr, "/pools/cached?offset=-1").await;
+        assert_eq!(status, 400);
+        assert_eq!(body["error"], "bad_request");
+    }
+
+    #[tokio::test]
+    async fn symbol_pools_returns_only_that_symbol_by_lp() {
+        let state = Arc::new(test_state(&test_config(), 4));
+        let with_lp = |base: Arc<models::PoolData>, lp: f64| Arc::new(models::PoolData { lp_reserve_usd: lp, ..(*base).clone() });
+        cache_pools(&state, &[
+            with_lp(pool("ETH", "0xe1", 3000.0), 200_000.0),
+            with_lp(pool("ETH", "0xe2", 3001.0), 900_000.0),
+            with_lp(pool("BTC", "0xb1", 60_000.0), 5_000_000.0),
+        ]);
+        let addr = serve(state).await;
+
+        let (status, body) = get_json(addr, "/pools/eth").await;
+        assert_eq!(status, 200);
+        let pools = body.as_array().unwrap();
+        let addresses: Vec<&str> = pools.iter().map(|p| p["pool_address"].as_str().unwrap()).collect();
+        assert_eq!(addresses, ["0xe2", "0xe1"]);
+        assert!(pools.iter().all(|p| p["symbol"] == "ETH"));
+
+        let (status, body) = get_json(addr, "/pools/DOGE").await;
+        assert_eq!(status, 200, "unknown symbol is an empty list, not 404");
+        assert_eq!(body, serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn history_validates_the_symbol() {
+        let dir = std::env::temp_dir().join(format!("dex-gatherer-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let storage = services::LocalStorage::new(dir.to_str().unwrap());
+        storage.save_symbol_pools("ETH", &[(*pool("ETH", "0x1", 3000.0)).clone()]);
+        let mut state = test_state(&test_config(), 4);
+        state.storage = Some(Arc::new(storage));
+        let addr = serve(Arc::new(state)).await;
+
+        // Lowercase is normalized to the stored file's symbol
+        let (status, body) = get_json(addr, "/history/eth").await;
+        assert_eq!(status, 200);
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["symbol"], "ETH");
+
+        for path in ["/history/..%2F..%2Fetc%2Fpasswd", "/history/ETH%2F..", "/history/E.TH"] {
+            let (status, body) = get_json(addr, path).await;
+            assert_eq!(status, 400, "{path}");
+            assert_eq!(body["error"], "bad_request");
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn subscription_commands_update_the_filter() {
+        let mut subscription = WsSubscription::default();
+        assert!(subscription.matches("ETH") && subscription.matches("DOGE"), "empty = everything");
+
+        assert!(subscription.handle(r#"{"type":"subscribe","symbols":["btc"," ETH "]}"#));
+        assert!(subscription.matches("eth") && subscription.matches("BTC"));
+        assert!(!subscription.matches("DOGE"));
+
+        assert!(subscription.handle(r#"{"type":"unsubscribe","symbols":["BTC"]}"#));
+        assert!(!subscription.matches("BTC") && subscription.matches("ETH"));
+
+        assert!(subscription.handle(r#"{"type":"unsubscribe"}"#));
+        assert!(subscription.matches("DOGE"));
+
+        assert!(!subscription.handle(r#"{"type":"hello"}"#));
+        assert!(!subscription.handle("not json"));
+    }
+
+    /// Symbols carried by a pool message (`data` of a snapshot, `updated` of a delta)
+    fn message_symbols(msg: &serde_json::Value, field: &str) -> Vec<String> {
+        let mut symbols: Vec<String> = msg[field].as_array().unwrap().iter()
+            .map(|p| p["symbol"].as_str().unwrap().to_string())
+            .collect();
+        symbols.sort();
+        symbols
+    }
+
+    #[tokio::test]
+    async fn ws_subscription_filters_pools() {
+        let state = Arc::new(test_state(&test_config(), 8));
+        state.ws_updates.publish(vec![pool("ETH", "0x1", 3000.0), pool("BTC", "0x2", 60_000.0)], Vec::new());
+        let addr = serve(state.clone()).await;
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws")).await.unwrap();
+        assert_eq!(message_symbols(&next_json(&mut ws).await, "data"), ["BTC", "ETH"]);
+
+        ws.send(WsMessage::Text(r#"{"type":"subscribe","symbols":["eth"]}"#.into())).await.unwrap();
+        let ack = next_json(&mut ws).await;
+        assert_eq!(ack, serde_json::json!({ "type": "subscribed", "symbols": ["ETH"] }));
+        let snapshot = next_json(&mut ws).await;
+        assert_eq!(snapshot["type"], "pool_update");
+        assert_eq!(message_symbols(&snapshot, "data"), ["ETH"]);
+
+        // Both prices move; only the subscribed one comes through
+        state.ws_updates.publish(vec![pool("ETH", "0x1", 3100.0), pool("BTC", "0x2", 61_000.0)], Vec::new());
+        let delta = next_json(&mut ws).await;
+        assert_eq!(delta["type"], "pool_delta");
+        assert_eq!(message_symbols(&delta, "updated"), ["ETH"]);
+
+        ws.send(WsMessage::Text(r#"{"type":"unsubscribe"}"#.into())).await.unwrap();
+        assert_eq!(next_json(&mut ws).await["symbols"], serde_json::json!([]));
+        assert_eq!(message_symbols(&next_json(&mut ws).await, "data"), ["BTC", "ETH"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ws_updates_follow_the_configured_interval() {
+        let mut state = test_state(&test_config(), 64);
+        state.ws_update_interval = Duration::from_millis(50);
+        let state = Arc::new(state);
+        let (_, mut updates) = state.ws_updates.subscribe();
+        let task = spawn_ws_updates(state.clone());
+
+        // Ticks at 0, 50, ..., 250ms
+        let mut received = 0;
+        let window = tokio::time::sleep(Duration::from_millis(275));
+        tokio::pin!(window);
+        loop {
+            tokio::select! {
+                _ = &mut window => break,
+                update = updates.recv() => {
+                    update.unwrap();
+                    received += 1;
+                }
+            }
+        }
+        assert_eq!(received, 6);
+
+        state.shutdown.cancel();
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn ws_heartbeat_follows_the_configured_interval() {
+        let mut state = test_state(&test_config(), 4);
+        state.ws_heartbeat = Duration::from_millis(40);
+        let addr = serve(Arc::new(state)).await;
+        let (mut ws, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws")).await.unwrap();
+
+        let mut pings = 0;
+        let deadline = tokio::time::Instant::now() + Duration::from_millis(420);
+        while let Ok(Some(msg)) = tokio::time::timeout_at(deadline, ws.next()).await {
+            if matches!(msg.unwrap(), WsMessage::Ping(_)) {
+                pings += 1;
+            }
+        }
+        // ~11 at 40ms; the default (10s) would give 1
+        assert!((6..=12).contains(&pings), "{pings} pings");
+    }
+
+    /// Write `content` and push the mtime forward so the change is seen even on coarse clocks
+    fn rewrite_config(path: &Path, content: &str, bump_secs: u64) {
+        std::fs::write(path, content).unwrap();
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(std::time::SystemTime::now() + Duration::from_secs(bump_secs)).unwrap();
+    }
+
+    /// Poll until `done` holds (or give up after 5s)
+    async fn eventually(mut done: impl FnMut() -> bool) -> bool {
+        for _ in 0..250 {
+            if done() {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        done()
+    }
+
+    #[tokio::test]
+    async fn config_reload_applies_a_new_threshold() {
+        let dir = std::env::temp_dir().join(format!("dex-gatherer-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(&path, TEST_CONFIG).unwrap();
+        let state = Arc::new(test_state(&test_config(), 4));
+        assert_eq!(state.detector.threshold_for("ETH"), 0.01);
+        let task = spawn_config_reload(path.clone(), state.clone(), Duration::from_millis(20));
+
+        rewrite_config(&path, &TEST_CONFIG.replace("threshold = 0.01", "threshold = 0.035"), 10);
+        assert!(eventually(|| state.detector.threshold_for("ETH") == 0.035).await, "new threshold applied");
+
+        // An invalid edit is ignored and the previous value kept
+        rewrite_config(&path, &TEST_CONFIG.replace("threshold = 0.01", "threshold = 7"), 20);
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(state.detector.threshold_for("ETH"), 0.035);
+
+        state.shutdown.cancel();
+        task.await.unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn gaps_are_unavailable_before_upbit_prices() {
+        let addr = serve(Arc::new(test_state(&test_config(), 4))).await;
+        let (status, body) = get_json(addr, "/gaps").await;
+        assert_eq!(status, 503);
+        assert_eq!(body["error"], "unavailable");
+    }
+
+    #[tokio::test]
+    async fn vwap_endpoint_returns_one_price_per_symbol() {
+        let state = test_state(&test_config(), 4);
+        let mut thin = (*pool("ETH", "0x2", 3300.0)).clone();
+        thin.volume_24h = 0.0;
+        cache_pools(&state, &[pool("ETH", "0x1", 3000.0), Arc::new(thin), pool("BTC", "0x3", 60_000.0)]);
+        let addr = serve(Arc::new(state)).await;
+
+        let (status, body) = get_json(addr, "/prices/vwap").await;
+        assert_eq!(status, 200);
+        assert_eq!(body, serde_json::json!({ "ETH": 3000.0, "BTC": 60000.0 }));
+    }
+
+    #[tokio::test]
+    async fn source_debug_reports_each_source_and_503s_when_all_fail() {
+        use dex_gatherer::sources::mock::MockSource;
+        use dex_gatherer::sources::{PoolSource, SourceError};
+
+        let config = test_config();
+        let debug_state = |sources: Vec<Arc<dyn PoolSource>>| {
+            let mut state = test_state(&config, 4);
+            state.collector = DexGatherer::with_sources(&config, sources).collector().clone();
+            Arc::new(state)
+        };
+
+        let addr = serve(debug_state(vec![
+            Arc::new(MockSource::new("Good").with_pools("ETH", vec![(*pool("ETH", "0x1", 3000.0)).clone()])),
+            Arc::new(MockSource::failing("Broken", SourceError::Http(500))),
+        ])).await;
+        let (status, body) = get_json(addr, "/sources/debug/ETH").await;
+        assert_eq!(status, 200);
+        assert_eq!(body[0]["source"], "Good");
+        assert_eq!(body[0]["pool_count"], 1);
+        assert_eq!(body[0]["pools"][0]["pool_address"], "0x1");
+        assert!(body[0]["error"].is_null());
+        assert!(body[0]["elapsed_ms"].is_u64());
+        assert_eq!(body[1]["source"], "Broken");
+        assert_eq!(body[1]["error"], SourceError::Http(500).to_string());
+
+        let addr = serve(debug_state(vec![Arc::new(MockSource::failing("Broken", SourceError::Http(500)))])).await;
+        let (status, body) = get_json(addr, "/sources/debug/ETH").await;
+        assert_eq!(status, 503);
+        assert_eq!(body["error"], "unavailable");
+        assert!(body["message"].as_str().unwrap().contains("Broken"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn stale_collection_degrades_health_with_a_503() {
+        let state = Arc::new(test_state(&test_config(), 4));
+        cache_pools(&state, &[pool("ETH", "0x1", 3000.0)]);
+        let now = chrono::Utc::now().timestamp();
+        state.last_collection.store(now - COLLECTION_STALE_SECS - 60, Ordering::Relaxed);
+        let addr = serve(state.clone()).await;
+
+        let (status, body) = get_json(addr, "/health").await;
+        assert_eq!(status, 503);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["cache_pools"], 1);
+        assert!(body["last_collection_secs_ago"].as_i64().unwrap() >= COLLECTION_STALE_SECS + 60);
+        assert!(body["problems"].as_array().unwrap().contains(&serde_json::json!("collection")), "{body}");
+
+        // A fresh cycle clears the 503; the disconnected Upbit feed still degrades it
+        state.last_collection.store(now, Ordering::Relaxed);
+        let (status, body) = get_json(addr, "/health").await;
+        assert_eq!(status, 200);
+        assert_eq!(body["status"], "degraded");
+        assert_eq!(body["problems"], serde_json::json!(["upbit"]));
+    }
+
+    #[test]
+    fn collection_counts_as_stale_from_startup_before_the_first_cycle() {
+        let mut state = test_state(&test_config(), 4);
+        let now = chrono::Utc::now().timestamp();
+        state.started_at = now - 10;
+        let report = HealthReport::evaluate(&state, now);
+        assert_eq!(report.http_status(), axum::http::StatusCode::OK);
+        assert_eq!(report.last_collection_secs_ago, None);
+        assert!(!report.problems.contains(&"cache"), "an empty cache is expected before the first cycle");
+
+        state.started_at = now - COLLECTION_STALE_SECS - 1;
+        let report = HealthReport::evaluate(&state, now);
+        assert_eq!(report.http_status(), axum::http::StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(report.status, "degraded");
+    }
+
+    #[tokio::test]
+    async fn last_cycle_endpoint_reflects_the_stored_result() {
+        let state = Arc::new(test_state(&test_config(), 4));
+        let addr = serve(state.clone()).await;
+
+        let (status, body) = get_json(addr, "/stats/last-cycle").await;
+        assert_eq!(status, 200);
+        assert!(body.is_null(), "no cycle has finished yet");
+
+        let store = |total, successful, failed, timestamp, duration_ms| {
+            *state.last_cycle.write() = Some(LastCycle {
+                result: services::collector::CollectorResult { total, successful, failed },
+                timestamp,
+                duration_ms,
+            });
+        };
+        store(10, 8, 2, 1_735_787_045, 1234);
+        let (_, body) = get_json(addr, "/stats/last-cycle").await;
+        assert_eq!(body, serde_json::json!({
+            "total": 10,
+            "successful": 8,
+            "failed": 2,
+            "timestamp": 1_735_787_045,
+            "timestamp_iso": "2025-01-02T03:04:05Z",
+            "duration_ms": 1234,
+        }));
+
+        // The next cycle replaces it
+        store(12, 12, 0, 1_735_787_105, 900);
+        let (_, body) = get_json(addr, "/stats/last-cycle").await;
+        assert_eq!((body["successful"].as_u64(), body["timestamp"].as_i64(), body["duration_ms"].as_u64()),
+            (Some(12), Some(1_735_787_105), Some(900)));
+    }
+
+    #[tokio::test]
+    async fn live_history_endpoint_returns_the_ring_oldest_first() {
+        let mut config = test_config();
+        config.live_history.max_points = 2;
+        let state = Arc::new(test_state(&config, 4));
+        for (ts, price) in [(1_735_787_045, 3000.0), (1_735_787_105, 3010.0), (1_735_787_165, 3020.0)] {
+            state.live_history.record(&std::collections::HashMap::from([("ETH".to_string(), price)]), ts);
+        }
+        let addr = serve(state).await;
+
+        let (status, body) = get_json(addr, "/history/live/eth").await;
+        assert_eq!(status, 200);
+        assert_eq!(body, serde_json::json!([
+            { "timestamp": 1_735_787_105, "timestamp_iso": "2025-01-02T03:05:05Z", "price_usd": 3010.0 },
+            { "timestamp": 1_735_787_165, "timestamp_iso": "2025-01-02T03:06:05Z", "price_usd": 3020.0 },
+        ]));
+
+        let (status, body) = get_json(addr, "/history/live/BTC").await;
+        assert_eq!(status, 404);
+        assert_eq!(body["error"], "not_found");
+    }
+}
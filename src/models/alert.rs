@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use super::PoolData;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArbitrageAlert {
     pub symbol: String,
     pub arb_type: ArbType,
@@ -10,15 +10,48 @@ pub struct ArbitrageAlert {
     pub high_price: f64,
     pub high_source: String,
     pub diff_pct: f64,
+    /// Plain-language trade direction, e.g. "Buy on uniswap_v3 (0xabc1…), sell on Upbit"
+    #[serde(default)]
+    pub action: String,
+    /// Spread after both legs' swap fees and gas (percent)
+    pub net_diff_pct: f64,
+    /// Gas for the swaps at the reference trade size (USD)
+    pub estimated_gas_usd: f64,
+    /// Trade size the thinner pool can absorb (LP × 2%)
+    pub tradeable_usd: f64,
+    /// Realized profit at `tradeable_usd` after fees and gas
+    pub estimated_profit_usd: f64,
     pub timestamp: i64,
+    /// Alert ladder rung this alert satisfies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rung: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ArbType {
     DexToDex,
     DexToCex,
 }
 
+/// "0xabc123…" style short address
+fn short_address(address: &str) -> String {
+    if address.chars().count() > 10 {
+        format!("{}…", address.chars().take(8).collect::<String>())
+    } else {
+        address.to_string()
+    }
+}
+
+/// Venue label for a DEX pool: "dex (0xabc1…)"
+pub fn dex_venue(pool: &PoolData) -> String {
+    format!("{} ({})", pool.dex, short_address(&pool.pool_address))
+}
+
+/// "Buy on X, sell on Y"
+pub fn trade_action(buy_venue: &str, sell_venue: &str) -> String {
+    format!("Buy on {}, sell on {}", buy_venue, sell_venue)
+}
+
 impl ArbitrageAlert {
     pub fn from_pools(low: &PoolData, high: &PoolData) -> Self {
         let diff_pct = (high.price_usd - low.price_usd) / low.price_usd * 100.0;
@@ -30,7 +63,80 @@ impl ArbitrageAlert {
             high_price: high.price_usd,
             high_source: format!("{}:{}", high.dex, high.pool_address),
             diff_pct,
+            action: trade_action(&dex_venue(low), &dex_venue(high)),
+            net_diff_pct: diff_pct,
+            estimated_gas_usd: 0.0,
+            tradeable_usd: 0.0,
+            estimated_profit_usd: 0.0,
             timestamp: chrono::Utc::now().timestamp(),
+            rung: None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    pub(crate) fn arbitrary_alert(rng: &mut Rng, arb_type: ArbType) -> ArbitrageAlert {
+        ArbitrageAlert {
+            symbol: rng.string(),
+            arb_type,
+            low_price: rng.finite_f64(),
+            low_source: rng.string(),
+            high_price: rng.finite_f64(),
+            high_source: rng.string(),
+            diff_pct: rng.finite_f64(),
+            action: rng.string(),
+            net_diff_pct: rng.finite_f64(),
+            estimated_gas_usd: rng.finite_f64(),
+            tradeable_usd: rng.finite_f64(),
+            estimated_profit_usd: rng.finite_f64(),
+            timestamp: rng.next_u64() as i64,
+            rung: rng.option(Rng::string),
+        }
+    }
+
+    #[test]
+    fn alert_serde_round_trip_for_every_arb_type() {
+        let mut rng = Rng::new(0x5eed_0002);
+        for arb_type in [ArbType::DexToDex, ArbType::DexToCex] {
+            for _ in 0..1000 {
+                let alert = arbitrary_alert(&mut rng, arb_type);
+                let json = serde_json::to_string(&alert).unwrap();
+                let back: ArbitrageAlert = serde_json::from_str(&json).unwrap();
+                assert_eq!(back, alert, "{json}");
+            }
+        }
+    }
+
+    #[test]
+    fn action_names_the_cheap_pool_as_the_buy_leg() {
+        let low = PoolData {
+            symbol: "ETH".to_string(),
+            dex: "uniswap_v3".to_string(),
+            pool_address: "0xabc1234567890".to_string(),
+            price_usd: 1000.0,
+            ..PoolData::default()
+        };
+        let high = PoolData { dex: "sushiswap".to_string(), pool_address: "0xdef9".to_string(), price_usd: 1010.0, ..low.clone() };
+        let alert = ArbitrageAlert::from_pools(&low, &high);
+        assert_eq!(alert.action, "Buy on uniswap_v3 (0xabc123…), sell on sushiswap (0xdef9)");
+        // Existing fields keep their raw form
+        assert_eq!(alert.low_source, "uniswap_v3:0xabc1234567890");
+    }
+
+    #[test]
+    fn arb_type_uses_variant_names() {
+        assert_eq!(serde_json::to_string(&ArbType::DexToDex).unwrap(), "\"DexToDex\"");
+        assert_eq!(serde_json::to_string(&ArbType::DexToCex).unwrap(), "\"DexToCex\"");
+    }
+
+    #[test]
+    fn untagged_alert_omits_rung() {
+        let alert = arbitrary_alert(&mut Rng::new(7), ArbType::DexToDex);
+        let value = serde_json::to_value(ArbitrageAlert { rung: None, ..alert }).unwrap();
+        assert!(value.get("rung").is_none());
+    }
+}
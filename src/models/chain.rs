@@ -0,0 +1,90 @@
+/// Alias (lowercase, `_`/space written as `-`) -> canonical chain name.
+/// Numeric entries are EVM chain IDs.
+const ALIASES: &[(&str, &str)] = &[
+    ("ethereum", "ethereum"), ("eth", "ethereum"), ("mainnet", "ethereum"), ("erc20", "ethereum"), ("1", "ethereum"),
+    ("bsc", "bsc"), ("bnb", "bsc"), ("binance", "bsc"), ("binance-smart-chain", "bsc"), ("bnb-chain", "bsc"),
+    ("bep20", "bsc"), ("56", "bsc"),
+    ("polygon", "polygon"), ("matic", "polygon"), ("polygon-pos", "polygon"), ("137", "polygon"),
+    ("arbitrum", "arbitrum"), ("arb", "arbitrum"), ("arbitrum-one", "arbitrum"), ("42161", "arbitrum"),
+    ("optimism", "optimism"), ("op", "optimism"), ("optimistic-ethereum", "optimism"), ("10", "optimism"),
+    ("avalanche", "avalanche"), ("avax", "avalanche"), ("avalanche-c", "avalanche"), ("c-chain", "avalanche"),
+    ("43114", "avalanche"),
+    ("base", "base"), ("8453", "base"),
+    ("fantom", "fantom"), ("ftm", "fantom"), ("250", "fantom"),
+    ("linea", "linea"), ("59144", "linea"),
+    ("scroll", "scroll"), ("534352", "scroll"),
+    ("mantle", "mantle"), ("5000", "mantle"),
+    ("blast", "blast"), ("81457", "blast"),
+    ("mode", "mode"), ("34443", "mode"),
+    ("unichain", "unichain"), ("130", "unichain"),
+    ("zksync", "zksync"), ("zksync-era", "zksync"), ("era", "zksync"), ("324", "zksync"),
+    ("gnosis", "gnosis"), ("xdai", "gnosis"), ("100", "gnosis"),
+    ("cronos", "cronos"), ("cro", "cronos"), ("25", "cronos"),
+    ("monad", "monad"), ("143", "monad"),
+    ("sonic", "sonic"), ("9745", "sonic"),
+    ("solana", "solana"), ("sol", "solana"),
+    ("tron", "tron"), ("trx", "tron"),
+    ("ton", "ton"),
+    ("sui", "sui"),
+    ("aptos", "aptos"),
+];
+
+/// Canonical name for a known chain alias ("ETH", "eth", "1" -> "ethereum")
+pub fn normalize_chain(raw: &str) -> Option<&'static str> {
+    let key = raw.trim().to_lowercase().replace(['_', ' '], "-");
+    ALIASES.iter()
+        .find(|(alias, _)| *alias == key)
+        .map(|(_, canonical)| *canonical)
+}
+
+/// Canonical name for an EVM chain ID
+pub fn chain_for_id(chain_id: u64) -> Option<&'static str> {
+    normalize_chain(&chain_id.to_string())
+}
+
+/// Canonical name, or the trimmed lowercase input for chains not in the table
+pub fn canonical_chain(raw: &str) -> String {
+    normalize_chain(raw)
+        .map(str::to_string)
+        .unwrap_or_else(|| raw.trim().to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_aliases_normalize() {
+        let cases = [
+            ("ethereum", "ethereum"), ("ETH", "ethereum"), (" Mainnet ", "ethereum"), ("1", "ethereum"),
+            ("bsc", "bsc"), ("BNB", "bsc"), ("binance_smart_chain", "bsc"), ("BNB Chain", "bsc"), ("56", "bsc"),
+            ("matic", "polygon"), ("polygon_pos", "polygon"), ("137", "polygon"),
+            ("arb", "arbitrum"), ("Arbitrum One", "arbitrum"), ("42161", "arbitrum"),
+            ("op", "optimism"), ("optimistic-ethereum", "optimism"),
+            ("avax", "avalanche"), ("c-chain", "avalanche"),
+            ("8453", "base"), ("ftm", "fantom"), ("zksync_era", "zksync"), ("xdai", "gnosis"),
+            ("sol", "solana"), ("trx", "tron"),
+        ];
+        for (alias, canonical) in cases {
+            assert_eq!(normalize_chain(alias), Some(canonical), "{alias:?}");
+        }
+    }
+
+    #[test]
+    fn unknown_chains() {
+        assert_eq!(normalize_chain("multi"), None);
+        assert_eq!(normalize_chain(""), None);
+        assert_eq!(chain_for_id(99_999), None);
+        assert_eq!(chain_for_id(10), Some("optimism"));
+        // Kept as-is (lowercased) so they still group together
+        assert_eq!(canonical_chain(" Berachain "), "berachain");
+        assert_eq!(canonical_chain("Matic"), "polygon");
+    }
+
+    #[test]
+    fn every_canonical_name_maps_to_itself() {
+        for (_, canonical) in ALIASES {
+            assert_eq!(normalize_chain(canonical), Some(*canonical));
+        }
+    }
+}
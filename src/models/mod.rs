@@ -1,5 +1,9 @@
 pub mod pool;
 pub mod alert;
+pub mod chain;
+pub mod timestamp;
 
-pub use pool::PoolData;
+pub use pool::{PoolData, cmp_desc_nan_last};
 pub use alert::ArbitrageAlert;
+pub use chain::{canonical_chain, chain_for_id};
+pub use timestamp::{with_iso, WithIsoTime};
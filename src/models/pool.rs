@@ -1,6 +1,44 @@
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Descending order for prices/gaps; NaN always sorts last so one bad value can't panic a sort
+pub fn cmp_desc_nan_last(a: f64, b: f64) -> Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => b.total_cmp(&a),
+    }
+}
+
+/// Flat fee of a V2-style DEX ("uniswap_v2", "sushiswap", ...).
+/// None for concentrated-liquidity DEXes (V3/CLMM), where the fee is per pool.
+pub fn conventional_fee(dex_kind: &str) -> Option<f64> {
+    let dex = dex_kind.trim().to_lowercase().replace(['-', ' '], "_");
+    if ["v3", "v4", "clmm", "dlmm", "whirlpool", "slipstream"].iter().any(|k| dex.contains(k)) {
+        return None;
+    }
+    let name = dex.strip_suffix("_v2").unwrap_or(&dex);
+    match name {
+        "pancakeswap" | "raydium" | "biswap" => Some(0.0025),
+        "uniswap" | "sushiswap" | "quickswap" | "traderjoe" | "spookyswap"
+        | "camelot" | "baseswap" => Some(0.003),
+        _ => None,
+    }
+}
+
+/// Fee in a "0.05%" style label (the token that ends with '%'), as a fraction
+pub fn parse_fee_label(label: &str) -> Option<f64> {
+    label.split_whitespace()
+        .find_map(|part| part.strip_suffix('%'))
+        .and_then(|pct| pct.parse::<f64>().ok())
+        .filter(|pct| pct.is_finite() && (0.0..100.0).contains(pct))
+        .map(|pct| pct / 100.0)
+}
+
+/// Missing fields deserialize to defaults so reduced storage schemas still load
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PoolData {
     pub symbol: String,
     pub chain: String,
@@ -10,12 +48,20 @@ pub struct PoolData {
     pub price_usd: f64,
     pub lp_reserve_usd: f64,
     pub volume_24h: f64,
+    /// Swap fee as a fraction (0.003 = 0.3%); None = unknown
     pub fee_tier: Option<f64>,
+    /// `fee_tier` is the DEX's conventional fee, not one reported for this pool
+    pub fee_estimated: bool,
+    /// Contract address of the priced token, when the source reports it
+    pub base_token: Option<String>,
+    /// Contract address of the token it is priced against (USDC, WETH, ...)
+    pub quote_token: Option<String>,
     pub source: String,
     pub timestamp: i64,
 }
 
 impl PoolData {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         symbol: String,
         chain: String,
@@ -29,7 +75,8 @@ impl PoolData {
     ) -> Self {
         Self {
             symbol,
-            chain,
+            // One name per chain across sources ("eth"/"1" -> "ethereum")
+            chain: super::chain::canonical_chain(&chain),
             dex,
             pool_address,
             pair,
@@ -37,8 +84,152 @@ impl PoolData {
             lp_reserve_usd,
             volume_24h,
             fee_tier: None,
+            fee_estimated: false,
+            base_token: None,
+            quote_token: None,
             source,
             timestamp: chrono::Utc::now().timestamp(),
         }
     }
+
+    /// Price is a real number (not NaN/inf, e.g. from a divide-by-zero in quote math)
+    pub fn is_finite_price(&self) -> bool {
+        self.price_usd.is_finite()
+    }
+
+    /// Base/quote token contract addresses (empty strings count as unknown)
+    pub fn with_tokens(mut self, base: Option<String>, quote: Option<String>) -> Self {
+        self.base_token = base.filter(|a| !a.is_empty());
+        self.quote_token = quote.filter(|a| !a.is_empty());
+        self
+    }
+
+    /// Fee reported by the source for this pool (no-op for None)
+    pub fn with_fee_tier(mut self, fee: Option<f64>) -> Self {
+        if let Some(fee) = fee {
+            self.fee_tier = Some(fee);
+            self.fee_estimated = false;
+        }
+        self
+    }
+
+    /// Falls back to the conventional fee of `dex_kind` when no fee is known yet
+    pub fn with_conventional_fee(mut self, dex_kind: &str) -> Self {
+        if self.fee_tier.is_none() {
+            if let Some(fee) = conventional_fee(dex_kind) {
+                self.fee_tier = Some(fee);
+                self.fee_estimated = true;
+            }
+        }
+        self
+    }
+
+    /// Source-independent identity of the on-chain pool: `chain:pool_address` (lowercase)
+    pub fn dedup_key(&self) -> String {
+        format!("{}:{}", self.chain.to_lowercase(), self.pool_address.trim().to_lowercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::Rng;
+
+    /// Arbitrary pool (struct literal so a new field can't be silently left out)
+    pub(crate) fn arbitrary_pool(rng: &mut Rng) -> PoolData {
+        PoolData {
+            symbol: rng.string(),
+            chain: rng.string(),
+            dex: rng.string(),
+            pool_address: rng.string(),
+            pair: rng.string(),
+            price_usd: rng.finite_f64(),
+            lp_reserve_usd: rng.finite_f64(),
+            volume_24h: rng.finite_f64(),
+            fee_tier: rng.option(Rng::finite_f64),
+            fee_estimated: rng.bool(),
+            base_token: rng.option(Rng::string),
+            quote_token: rng.option(Rng::string),
+            source: rng.string(),
+            timestamp: rng.next_u64() as i64,
+        }
+    }
+
+    #[test]
+    fn pool_serde_round_trip() {
+        let mut rng = Rng::new(0x5eed_0001);
+        for _ in 0..2000 {
+            let pool = arbitrary_pool(&mut rng);
+            let json = serde_json::to_string(&pool).unwrap();
+            let back: PoolData = serde_json::from_str(&json).unwrap();
+            assert_eq!(back, pool, "{json}");
+        }
+    }
+
+    #[test]
+    fn pool_fee_tier_none_and_some_survive() {
+        for fee_tier in [None, Some(0.0), Some(0.0005), Some(0.01)] {
+            let pool = PoolData { fee_tier, ..PoolData::default() };
+            let back: PoolData = serde_json::from_str(&serde_json::to_string(&pool).unwrap()).unwrap();
+            assert_eq!(back.fee_tier, fee_tier);
+        }
+    }
+
+    #[test]
+    fn reduced_schema_fills_defaults() {
+        let pool: PoolData = serde_json::from_str(r#"{"symbol":"ETH","price_usd":3000.5}"#).unwrap();
+        assert_eq!(pool.symbol, "ETH");
+        assert_eq!(pool.price_usd, 3000.5);
+        assert_eq!(pool.fee_tier, None);
+        assert_eq!(pool.timestamp, 0);
+    }
+
+    #[test]
+    fn nan_sorts_last_without_panicking() {
+        let mut prices = [2.0, f64::NAN, 5.0, -1.0, f64::NAN, 3.5];
+        prices.sort_by(|a, b| cmp_desc_nan_last(*a, *b));
+        assert_eq!(&prices[..4], &[5.0, 3.5, 2.0, -1.0]);
+        assert!(prices[4..].iter().all(|p| p.is_nan()));
+    }
+
+    #[test]
+    fn nan_comparisons_are_consistent() {
+        assert_eq!(cmp_desc_nan_last(f64::NAN, f64::NAN), Ordering::Equal);
+        assert_eq!(cmp_desc_nan_last(f64::NAN, 1.0), Ordering::Greater);
+        assert_eq!(cmp_desc_nan_last(1.0, f64::NAN), Ordering::Less);
+        assert_eq!(cmp_desc_nan_last(f64::INFINITY, 1.0), Ordering::Less);
+    }
+
+    #[test]
+    fn fee_labels_parse_as_fractions() {
+        assert_eq!(parse_fee_label("WETH / USDC 0.05%"), Some(0.0005));
+        assert_eq!(parse_fee_label("1%"), Some(0.01));
+        assert_eq!(parse_fee_label("WETH / USDC"), None);
+        assert_eq!(parse_fee_label("v3"), None);
+        assert_eq!(parse_fee_label("abc%"), None);
+        assert_eq!(parse_fee_label("150%"), None, "out of range");
+    }
+
+    #[test]
+    fn reported_fee_wins_over_the_conventional_default() {
+        let pool = |dex: &str| PoolData::new(
+            "ETH".into(), "ethereum".into(), dex.into(), "0x1".into(), "WETH/USDC".into(),
+            3000.0, 1.0, 1.0, "test".into(),
+        );
+        assert_eq!(pool("uniswap").fee_tier, None, "unknown until a source says otherwise");
+
+        let reported = pool("uniswap").with_fee_tier(Some(0.0005)).with_conventional_fee("uniswap");
+        assert_eq!((reported.fee_tier, reported.fee_estimated), (Some(0.0005), false));
+
+        let defaulted = pool("uniswap").with_fee_tier(None).with_conventional_fee("uniswap_v2");
+        assert_eq!((defaulted.fee_tier, defaulted.fee_estimated), (Some(0.003), true));
+        let pancake = pool("pancakeswap").with_conventional_fee("pancakeswap");
+        assert_eq!(pancake.fee_tier, Some(0.0025));
+
+        // Concentrated-liquidity and unknown DEXes have no single fee to assume
+        for kind in ["uniswap_v3", "orca whirlpool", "raydium-clmm", "somedex"] {
+            let pool = pool("x").with_conventional_fee(kind);
+            assert_eq!((pool.fee_tier, pool.fee_estimated), (None, false), "{kind}");
+        }
+    }
 }
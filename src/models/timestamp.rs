@@ -0,0 +1,104 @@
+use serde::Serialize;
+use super::{ArbitrageAlert, PoolData};
+
+/// Record with a unix-seconds `timestamp`
+pub trait HasTimestamp {
+    fn timestamp(&self) -> i64;
+}
+
+impl HasTimestamp for PoolData {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+impl HasTimestamp for ArbitrageAlert {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+impl<T: HasTimestamp + ?Sized> HasTimestamp for &T {
+    fn timestamp(&self) -> i64 {
+        (**self).timestamp()
+    }
+}
+
+/// API view: the record unchanged plus `timestamp_iso` (RFC3339, UTC).
+/// Stored files keep only the numeric field.
+#[derive(Debug, Serialize)]
+pub struct WithIsoTime<T> {
+    #[serde(flatten)]
+    pub inner: T,
+    pub timestamp_iso: String,
+}
+
+impl<T: HasTimestamp> From<T> for WithIsoTime<T> {
+    fn from(inner: T) -> Self {
+        let timestamp_iso = rfc3339(inner.timestamp());
+        Self { inner, timestamp_iso }
+    }
+}
+
+/// "2025-01-02T03:04:05Z"; empty for out-of-range values
+pub fn rfc3339(epoch_secs: i64) -> String {
+    chrono::DateTime::from_timestamp(epoch_secs, 0)
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+/// Wrap every record for an API response
+pub fn with_iso<T: HasTimestamp>(items: impl IntoIterator<Item = T>) -> Vec<WithIsoTime<T>> {
+    items.into_iter().map(WithIsoTime::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn iso_view_flattens_the_record() {
+        let pool = PoolData { symbol: "ETH".to_string(), price_usd: 3000.0, timestamp: 1_735_787_045, ..PoolData::default() };
+        let value = serde_json::to_value(WithIsoTime::from(&pool)).unwrap();
+
+        assert_eq!(value["timestamp_iso"], "2025-01-02T03:04:05Z");
+        assert_eq!(value["timestamp"], 1_735_787_045);
+        assert_eq!(value["symbol"], "ETH");
+        assert!(value.get("inner").is_none());
+
+        // Apart from the extra field it is the plain record
+        let mut plain = value.as_object().unwrap().clone();
+        plain.remove("timestamp_iso");
+        assert_eq!(serde_json::Value::Object(plain), serde_json::to_value(&pool).unwrap());
+    }
+
+    #[test]
+    fn with_iso_wraps_every_item() {
+        let pools = [PoolData { timestamp: 0, ..PoolData::default() }, PoolData { timestamp: 60, ..PoolData::default() }];
+        let wrapped = with_iso(pools.iter());
+        let iso: Vec<&str> = wrapped.iter().map(|w| w.timestamp_iso.as_str()).collect();
+        assert_eq!(iso, ["1970-01-01T00:00:00Z", "1970-01-01T00:01:00Z"]);
+    }
+
+    #[test]
+    fn iso_string_parses_back_to_the_epoch() {
+        for epoch in [0, 1, 1_700_000_000, 1_735_787_045, 4_102_444_800] {
+            let parsed = chrono::DateTime::parse_from_rfc3339(&rfc3339(epoch)).unwrap();
+            assert_eq!(parsed.timestamp(), epoch);
+            assert_eq!(parsed.offset().local_minus_utc(), 0, "UTC");
+        }
+        assert_eq!(rfc3339(i64::MAX), "");
+    }
+
+    #[test]
+    fn alerts_get_the_same_iso_field() {
+        let low = PoolData { symbol: "ETH".to_string(), price_usd: 3000.0, ..PoolData::default() };
+        let high = PoolData { price_usd: 3100.0, ..low.clone() };
+        let alert = ArbitrageAlert { timestamp: 1_735_787_045, ..ArbitrageAlert::from_pools(&low, &high) };
+
+        let value = serde_json::to_value(WithIsoTime::from(&alert)).unwrap();
+        assert_eq!(value["timestamp"], 1_735_787_045);
+        assert_eq!(value["timestamp_iso"], "2025-01-02T03:04:05Z");
+        assert_eq!(value["symbol"], "ETH");
+    }
+}
@@ -0,0 +1,231 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::models::{cmp_desc_nan_last, PoolData};
+
+/// How several pool prices for one symbol are collapsed into one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PriceAggregation {
+    Mean,
+    /// Robust to a single bad pool
+    #[default]
+    Median,
+    /// Volume-weighted; falls back to the mean without volume data
+    Vwap,
+}
+
+impl FromStr for PriceAggregation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mean" | "avg" => Ok(PriceAggregation::Mean),
+            "median" => Ok(PriceAggregation::Median),
+            "vwap" => Ok(PriceAggregation::Vwap),
+            other => Err(format!("unknown aggregation '{}' (mean|median|vwap)", other)),
+        }
+    }
+}
+
+impl PriceAggregation {
+    pub fn label(&self) -> &'static str {
+        match self {
+            PriceAggregation::Mean => "평균",
+            PriceAggregation::Median => "중앙값",
+            PriceAggregation::Vwap => "VWAP",
+        }
+    }
+
+    /// Aggregate `(price, volume)` samples; non-finite/non-positive prices are ignored
+    pub fn apply(&self, samples: &[(f64, f64)]) -> Option<f64> {
+        let valid: Vec<(f64, f64)> = samples.iter()
+            .copied()
+            .filter(|(price, _)| price.is_finite() && *price > 0.0)
+            .collect();
+        if valid.is_empty() {
+            return None;
+        }
+
+        match self {
+            PriceAggregation::Mean => Some(mean(&valid)),
+            PriceAggregation::Median => Some(median(&valid)),
+            PriceAggregation::Vwap => {
+                let volume: f64 = valid.iter()
+                    .map(|(_, v)| v.max(0.0))
+                    .filter(|v| v.is_finite())
+                    .sum();
+                if volume > 0.0 {
+                    let weighted: f64 = valid.iter()
+                        .filter(|(_, v)| v.is_finite())
+                        .map(|(p, v)| p * v.max(0.0))
+                        .sum();
+                    Some(weighted / volume)
+                } else {
+                    Some(mean(&valid))
+                }
+            }
+        }
+    }
+}
+
+/// One representative price per symbol: `volume_24h`-weighted mean of the pool
+/// prices (zero-volume pools carry no weight). A symbol whose pools all lack
+/// volume falls back to the plain mean, as [`PriceAggregation::Vwap`] does.
+pub fn vwap<P: Borrow<PoolData>>(pools: &[P]) -> HashMap<String, f64> {
+    let mut samples: HashMap<&str, Vec<(f64, f64)>> = HashMap::new();
+    for pool in pools {
+        let pool = pool.borrow();
+        samples.entry(pool.symbol.as_str()).or_default().push((pool.price_usd, pool.volume_24h));
+    }
+
+    samples.into_iter()
+        .filter_map(|(symbol, samples)| {
+            PriceAggregation::Vwap.apply(&samples).map(|price| (symbol.to_string(), price))
+        })
+        .collect()
+}
+
+/// CEX price vs DEX VWAP of one symbol ("kimchi premium" for Upbit)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Premium {
+    pub symbol: String,
+    pub upbit_price_usd: f64,
+    pub dex_vwap_usd: f64,
+    /// (upbit - dex) / dex * 100
+    pub premium_pct: f64,
+}
+
+/// Premium of every symbol priced on both sides, highest first; symbols missing
+/// (or non-positive) on either side are omitted
+pub fn premiums(cex_prices: &HashMap<String, f64>, dex_vwap: &HashMap<String, f64>) -> Vec<Premium> {
+    let mut premiums: Vec<Premium> = cex_prices.iter()
+        .filter_map(|(symbol, &cex)| {
+            let dex = *dex_vwap.get(symbol)?;
+            let usable = |p: f64| p.is_finite() && p > 0.0;
+            (usable(cex) && usable(dex)).then(|| Premium {
+                symbol: symbol.clone(),
+                upbit_price_usd: cex,
+                dex_vwap_usd: dex,
+                premium_pct: (cex - dex) / dex * 100.0,
+            })
+        })
+        .collect();
+    premiums.sort_by(|a, b| cmp_desc_nan_last(a.premium_pct, b.premium_pct)
+        .then_with(|| a.symbol.cmp(&b.symbol)));
+    premiums
+}
+
+fn mean(samples: &[(f64, f64)]) -> f64 {
+    samples.iter().map(|(p, _)| p).sum::<f64>() / samples.len() as f64
+}
+
+fn median(samples: &[(f64, f64)]) -> f64 {
+    let mut prices: Vec<f64> = samples.iter().map(|(p, _)| *p).collect();
+    prices.sort_by(|a, b| a.total_cmp(b));
+    let mid = prices.len() / 2;
+    if prices.len().is_multiple_of(2) {
+        (prices[mid - 1] + prices[mid]) / 2.0
+    } else {
+        prices[mid]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    /// Four pools near $3000 and one reporting $30000
+    const WITH_OUTLIER: [(f64, f64); 5] = [(2990.0, 1.0), (3000.0, 1.0), (3010.0, 1.0), (3020.0, 1.0), (30_000.0, 1.0)];
+
+    #[test]
+    fn median_ignores_an_outlier_that_skews_the_mean() {
+        assert_eq!(PriceAggregation::Median.apply(&WITH_OUTLIER), Some(3010.0));
+        assert_eq!(PriceAggregation::Mean.apply(&WITH_OUTLIER), Some(8404.0));
+        // Even count: midpoint of the two middle prices
+        assert_eq!(PriceAggregation::Median.apply(&WITH_OUTLIER[..4]), Some(3005.0));
+    }
+
+    #[test]
+    fn vwap_without_volume_is_the_mean() {
+        let no_volume = WITH_OUTLIER.map(|(price, _)| (price, 0.0));
+        assert_eq!(PriceAggregation::Vwap.apply(&no_volume), PriceAggregation::Mean.apply(&no_volume));
+        assert_eq!(PriceAggregation::Vwap.apply(&no_volume), Some(8404.0));
+    }
+
+    #[test]
+    fn unusable_prices_are_ignored() {
+        let samples = [(f64::NAN, 1.0), (-1.0, 1.0), (0.0, 1.0), (3000.0, 1.0)];
+        for aggregation in [PriceAggregation::Mean, PriceAggregation::Median, PriceAggregation::Vwap] {
+            assert_eq!(aggregation.apply(&samples), Some(3000.0), "{aggregation:?}");
+            assert_eq!(aggregation.apply(&samples[..3]), None, "{aggregation:?}");
+        }
+    }
+
+    #[test]
+    fn parses_names_and_defaults_to_median() {
+        assert_eq!("MEAN".parse::<PriceAggregation>(), Ok(PriceAggregation::Mean));
+        assert_eq!("avg".parse::<PriceAggregation>(), Ok(PriceAggregation::Mean));
+        assert_eq!("vwap".parse::<PriceAggregation>(), Ok(PriceAggregation::Vwap));
+        assert!("mode".parse::<PriceAggregation>().is_err());
+        assert_eq!(PriceAggregation::default(), PriceAggregation::Median);
+    }
+
+    fn pool(symbol: &str, price_usd: f64, volume_24h: f64) -> PoolData {
+        test_util::pool(symbol, "test", price_usd, 0).with_volume(volume_24h)
+    }
+
+    #[test]
+    fn vwap_weights_by_volume_and_skips_zero_volume_pools() {
+        let pools = [
+            pool("ETH", 3000.0, 300_000.0),
+            pool("ETH", 3100.0, 100_000.0),
+            // No volume: no weight, even though it is far off
+            pool("ETH", 9000.0, 0.0),
+            pool("USDC", 1.0, 5.0),
+        ];
+        let prices = vwap(&pools);
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices["ETH"], 3025.0);
+        assert_eq!(prices["USDC"], 1.0);
+    }
+
+    #[test]
+    fn vwap_falls_back_to_the_mean_without_volume() {
+        let pools = [pool("BTC", 60_000.0, 0.0), pool("BTC", 62_000.0, 0.0), pool("BTC", 64_000.0, 0.0)];
+        assert_eq!(vwap(&pools)["BTC"], 62_000.0);
+        // Symbols with no usable price at all are left out
+        assert!(vwap(&[pool("DEAD", 0.0, 0.0)]).is_empty());
+    }
+
+    fn prices(entries: &[(&str, f64)]) -> HashMap<String, f64> {
+        entries.iter().map(|(symbol, price)| (symbol.to_string(), *price)).collect()
+    }
+
+    #[test]
+    fn premium_is_the_upbit_markup_over_the_dex_vwap() {
+        let upbit = prices(&[("ETH", 3150.0), ("BTC", 58_800.0), ("XRP", 0.6)]);
+        let dex = prices(&[("ETH", 3000.0), ("BTC", 60_000.0), ("SOL", 150.0)]);
+
+        let premiums = premiums(&upbit, &dex);
+        let got: Vec<(&str, f64, f64, f64)> = premiums.iter()
+            .map(|p| (p.symbol.as_str(), p.upbit_price_usd, p.dex_vwap_usd, p.premium_pct))
+            .collect();
+        // Sorted by premium, highest first; XRP and SOL are one-sided
+        assert_eq!(got.len(), 2);
+        assert_eq!((got[0].0, got[0].1, got[0].2), ("ETH", 3150.0, 3000.0));
+        assert!((got[0].3 - 5.0).abs() < 1e-9, "{}", got[0].3);
+        assert_eq!(got[1].0, "BTC");
+        assert!((got[1].3 + 2.0).abs() < 1e-9, "a discount is negative: {}", got[1].3);
+    }
+
+    #[test]
+    fn premiums_skip_unusable_prices() {
+        let upbit = prices(&[("ETH", 3150.0), ("BTC", f64::NAN), ("SOL", 150.0)]);
+        let dex = prices(&[("ETH", 0.0), ("BTC", 60_000.0), ("SOL", 148.5)]);
+        let symbols: Vec<String> = premiums(&upbit, &dex).into_iter().map(|p| p.symbol).collect();
+        assert_eq!(symbols, ["SOL"]);
+    }
+}
@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use parking_lot::{Mutex, RwLock};
+use tokio::sync::broadcast;
+use crate::models::{ArbitrageAlert, PoolData};
+
+/// Relative price change below which a pool isn't re-sent
+const PRICE_EPSILON: f64 = 1e-6;
+
+/// Pool that left the cache since the previous update
+pub struct RemovedPool {
+    /// `chain:pool_address`
+    pub key: String,
+    pub symbol: String,
+}
+
+/// One /ws update cycle, built once and shared by every connection
+pub struct WsUpdate {
+    /// Full cache snapshot (initial connect / resync)
+    pub pools: Vec<Arc<PoolData>>,
+    /// New pools or prices that moved since the previous update
+    pub updated: Vec<Arc<PoolData>>,
+    pub removed: Vec<RemovedPool>,
+    /// Already tagged and filtered to websocket-routed rungs
+    pub alerts: Vec<ArbitrageAlert>,
+}
+
+/// Fan-out of cache snapshots to /ws connections. The latest update is kept
+/// so new (or lagging) clients get data immediately instead of waiting a cycle.
+pub struct UpdateBroadcaster {
+    tx: broadcast::Sender<Arc<WsUpdate>>,
+    latest: RwLock<Option<Arc<WsUpdate>>>,
+    /// dedup key -> (price, symbol) as of the previous publish
+    previous: Mutex<HashMap<String, (f64, String)>>,
+}
+
+impl UpdateBroadcaster {
+    pub fn new(capacity: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity.max(1));
+        Self {
+            tx,
+            latest: RwLock::new(None),
+            previous: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Diff against the previous publish and send to every subscriber;
+    /// returns how many received it
+    pub fn publish(&self, pools: Vec<Arc<PoolData>>, alerts: Vec<ArbitrageAlert>) -> usize {
+        let mut previous = self.previous.lock();
+        let (updated, removed) = diff(&previous, &pools);
+        *previous = pools.iter()
+            .map(|p| (p.dedup_key(), (p.price_usd, p.symbol.clone())))
+            .collect();
+        drop(previous);
+
+        let update = Arc::new(WsUpdate { pools, updated, removed, alerts });
+        *self.latest.write() = Some(update.clone());
+        self.tx.send(update).unwrap_or(0)
+    }
+
+    pub fn latest(&self) -> Option<Arc<WsUpdate>> {
+        self.latest.read().clone()
+    }
+
+    /// Latest update (if any) plus a receiver for the following ones
+    pub fn subscribe(&self) -> (Option<Arc<WsUpdate>>, broadcast::Receiver<Arc<WsUpdate>>) {
+        // Subscribe first so nothing published in between is missed
+        let rx = self.tx.subscribe();
+        (self.latest(), rx)
+    }
+}
+
+/// Pools that appeared or moved beyond [`PRICE_EPSILON`], and keys that disappeared
+fn diff(previous: &HashMap<String, (f64, String)>, pools: &[Arc<PoolData>]) -> (Vec<Arc<PoolData>>, Vec<RemovedPool>) {
+    let mut seen = std::collections::HashSet::with_capacity(pools.len());
+    let updated = pools.iter()
+        .filter(|pool| {
+            let key = pool.dedup_key();
+            let changed = match previous.get(&key) {
+                Some((old, _)) => price_moved(*old, pool.price_usd),
+                None => true,
+            };
+            seen.insert(key);
+            changed
+        })
+        .cloned()
+        .collect();
+
+    let removed = previous.iter()
+        .filter(|(key, _)| !seen.contains(*key))
+        .map(|(key, (_, symbol))| RemovedPool { key: key.clone(), symbol: symbol.clone() })
+        .collect();
+    (updated, removed)
+}
+
+fn price_moved(old: f64, new: f64) -> bool {
+    if old == new {
+        return false;
+    }
+    if old.is_nan() || new.is_nan() {
+        return true;
+    }
+    (old - new).abs() > old.abs().max(new.abs()) * PRICE_EPSILON
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pool;
+
+    #[tokio::test]
+    async fn every_subscriber_gets_the_same_update() {
+        let broadcaster = UpdateBroadcaster::new(4);
+        let (_, mut first) = broadcaster.subscribe();
+        let (_, mut second) = broadcaster.subscribe();
+
+        let delivered = broadcaster.publish(vec![pool("ETH", "a", 3000.0, 1).shared(), pool("BTC", "a", 60_000.0, 1).shared()], Vec::new());
+        assert_eq!(delivered, 2);
+
+        let (a, b) = (first.recv().await.unwrap(), second.recv().await.unwrap());
+        assert!(Arc::ptr_eq(&a, &b), "one update built once and shared");
+        assert_eq!(a.pools.len(), 2);
+
+        // Late joiners start from the latest update
+        let (latest, _) = broadcaster.subscribe();
+        assert!(Arc::ptr_eq(&latest.unwrap(), &a));
+    }
+
+    #[test]
+    fn delta_holds_only_what_changed() {
+        let broadcaster = UpdateBroadcaster::new(4);
+        let eth = pool("ETH", "a", 3000.0, 1).shared();
+        let btc = pool("BTC", "a", 60_000.0, 1).shared();
+        let sol = pool("SOL", "a", 150.0, 1).shared();
+        broadcaster.publish(vec![eth.clone(), btc.clone(), sol.clone()], Vec::new());
+        let first = broadcaster.latest().unwrap();
+        assert_eq!(first.updated.len(), 3, "everything is new on the first publish");
+
+        // ETH moves, BTC jitters below the epsilon, SOL is unchanged
+        let eth_moved = Arc::new(PoolData { price_usd: 3010.0, ..(*eth).clone() });
+        let btc_jitter = Arc::new(PoolData { price_usd: 60_000.0 * (1.0 + PRICE_EPSILON / 10.0), ..(*btc).clone() });
+        broadcaster.publish(vec![eth_moved, btc_jitter, sol.clone()], Vec::new());
+        let update = broadcaster.latest().unwrap();
+        assert_eq!(update.pools.len(), 3, "full snapshot still carried for late joiners");
+        assert_eq!(update.updated.len(), 1);
+        assert_eq!((update.updated[0].symbol.as_str(), update.updated[0].price_usd), ("ETH", 3010.0));
+        assert!(update.removed.is_empty());
+
+        // SOL disappears
+        broadcaster.publish(vec![eth.clone(), btc.clone()], Vec::new());
+        let update = broadcaster.latest().unwrap();
+        let removed: Vec<(&str, &str)> = update.removed.iter().map(|r| (r.key.as_str(), r.symbol.as_str())).collect();
+        assert_eq!(removed, [(sol.dedup_key().as_str(), "SOL")]);
+        assert_eq!(update.updated.len(), 1, "ETH moved back");
+    }
+}
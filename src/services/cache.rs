@@ -1,40 +1,187 @@
 use parking_lot::RwLock;
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
 use std::time::{Duration, Instant};
+use serde::Serialize;
 use crate::models::PoolData;
+use super::reconcile::pool_identity;
+
+/// Capacity sentinel for caches without a size cap
+const UNBOUNDED: usize = usize::MAX;
+
+struct CacheEntry {
+    pool: Arc<PoolData>,
+    /// Insertion sequence number (newer = larger)
+    seq: u64,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<String, CacheEntry>,
+    /// Keys in insertion order; stale (re-inserted/removed) keys are skipped lazily
+    order: VecDeque<(u64, String)>,
+    next_seq: u64,
+}
+
+impl CacheInner {
+    /// Evict the oldest-inserted entries until the cap is satisfied
+    fn evict_to(&mut self, max_entries: usize) -> usize {
+        let mut evicted = 0;
+        while self.entries.len() > max_entries {
+            let Some((seq, key)) = self.order.pop_front() else { break };
+            if self.entries.get(&key).map(|e| e.seq) == Some(seq) {
+                self.entries.remove(&key);
+                evicted += 1;
+            }
+        }
+        evicted
+    }
+
+    /// Rebuild the order queue once stale keys dominate it
+    fn compact_order(&mut self) {
+        if self.order.len() <= self.entries.len() * 2 + 64 {
+            return;
+        }
+        let mut live: Vec<(u64, String)> = self.entries.iter()
+            .map(|(key, entry)| (entry.seq, key.clone()))
+            .collect();
+        live.sort_unstable_by_key(|(seq, _)| *seq);
+        self.order = live.into();
+    }
+}
+
+/// Lookup counters and current size
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CacheStats {
+    pub hits: usize,
+    pub misses: usize,
+    pub entries: usize,
+}
 
 pub struct PoolCache {
-    cache: Arc<RwLock<HashMap<String, Arc<PoolData>>>>,
+    cache: Arc<RwLock<CacheInner>>,
     ttl: Duration,
+    max_entries: usize,
     last_cleanup: Arc<RwLock<Instant>>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+    /// Bumped on every mutation (cheap change detection for consumers)
+    generation: AtomicU64,
 }
 
 impl PoolCache {
     pub fn new(ttl_seconds: u64) -> Self {
+        Self::with_capacity(ttl_seconds, UNBOUNDED)
+    }
+
+    /// Cache with a hard cap; the oldest-inserted entries are evicted first
+    pub fn with_capacity(ttl_seconds: u64, max_entries: usize) -> Self {
         Self {
-            cache: Arc::new(RwLock::new(HashMap::new())),
+            cache: Arc::new(RwLock::new(CacheInner::default())),
             ttl: Duration::from_secs(ttl_seconds),
+            max_entries: max_entries.max(1),
             last_cleanup: Arc::new(RwLock::new(Instant::now())),
+            hits: AtomicUsize::new(0),
+            misses: AtomicUsize::new(0),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Cache key for a pool: one entry per on-chain pool across sources,
+    /// per-source for aggregator quotes without a pool address
+    pub fn key_for(pool: &PoolData) -> String {
+        match pool_identity(pool) {
+            Some(identity) => identity,
+            None => format!("{}:{}:{}", pool.source, pool.chain, pool.pool_address),
         }
     }
 
+    /// Current generation; unchanged generation means unchanged contents
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    fn bump_generation(&self) {
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    /// Maximum number of entries (`usize::MAX` when unbounded)
+    pub fn capacity(&self) -> usize {
+        self.max_entries
+    }
+
     /// Zero-copy retrieval - returns Arc clone (pointer only)
     pub fn get(&self, key: &str) -> Option<Arc<PoolData>> {
-        let cache = self.cache.read();
-        cache.get(key).cloned()
+        let found = self.cache.read().entries.get(key).map(|e| e.pool.clone());
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Hit/miss counters (lock-free) plus entry count
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.len(),
+        }
     }
 
     /// Single insertion
     pub fn insert(&self, key: String, pool: PoolData) {
         let mut cache = self.cache.write();
-        cache.insert(key, Arc::new(pool));
+        self.insert_locked(&mut cache, key, pool);
+        cache.compact_order();
+        self.bump_generation();
+    }
+
+    /// Batch insertion under a single write lock
+    pub fn insert_many(&self, pools: impl IntoIterator<Item = (String, PoolData)>) {
+        let mut cache = self.cache.write();
+        for (key, pool) in pools {
+            self.insert_locked(&mut cache, key, pool);
+        }
+        cache.compact_order();
+        self.bump_generation();
+    }
+
+    fn insert_locked(&self, cache: &mut CacheInner, key: String, pool: PoolData) {
+        let seq = cache.next_seq;
+        cache.next_seq += 1;
+
+        cache.entries.insert(key.clone(), CacheEntry { pool: Arc::new(pool), seq });
+        cache.order.push_back((seq, key));
+
+        let evicted = cache.evict_to(self.max_entries);
+        if evicted > 0 {
+            tracing::debug!("🧹 Evicted {} pools (capacity {})", evicted, self.max_entries);
+        }
+    }
+
+    pub fn remove(&self, key: &str) -> Option<Arc<PoolData>> {
+        // Stale order slot is skipped lazily on eviction/compaction
+        let removed = self.cache.write().entries.remove(key).map(|e| e.pool);
+        if removed.is_some() {
+            self.bump_generation();
+        }
+        removed
+    }
+
+    pub fn clear(&self) {
+        let mut cache = self.cache.write();
+        cache.entries.clear();
+        cache.order.clear();
+        self.bump_generation();
     }
 
     /// Zero-copy all pools
     pub fn get_all(&self) -> Vec<Arc<PoolData>> {
         let cache = self.cache.read();
-        cache.values().cloned().collect()
+        cache.entries.values().map(|e| e.pool.clone()).collect()
     }
 
     /// Smart cleanup (only when needed)
@@ -45,27 +192,250 @@ impl PoolCache {
         }
 
         let now = Instant::now();
+        let now_ts = chrono::Utc::now().timestamp();
+        let ttl_secs = self.ttl.as_secs() as i64;
         let mut cache = self.cache.write();
-        let before = cache.len();
-
-        cache.retain(|_, pool| {
-            let age = now.duration_since(
-                Instant::now() - Duration::from_secs(
-                    now.elapsed().as_secs().saturating_sub(pool.timestamp as u64)
-                )
-            );
-            age < self.ttl
-        });
-
-        let removed = before - cache.len();
+        let before = cache.entries.len();
+
+        // pool.timestamp is unix epoch seconds
+        cache.entries.retain(|_, entry| now_ts - entry.pool.timestamp < ttl_secs);
+        cache.compact_order();
+
+        let removed = before - cache.entries.len();
         if removed > 0 {
+            self.bump_generation();
             tracing::info!("🧹 Cleaned {} expired pools", removed);
         }
 
         *last_cleanup = now;
     }
 
+    /// Write all cached pools to a single JSON file (temp file + rename)
+    pub fn dump_to(&self, path: &Path) -> std::io::Result<usize> {
+        let pools: Vec<PoolData> = {
+            let cache = self.cache.read();
+            let mut entries: Vec<&CacheEntry> = cache.entries.values().collect();
+            entries.sort_unstable_by_key(|e| e.seq);
+            entries.into_iter().map(|e| (*e.pool).clone()).collect()
+        };
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        let writer = BufWriter::new(File::create(&tmp)?);
+        serde_json::to_writer(writer, &pools)?;
+        fs::rename(&tmp, path)?;
+
+        Ok(pools.len())
+    }
+
+    /// Restore pools saved by `dump_to`, skipping ones already past TTL.
+    /// A missing or corrupt file only logs a warning and leaves the cache as is.
+    pub fn load_from(&self, path: &Path) -> usize {
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) => {
+                tracing::warn!("⚠️ Cache snapshot {} not loaded: {}", path.display(), e);
+                return 0;
+            }
+        };
+
+        let pools: Vec<PoolData> = match serde_json::from_reader(BufReader::new(file)) {
+            Ok(pools) => pools,
+            Err(e) => {
+                tracing::warn!("⚠️ Cache snapshot {} is corrupt: {}", path.display(), e);
+                return 0;
+            }
+        };
+
+        let now_ts = chrono::Utc::now().timestamp();
+        let ttl_secs = self.ttl.as_secs() as i64;
+        let fresh: Vec<(String, PoolData)> = pools.into_iter()
+            .filter(|pool| now_ts - pool.timestamp < ttl_secs)
+            .map(|pool| (Self::key_for(&pool), pool))
+            .collect();
+        let loaded = fresh.len();
+        if loaded > 0 {
+            self.insert_many(fresh);
+        }
+        loaded
+    }
+
     pub fn len(&self) -> usize {
-        self.cache.read().len()
+        self.cache.read().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pool;
+
+    fn aged(symbol: &str, age_secs: i64) -> PoolData {
+        pool(symbol, "test", 1.0, chrono::Utc::now().timestamp() - age_secs)
+    }
+
+    /// Pretend the last cleanup ran long enough ago for `cleanup_if_needed` to act
+    fn make_cleanup_due(cache: &PoolCache) {
+        *cache.last_cleanup.write() = Instant::now()
+            .checked_sub(Duration::from_secs(61))
+            .expect("monotonic clock older than a minute");
+    }
+
+    #[test]
+    fn cleanup_removes_pool_past_ttl() {
+        let cache = PoolCache::new(300);
+        cache.insert("old".to_string(), aged("OLD", 3600));
+        make_cleanup_due(&cache);
+        cache.cleanup_if_needed();
+        assert!(cache.get("old").is_none());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn cleanup_keeps_fresh_pool() {
+        let cache = PoolCache::new(300);
+        cache.insert("old".to_string(), aged("OLD", 301));
+        cache.insert("fresh".to_string(), aged("FRESH", 10));
+        make_cleanup_due(&cache);
+        cache.cleanup_if_needed();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.get("fresh").unwrap().symbol, "FRESH");
+    }
+
+    #[test]
+    fn cleanup_waits_for_its_interval() {
+        let cache = PoolCache::new(300);
+        cache.insert("old".to_string(), aged("OLD", 3600));
+        cache.cleanup_if_needed();
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn capacity_evicts_oldest_inserted_first() {
+        let cache = PoolCache::with_capacity(300, 3);
+        assert_eq!(cache.capacity(), 3);
+        for key in ["a", "b", "c"] {
+            cache.insert(key.to_string(), aged(key, 0));
+        }
+        // Re-inserting "a" makes it the newest, so "b" is now the oldest
+        cache.insert("a".to_string(), aged("a", 0));
+        cache.insert("d".to_string(), aged("d", 0));
+        cache.insert_many([("e".to_string(), aged("e", 0))]);
+
+        assert_eq!(cache.len(), 3);
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("c").is_none());
+        for key in ["a", "d", "e"] {
+            assert!(cache.get(key).is_some(), "{key} evicted");
+        }
+    }
+
+    #[test]
+    fn unbounded_cache_reports_sentinel_capacity() {
+        assert_eq!(PoolCache::new(300).capacity(), usize::MAX);
+    }
+
+    #[test]
+    fn ttl_cleanup_still_works_with_capacity_cap() {
+        let cache = PoolCache::with_capacity(300, 2);
+        cache.insert("old".to_string(), aged("OLD", 3600));
+        cache.insert("fresh".to_string(), aged("FRESH", 0));
+        make_cleanup_due(&cache);
+        cache.cleanup_if_needed();
+        assert_eq!(cache.len(), 1);
+
+        // The freed slot is reusable without evicting the survivor
+        cache.insert("next".to_string(), aged("NEXT", 0));
+        assert!(cache.get("fresh").is_some());
+        assert!(cache.get("next").is_some());
+    }
+
+    #[test]
+    fn dump_and_load_round_trip_skips_expired() {
+        let dir = crate::test_util::temp_dir("cache-snapshot");
+        let path = dir.join("cache.json");
+
+        let cache = PoolCache::new(300);
+        let mut with_fee = aged("ETH", 5);
+        with_fee.fee_tier = Some(0.0005);
+        let pools = [with_fee, aged("BTC", 10), aged("OLD", 3600)];
+        cache.insert_many(pools.iter().map(|p| (PoolCache::key_for(p), p.clone())));
+        assert_eq!(cache.dump_to(&path).unwrap(), 3);
+
+        let restored = PoolCache::new(300);
+        assert_eq!(restored.load_from(&path), 2);
+        for pool in &pools[..2] {
+            assert_eq!(restored.get(&PoolCache::key_for(pool)).as_deref(), Some(pool));
+        }
+        assert!(restored.get(&PoolCache::key_for(&pools[2])).is_none());
+    }
+
+    #[test]
+    fn missing_or_corrupt_snapshot_leaves_cache_empty() {
+        let dir = crate::test_util::temp_dir("cache-corrupt");
+        let cache = PoolCache::new(300);
+        assert_eq!(cache.load_from(&dir.join("absent.json")), 0);
+
+        let corrupt = dir.join("corrupt.json");
+        fs::write(&corrupt, "[{\"symbol\": ").unwrap();
+        assert_eq!(cache.load_from(&corrupt), 0);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn stats_count_hits_and_misses() {
+        let cache = PoolCache::new(300);
+        cache.insert("eth".to_string(), aged("ETH", 0));
+        cache.insert("btc".to_string(), aged("BTC", 0));
+        for _ in 0..3 {
+            assert!(cache.get("eth").is_some());
+        }
+        assert!(cache.get("btc").is_some());
+        assert!(cache.get("sol").is_none());
+        assert!(cache.get("").is_none());
+        // Bulk reads don't count as lookups
+        cache.get_all();
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (4, 2, 2));
+    }
+
+    #[test]
+    fn generation_advances_on_mutations_only() {
+        let cache = PoolCache::new(300);
+        let mut last = cache.generation();
+        let mut advanced = |cache: &PoolCache| {
+            let now = cache.generation();
+            let moved = now > last;
+            last = now;
+            moved
+        };
+
+        cache.insert("a".to_string(), aged("A", 0));
+        assert!(advanced(&cache));
+        cache.insert_many([("b".to_string(), aged("B", 0)), ("c".to_string(), aged("C", 0))]);
+        assert!(advanced(&cache));
+
+        cache.get("a");
+        cache.get("missing");
+        cache.get_all();
+        cache.stats();
+        cache.len();
+        assert!(!advanced(&cache));
+
+        assert!(cache.remove("a").is_some());
+        assert!(advanced(&cache));
+        // Removing something absent changes nothing
+        assert!(cache.remove("a").is_none());
+        assert!(!advanced(&cache));
+
+        cache.clear();
+        assert!(advanced(&cache));
     }
 }
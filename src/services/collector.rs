@@ -1,19 +1,42 @@
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use dashmap::DashMap;
+use parking_lot::{Mutex, RwLock};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use tokio::sync::Semaphore;
+use futures::future::join_all;
 use futures::stream::{self, StreamExt};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use crate::models::PoolData;
 use crate::sources::{
-    PoolSource, 
+    PoolSource, http,
     gecko::GeckoTerminal, 
-    aggregators::{DexScreenerSource, MatchaSource},
+    aggregators::{DexScreenerSource, DexScreenerTokenSource, MatchaSource},
     meta_agg::{self, OpenOceanDirectSource, ParaSwapDirectSource},
+    dexguru::DexGuruSource,
+    uniswap_v3::UniswapV3Source,
+    jupiter::JupiterSource,
 };
+use crate::config::Config;
 use super::{PoolCache, PoolFilter};
+use super::new_pools::NewPoolTracker;
+use super::reconcile::{self, pool_identity, Claim, SourcePriority};
 
 const MAX_RETRIES: usize = 3;
 
+/// Default per-symbol budget for one source call (`[collector] fetch_timeout_secs`)
+const DEFAULT_FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default in-flight limits (`[concurrency]`)
+const DEFAULT_SYMBOL_CONCURRENCY: usize = 20;
+const DEFAULT_SOURCE_CONCURRENCY: usize = 20;
+
+/// Latest call durations kept per source for the latency summary
+const LATENCY_SAMPLES: usize = 512;
+
+/// (symbol, pools or failure) per symbol from one source
+type FetchResults = Vec<(String, Result<Vec<PoolData>, ()>)>;
+
 /// Collection statistics for monitoring
 #[derive(Default)]
 pub struct CollectorStats {
@@ -21,9 +44,93 @@ pub struct CollectorStats {
     pub successful: AtomicUsize,
     pub failed: AtomicUsize,
     pub pools_collected: AtomicUsize,
+    /// source name -> that source's counters
+    pub per_source: DashMap<&'static str, SourceStat>,
+}
+
+/// Counters for a single source
+#[derive(Default)]
+pub struct SourceStat {
+    pub requests: AtomicUsize,
+    pub successful: AtomicUsize,
+    pub failed: AtomicUsize,
+    pub pools: AtomicUsize,
+    /// Durations of the latest `fetch_pools` calls
+    pub latency: Mutex<LatencyWindow>,
+}
+
+/// Ring buffer of the latest `LATENCY_SAMPLES` call durations
+#[derive(Debug, Default)]
+pub struct LatencyWindow {
+    samples: VecDeque<Duration>,
+}
+
+impl LatencyWindow {
+    pub fn record(&mut self, elapsed: Duration) {
+        if self.samples.len() == LATENCY_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(elapsed);
+    }
+
+    /// None before the first call
+    pub fn summary(&self) -> Option<LatencySummary> {
+        let mut ms: Vec<f64> = self.samples.iter().map(|d| d.as_secs_f64() * 1000.0).collect();
+        if ms.is_empty() {
+            return None;
+        }
+        ms.sort_by(|a, b| a.total_cmp(b));
+        let p95 = ((ms.len() as f64 * 0.95).ceil() as usize).clamp(1, ms.len()) - 1;
+        Some(LatencySummary {
+            samples: ms.len(),
+            min_ms: ms[0],
+            avg_ms: ms.iter().sum::<f64>() / ms.len() as f64,
+            p95_ms: ms[p95],
+            max_ms: ms[ms.len() - 1],
+        })
+    }
+}
+
+/// Call latency over the sampled window, in milliseconds
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct LatencySummary {
+    pub samples: usize,
+    pub min_ms: f64,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub max_ms: f64,
+}
+
+/// Point-in-time copy of a `SourceStat`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SourceStatSnapshot {
+    pub source: &'static str,
+    pub requests: usize,
+    pub successful: usize,
+    pub failed: usize,
+    pub pools: usize,
+    pub latency: Option<LatencySummary>,
+}
+
+impl CollectorStats {
+    /// Per-source counters sorted by source name
+    pub fn source_snapshots(&self) -> Vec<SourceStatSnapshot> {
+        let mut snapshots: Vec<SourceStatSnapshot> = self.per_source.iter()
+            .map(|entry| SourceStatSnapshot {
+                source: entry.key(),
+                requests: entry.requests.load(Ordering::Relaxed),
+                successful: entry.successful.load(Ordering::Relaxed),
+                failed: entry.failed.load(Ordering::Relaxed),
+                pools: entry.pools.load(Ordering::Relaxed),
+                latency: entry.latency.lock().summary(),
+            })
+            .collect();
+        snapshots.sort_by_key(|s| s.source);
+        snapshots
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct CollectorResult {
     pub total: usize,
     pub successful: usize,
@@ -33,117 +140,342 @@ pub struct CollectorResult {
 pub struct PoolCollector {
     sources: Vec<Arc<dyn PoolSource>>,
     cache: Arc<PoolCache>,
-    filter: PoolFilter,
+    /// Swappable at runtime (config reload)
+    filter: RwLock<PoolFilter>,
+    /// Symbol fetches in flight across all sources
     semaphore: Arc<Semaphore>,
+    symbol_concurrency: usize,
+    /// Symbols one source fetches at a time
+    source_concurrency: usize,
+    /// Budget per (source, symbol) call; timed-out calls are retried
+    fetch_timeout: Duration,
     stats: Arc<CollectorStats>,
+    /// source name -> unix time of its last successful fetch
+    last_updates: DashMap<&'static str, i64>,
+    new_pools: Mutex<NewPoolTracker>,
+    priority: SourcePriority,
+    /// Uppercased symbol whitelist (empty = all) / blacklist
+    include: HashSet<String>,
+    exclude: HashSet<String>,
+}
+
+/// Built-in sources in priority order (names as returned by `PoolSource::name`)
+const DEFAULT_SOURCES: [&str; 6] = ["DexScreener", "GeckoTerminal", "Matcha", "OpenOcean", "ParaSwap", "Jupiter"];
+
+/// Built-in sources that stay off unless `[sources]` sets them to true
+const OPT_IN_SOURCES: [&str; 1] = ["DexScreenerTokens"];
+
+/// Built-in sources that pass `enabled`, sharing one client and token cache
+fn default_sources(token_cache: &meta_agg::TokenCache, enabled: impl Fn(&str) -> bool) -> Vec<Arc<dyn PoolSource>> {
+    let client = http::shared_client();
+
+    DEFAULT_SOURCES.iter()
+        .filter(|name| enabled(name))
+        .map(|name| -> Arc<dyn PoolSource> {
+            match *name {
+                "DexScreener" => Arc::new(DexScreenerSource::with_client(client.clone())),
+                "GeckoTerminal" => Arc::new(GeckoTerminal::with_client(client.clone())),
+                "Matcha" => {
+                    let mut matcha = MatchaSource::with_client(client.clone());
+                    matcha.set_token_cache(token_cache.clone());
+                    Arc::new(matcha)
+                }
+                "OpenOcean" => Arc::new(OpenOceanDirectSource::with_client(client.clone(), token_cache.clone())),
+                "ParaSwap" => Arc::new(ParaSwapDirectSource::with_client(client.clone(), token_cache.clone())),
+                "Jupiter" => Arc::new(JupiterSource::with_client(client.clone())),
+                other => unreachable!("no constructor for default source {other}"),
+            }
+        })
+        .collect()
 }
 
 impl PoolCollector {
     pub fn new(cache: Arc<PoolCache>, filter: PoolFilter) -> Self {
-        let token_cache = meta_agg::new_token_cache();
-        
-        // Sources in priority order: DexScreener → GeckoTerminal → Matcha → OpenOcean → ParaSwap
+        Self::with_sources(default_sources(&meta_agg::new_token_cache(), |_| true), cache, filter)
+    }
+
+    /// Collector over exactly `sources` (custom or mock sources, a subset, ...)
+    pub fn with_sources(sources: Vec<Arc<dyn PoolSource>>, cache: Arc<PoolCache>, filter: PoolFilter) -> Self {
         Self {
-            sources: vec![
-                Arc::new(DexScreenerSource::new()),
-                Arc::new(GeckoTerminal::new()),
-                Arc::new(MatchaSource::new()),
-                Arc::new(OpenOceanDirectSource::new(token_cache.clone())),
-                Arc::new(ParaSwapDirectSource::new(token_cache.clone())),
-            ],
+            sources,
             cache,
-            filter,
-            semaphore: Arc::new(Semaphore::new(20)),
+            filter: RwLock::new(filter),
+            semaphore: Arc::new(Semaphore::new(DEFAULT_SYMBOL_CONCURRENCY)),
+            symbol_concurrency: DEFAULT_SYMBOL_CONCURRENCY,
+            source_concurrency: DEFAULT_SOURCE_CONCURRENCY,
+            fetch_timeout: DEFAULT_FETCH_TIMEOUT,
             stats: Arc::new(CollectorStats::default()),
+            last_updates: DashMap::new(),
+            new_pools: Mutex::new(NewPoolTracker::new(600)),
+            priority: SourcePriority::default(),
+            include: HashSet::new(),
+            exclude: HashSet::new(),
+        }
+    }
+
+    /// Default sources plus the ones that need config/API keys; key-dependent
+    /// sources without a key are left out instead of failing every request
+    /// Sources switched off in `[sources]` are never constructed.
+    pub fn new_with_config(cache: Arc<PoolCache>, filter: PoolFilter, config: &Config) -> Self {
+        let known: Vec<&str> = DEFAULT_SOURCES.iter().chain(&OPT_IN_SOURCES).copied().chain(["DexGuru", "UniswapV3"]).collect();
+        config.sources.warn_unknown(&known);
+
+        let token_cache = meta_agg::new_token_cache();
+        let sources = default_sources(&token_cache, |name| config.sources.is_enabled(name));
+        let mut collector = Self::with_sources(sources, cache, filter);
+        if config.sources.is_opted_in("DexScreenerTokens") {
+            collector.add_source(Arc::new(DexScreenerTokenSource::with_client(http::shared_client(), token_cache.clone())));
+            tracing::info!("✓ DexScreenerTokens source enabled (token address lookup)");
+        }
+        if config.dexguru.enabled && config.sources.is_enabled("DexGuru") {
+            match DexGuruSource::with_client(http::shared_client(), &config.dexguru, &config.api_keys, token_cache) {
+                Some(source) => {
+                    collector.add_source(Arc::new(source));
+                    tracing::info!("✓ DexGuru source enabled ({} chains)", config.dexguru.chains.len());
+                }
+                None => tracing::warn!("⚠️ DexGuru enabled but no [api_keys] configured, source skipped"),
+            }
+        }
+        if config.uniswap_v3.enabled && config.sources.is_enabled("UniswapV3") {
+            match UniswapV3Source::with_client(http::shared_client(), &config.uniswap_v3, &config.api_keys) {
+                Some(source) => {
+                    tracing::info!("✓ Uniswap V3 subgraph source enabled ({})", source.chains().join(", "));
+                    collector.add_source(Arc::new(source));
+                }
+                None => tracing::warn!("⚠️ Uniswap V3 enabled but no subgraph is reachable (thegraph key), source skipped"),
+            }
         }
+        collector
+    }
+
+    /// Replace the pool filter for subsequent cycles
+    pub fn set_filter(&self, filter: PoolFilter) {
+        *self.filter.write() = filter;
+    }
+
+    /// Registered sources in polling order
+    pub fn sources(&self) -> &[Arc<dyn PoolSource>] {
+        &self.sources
+    }
+
+    /// Names of the polled sources, in polling order
+    pub fn active_sources(&self) -> Vec<&'static str> {
+        self.sources.iter().map(|s| s.name()).collect()
+    }
+
+    /// Register an additional source (polled after the defaults)
+    pub fn add_source(&mut self, source: Arc<dyn PoolSource>) {
+        self.sources.push(source);
+    }
+
+    /// In-flight limits: symbol fetches across all sources, and per source (each at least 1)
+    pub fn set_concurrency(&mut self, symbol_concurrency: usize, source_concurrency: usize) {
+        self.symbol_concurrency = symbol_concurrency.max(1);
+        self.source_concurrency = source_concurrency.max(1);
+        self.semaphore = Arc::new(Semaphore::new(self.symbol_concurrency));
+    }
+
+    pub fn symbol_concurrency(&self) -> usize {
+        self.symbol_concurrency
+    }
+
+    pub fn source_concurrency(&self) -> usize {
+        self.source_concurrency
+    }
+
+    /// Budget for one source call (at least 1ms)
+    pub fn set_fetch_timeout(&mut self, timeout: Duration) {
+        self.fetch_timeout = timeout.max(Duration::from_millis(1));
+    }
+
+    /// Per-symbol cooldown for new-pool alerts
+    pub fn set_new_pool_cooldown(&mut self, cooldown_secs: u64) {
+        self.new_pools.get_mut().set_cooldown(cooldown_secs);
+    }
+
+    /// Per-chain preferred source ordering used to drop redundant pools
+    pub fn set_source_priority(&mut self, priority: SourcePriority) {
+        self.priority = priority;
+    }
+
+    /// Symbol whitelist/blacklist (case-insensitive)
+    pub fn set_symbol_rules(&mut self, include: &[String], exclude: &[String]) {
+        self.include = include.iter().map(|s| s.to_uppercase()).collect();
+        self.exclude = exclude.iter().map(|s| s.to_uppercase()).collect();
+    }
+
+    /// Symbols left after applying `include` (when non-empty) and `exclude`
+    pub fn effective_symbols(&self, symbols: &[String]) -> Vec<String> {
+        symbols.iter()
+            .filter(|s| {
+                let upper = s.to_uppercase();
+                (self.include.is_empty() || self.include.contains(&upper))
+                    && !self.exclude.contains(&upper)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Fetch every symbol from one source (parallel per symbol, with retry)
+    async fn fetch_source(&self, source: &Arc<dyn PoolSource>, symbols: &[String]) -> (FetchResults, Duration) {
+        let start = Instant::now();
+        let results = stream::iter(symbols.iter().cloned())
+            .map(|symbol| {
+                let source = source.clone();
+                let semaphore = self.semaphore.clone();
+                
+                async move {
+                    let _permit = semaphore.acquire().await.unwrap();
+                    
+                    // Transient errors are retried inside the source; only timeouts retry here
+                    for attempt in 0..MAX_RETRIES {
+                        // Budget covers rate-limit queueing and the source's own retries
+                        let call_start = Instant::now();
+                        let result = tokio::time::timeout(
+                            self.fetch_timeout,
+                            source.fetch_pools(&symbol)
+                        ).await;
+                        let call_elapsed = call_start.elapsed();
+                        tracing::debug!("{} {} took {:.0}ms", source.name(), symbol, call_elapsed.as_secs_f64() * 1000.0);
+                        self.stats.per_source.entry(source.name()).or_default()
+                            .latency.lock().record(call_elapsed);
+
+                        match result {
+                            Ok(Ok(pools)) => return (symbol, Ok(pools)),
+                            Ok(Err(_)) => break,
+                            Err(_) => {
+                                if attempt < MAX_RETRIES - 1 {
+                                    tokio::time::sleep(Duration::from_millis(500)).await;
+                                }
+                            }
+                        }
+                    }
+                    (symbol, Err(()))
+                }
+            })
+            .buffer_unordered(self.source_concurrency)
+            .collect()
+            .await;
+        (results, start.elapsed())
     }
 
-    /// Collect data from all sources sequentially with retry
+    /// Collect data from all sources with retry. Sources are fetched concurrently
+    /// (cycle time ≈ slowest source instead of the sum; the shared semaphore still
+    /// bounds in-flight requests), then merged in priority order so dedup stays deterministic.
+    /// The OpenOcean/ParaSwap token cache is pre-loaded from disk, so no source depends on another.
     pub async fn collect_all(&self, symbols: &[String]) -> CollectorResult {
+        let symbols = &self.effective_symbols(symbols)[..];
         let total_pools = Arc::new(AtomicUsize::new(0));
         let successful = Arc::new(AtomicUsize::new(0));
         let failed = Arc::new(AtomicUsize::new(0));
 
+        // pool identity -> report kept this cycle (duplicates across sources collapse into one)
+        let mut claimed: HashMap<String, Claim> = HashMap::new();
+
         println!("\n📊 데이터 수집 시작 ({} 심볼)", symbols.len());
         println!("─────────────────────────────────────────");
 
-        // Process each source sequentially
-        for source in self.sources.iter() {
+        println!("\n🔍 {}개 소스 동시 조회 중...", self.sources.len());
+        let fetched = join_all(self.sources.iter().map(|source| self.fetch_source(source, symbols))).await;
+
+        // Merge in source order
+        for (source, (results, elapsed)) in self.sources.iter().zip(fetched) {
             let source_name = source.name();
-            println!("\n🔍 {} 조회 중...", source_name);
-            
-            let start = std::time::Instant::now();
             let mut source_pools = 0usize;
             let mut source_failed = 0usize;
-
-            // Process symbols in parallel for this source
-            let results: Vec<(String, Result<Vec<PoolData>, ()>)> = stream::iter(symbols.iter().cloned())
-                .map(|symbol| {
-                    let source = source.clone();
-                    let semaphore = self.semaphore.clone();
-                    
-                    async move {
-                        let _permit = semaphore.acquire().await.unwrap();
-                        
-                        // Retry logic
-                        for attempt in 0..MAX_RETRIES {
-                            match tokio::time::timeout(
-                                Duration::from_secs(10),
-                                source.fetch_pools(&symbol)
-                            ).await {
-                                Ok(Ok(pools)) => return (symbol, Ok(pools)),
-                                Ok(Err(_)) | Err(_) => {
-                                    if attempt < MAX_RETRIES - 1 {
-                                        tokio::time::sleep(Duration::from_millis(500)).await;
-                                    }
-                                }
-                            }
-                        }
-                        (symbol, Err(()))
-                    }
-                })
-                .buffer_unordered(20)
-                .collect()
-                .await;
+            let mut source_redundant = 0usize;
 
             // Process results
-            for (symbol, result) in results {
+            let stat = self.stats.per_source.entry(source_name).or_default();
+            for (_symbol, result) in results {
+                stat.requests.fetch_add(1, Ordering::Relaxed);
                 match result {
                     Ok(pools) => {
-                        let filtered: Vec<_> = pools.into_iter()
-                            .filter(|p| self.filter.is_valid(p))
-                            .collect();
+                        let filtered: Vec<_> = {
+                            let filter = self.filter.read();
+                            pools.into_iter().filter(|p| filter.is_valid(p)).collect()
+                        };
                         
+                        let now = chrono::Utc::now().timestamp();
                         for pool in filtered {
-                            let key = format!("{}:{}:{}", pool.source, pool.chain, pool.pool_address);
+                            let key = PoolCache::key_for(&pool);
+                            if !self.claim(&mut claimed, &pool) {
+                                source_redundant += 1;
+                                continue;
+                            }
+                            if let Some(alert) = self.new_pools.lock().observe(&key, &pool, now) {
+                                tracing::info!(
+                                    "🆕 New pool: {} {} {} ({}) via {}{}",
+                                    alert.symbol, alert.pool.dex, alert.pool.chain,
+                                    alert.pool.pool_address, alert.pool.source,
+                                    if alert.coalesced > 0 {
+                                        format!(" +{} more during cooldown", alert.coalesced)
+                                    } else {
+                                        String::new()
+                                    }
+                                );
+                            }
                             self.cache.insert(key, pool);
                             source_pools += 1;
                         }
                         successful.fetch_add(1, Ordering::Relaxed);
+                        stat.successful.fetch_add(1, Ordering::Relaxed);
                     }
                     Err(_) => {
                         source_failed += 1;
                         failed.fetch_add(1, Ordering::Relaxed);
+                        stat.failed.fetch_add(1, Ordering::Relaxed);
                     }
                 }
             }
+            stat.pools.fetch_add(source_pools, Ordering::Relaxed);
+            drop(stat);
 
             total_pools.fetch_add(source_pools, Ordering::Relaxed);
+
+            if source_failed < symbols.len() {
+                self.last_updates.insert(source_name, chrono::Utc::now().timestamp());
+            }
             
-            let elapsed = start.elapsed();
-            println!("   ✓ {} - {}개 풀 ({} 실패) [{:.2}초]",
-                source_name, source_pools, source_failed, elapsed.as_secs_f64());
+            if source_redundant > 0 {
+                println!("   ✓ {} - {}개 풀 ({} 실패, {} 중복 제외) [{:.2}초]",
+                    source_name, source_pools, source_failed, source_redundant, elapsed.as_secs_f64());
+            } else {
+                println!("   ✓ {} - {}개 풀 ({} 실패) [{:.2}초]",
+                    source_name, source_pools, source_failed, elapsed.as_secs_f64());
+            }
         }
 
+        self.new_pools.lock().finish_cycle();
+
         let total = total_pools.load(Ordering::Relaxed);
+        let (ok, err) = (successful.load(Ordering::Relaxed), failed.load(Ordering::Relaxed));
+        self.stats.total_requests.fetch_add(ok + err, Ordering::Relaxed);
+        self.stats.successful.fetch_add(ok, Ordering::Relaxed);
+        self.stats.failed.fetch_add(err, Ordering::Relaxed);
+        self.stats.pools_collected.fetch_add(total, Ordering::Relaxed);
         println!("\n─────────────────────────────────────────");
         println!("✅ 완료: 총 {}개 풀 수집", total);
 
         CollectorResult {
             total,
-            successful: successful.load(Ordering::Relaxed),
-            failed: failed.load(Ordering::Relaxed),
+            successful: ok,
+            failed: err,
+        }
+    }
+
+    /// Resolve a pool reported by several sources (source priority, then richest data).
+    /// Returns false when the report already kept this cycle wins.
+    fn claim(&self, claimed: &mut HashMap<String, Claim>, pool: &PoolData) -> bool {
+        let Some(identity) = pool_identity(pool) else { return true };
+
+        if let Some(kept) = claimed.get(&identity) {
+            if !reconcile::should_replace(&self.priority, kept, pool) {
+                return false;
+            }
         }
+        claimed.insert(identity, Claim::of(pool));
+        true
     }
 
     /// Get all cached pools
@@ -151,8 +483,350 @@ impl PoolCollector {
         self.cache.get_all()
     }
 
+    /// Last successful fetch time per source
+    pub fn source_last_updates(&self) -> Vec<(&'static str, i64)> {
+        self.last_updates.iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
     /// Get collection statistics
     pub fn get_stats(&self) -> Arc<CollectorStats> {
         self.stats.clone()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FilterConfig;
+    use crate::sources::mock::MockSource;
+    use crate::sources::SourceError;
+    use crate::test_util::{self, symbols};
+
+    fn test_filter_config() -> FilterConfig {
+        FilterConfig {
+            min_lp: 1_000.0,
+            min_volume: 100.0,
+            allow_zero_liquidity: true,
+            per_chain: HashMap::new(),
+        }
+    }
+
+    fn filter() -> PoolFilter {
+        PoolFilter::new(&test_filter_config())
+    }
+
+    fn collector(sources: Vec<Arc<dyn PoolSource>>) -> PoolCollector {
+        PoolCollector::with_sources(sources, Arc::new(PoolCache::new(300)), filter())
+    }
+
+    #[test]
+    fn effective_symbols_apply_include_and_exclude() {
+        let upbit = symbols(&["BTC", "eth", "XRP", "DOGE", "SHIB"]);
+
+        let mut collector = collector(Vec::new());
+        assert_eq!(collector.effective_symbols(&upbit), upbit);
+
+        collector.set_symbol_rules(&[], &symbols(&["doge", "Shib"]));
+        assert_eq!(collector.effective_symbols(&upbit), ["BTC", "eth", "XRP"]);
+
+        collector.set_symbol_rules(&symbols(&["ETH", "xrp", "SOL"]), &[]);
+        assert_eq!(collector.effective_symbols(&upbit), ["eth", "XRP"]);
+
+        // Exclude wins over include
+        collector.set_symbol_rules(&symbols(&["ETH", "XRP"]), &symbols(&["XRP"]));
+        assert_eq!(collector.effective_symbols(&upbit), ["eth"]);
+    }
+
+    fn pool(address: &str, price_usd: f64, lp: f64, volume: f64) -> PoolData {
+        test_util::pool("ETH", "test", price_usd, 0).at_address(address).with_lp(lp).with_volume(volume)
+    }
+
+    #[tokio::test]
+    async fn same_pool_from_three_sources_is_kept_once() {
+        // Gecko has no liquidity data (allowed: zero LP and volume); Kyber has LP only;
+        // ParaSwap has both and wins. Address case differs per source.
+        let sources: Vec<Arc<dyn PoolSource>> = vec![
+            Arc::new(MockSource::new("Gecko").with_pools("ETH", vec![pool("0xPOOL", 3000.0, 0.0, 0.0)])),
+            Arc::new(MockSource::new("Kyber").with_pools("ETH", vec![pool("0xpool", 3001.0, 50_000.0, 0.0)])),
+            Arc::new(MockSource::new("ParaSwap").with_pools("ETH", vec![
+                pool("0xPool", 3002.0, 50_000.0, 9_000.0),
+                pool("0xother", 2999.0, 80_000.0, 9_000.0),
+            ])),
+        ];
+        let collector = collector(sources);
+        // Kyber's LP-only report is thin on volume: let it through to exercise the ranking
+        collector.set_filter(PoolFilter::new(&FilterConfig { min_volume: 0.0, ..test_filter_config() }));
+
+        collector.collect_all(&symbols(&["ETH"])).await;
+        let mut pools = collector.get_cached_pools();
+        pools.sort_by(|a, b| a.pool_address.cmp(&b.pool_address));
+
+        assert_eq!(pools.len(), 2);
+        assert_eq!(pools[0].pool_address, "0xPool");
+        assert_eq!(pools[0].source, "ParaSwap");
+        assert_eq!(pools[0].price_usd, 3002.0);
+        assert_eq!(pools[1].pool_address, "0xother");
+    }
+
+    fn slow_sources(count: usize, delay: Duration, symbols: &[String]) -> Vec<Arc<dyn PoolSource>> {
+        const NAMES: [&str; 6] = ["DexScreener", "GeckoTerminal", "Matcha", "OpenOcean", "ParaSwap", "Jupiter"];
+        NAMES.iter().take(count)
+            .map(|name| {
+                let source = symbols.iter().enumerate().fold(MockSource::new(name), |source, (i, symbol)| {
+                    let address = format!("0x{name}{i}");
+                    source.with_pools(symbol, vec![PoolData { symbol: symbol.clone(), ..pool(&address, 3000.0, 50_000.0, 9_000.0) }])
+                });
+                Arc::new(source.with_delay(delay)) as Arc<dyn PoolSource>
+            })
+            .collect()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sources_are_fetched_concurrently() {
+        let symbols = symbols(&["ETH", "BTC"]);
+        let collector = collector(slow_sources(3, Duration::from_millis(200), &symbols));
+
+        let started = tokio::time::Instant::now();
+        let result = collector.collect_all(&symbols).await;
+
+        assert_eq!(result.total, 6);
+        assert_eq!(result.successful, 6);
+        // All six calls overlap: one delay, where sequential would be 3 sources x 2 symbols x 200ms
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+    }
+
+    /// Cycle time of 6 sources x 40 symbols at 50ms per call.
+    /// Measured (debug build): ~0.62s concurrent, i.e. 240 calls through the
+    /// 20-permit symbol semaphore, vs 12s for the old one-source-at-a-time loop.
+    #[tokio::test]
+    #[ignore = "timing benchmark; run with --ignored --nocapture"]
+    async fn bench_collect_all_latency() {
+        let symbols: Vec<String> = (0..40).map(|i| format!("SYM{i}")).collect();
+        let delay = Duration::from_millis(50);
+        let collector = collector(slow_sources(6, delay, &symbols));
+
+        let started = Instant::now();
+        let result = collector.collect_all(&symbols).await;
+        let elapsed = started.elapsed();
+        let sequential = delay * (6 * symbols.len()) as u32;
+        println!("collect_all: {} pools in {:?} (sequential equivalent {:?})", result.total, elapsed, sequential);
+        assert!(elapsed < sequential / 10);
+    }
+
+    #[tokio::test]
+    async fn stats_are_kept_per_source() {
+        let sources: Vec<Arc<dyn PoolSource>> = vec![
+            Arc::new(MockSource::new("Good").with_pools("ETH", vec![pool("0x1", 3000.0, 50_000.0, 9_000.0)])),
+            Arc::new(MockSource::failing("Broken", SourceError::Http(500))),
+        ];
+        let collector = collector(sources);
+        let symbols = symbols(&["ETH", "BTC"]);
+
+        collector.collect_all(&symbols).await;
+        collector.collect_all(&symbols).await;
+
+        let snapshots = collector.get_stats().source_snapshots();
+        let by_name: HashMap<&str, &SourceStatSnapshot> = snapshots.iter().map(|s| (s.source, s)).collect();
+        let (broken, good) = (by_name["Broken"], by_name["Good"]);
+        assert_eq!((broken.requests, broken.successful, broken.failed, broken.pools), (4, 0, 4, 0));
+        // BTC has no pools but the call itself succeeded
+        assert_eq!((good.requests, good.successful, good.failed, good.pools), (4, 4, 0, 2));
+        assert_eq!(collector.get_stats().failed.load(Ordering::Relaxed), 4);
+    }
+
+    /// Minimal valid config plus `extra` TOML sections
+    fn config(extra: &str) -> Config {
+        toml::from_str(&format!(r#"
+            [arbitrage]
+            threshold = 0.01
+            update_interval = 30
+            [filter]
+            min_lp = 0
+            min_volume = 0
+            [server]
+            host = "127.0.0.1"
+            port = 8080
+            {extra}
+        "#)).unwrap()
+    }
+
+    fn configured(extra: &str) -> Vec<&'static str> {
+        PoolCollector::new_with_config(Arc::new(PoolCache::new(300)), filter(), &config(extra)).active_sources()
+    }
+
+    #[test]
+    fn key_dependent_sources_need_their_key() {
+        let enabled = "[dexguru]\nenabled = true\n[uniswap_v3]\nenabled = true\n";
+        let without_keys = configured(enabled);
+        assert!(!without_keys.contains(&"DexGuru"), "{without_keys:?}");
+        assert!(!without_keys.contains(&"UniswapV3"), "{without_keys:?}");
+        assert_eq!(without_keys, DEFAULT_SOURCES);
+
+        // Blank keys count as absent
+        let blank = configured(&format!("{enabled}[api_keys]\nzerox = \"  \"\nthegraph = \"\"\n"));
+        assert_eq!(blank, DEFAULT_SOURCES);
+
+        let with_keys = configured(&format!("{enabled}[api_keys]\nzerox = \"zx-key\"\nthegraph = \"tg-key\"\n"));
+        assert!(with_keys.contains(&"DexGuru") && with_keys.contains(&"UniswapV3"), "{with_keys:?}");
+    }
+
+    #[test]
+    fn only_enabled_sources_are_built() {
+        assert_eq!(configured(""), DEFAULT_SOURCES, "everything on by default");
+
+        let subset = "[sources]\nDexScreener = true\ngeckoterminal = true\nMatcha = false\nOpenOcean = false\nParaSwap = false\nJupiter = false\n";
+        assert_eq!(configured(subset), ["DexScreener", "GeckoTerminal"]);
+
+        // Opt-in sources only appear when explicitly enabled
+        assert!(!configured("").contains(&"DexScreenerTokens"));
+        assert_eq!(configured("[sources]\nDexScreenerTokens = true\nJupiter = false\n"),
+            ["DexScreener", "GeckoTerminal", "Matcha", "OpenOcean", "ParaSwap", "DexScreenerTokens"]);
+    }
+
+    #[tokio::test]
+    async fn collected_pools_land_in_the_cache() {
+        let collector = collector(vec![
+            Arc::new(MockSource::new("A").with_pools("ETH", vec![pool("0xa", 3000.0, 50_000.0, 9_000.0)])),
+            Arc::new(MockSource::new("B").with_pools("BTC", vec![
+                PoolData { symbol: "BTC".to_string(), ..pool("0xb", 60_000.0, 80_000.0, 9_000.0) },
+            ])),
+        ]);
+
+        let result = collector.collect_all(&symbols(&["ETH", "BTC"])).await;
+        assert_eq!((result.total, result.successful, result.failed), (2, 4, 0));
+
+        let mut cached: Vec<(String, String)> = collector.get_cached_pools().iter()
+            .map(|p| (p.pool_address.clone(), p.source.clone()))
+            .collect();
+        cached.sort();
+        assert_eq!(cached, [("0xa".to_string(), "A".to_string()), ("0xb".to_string(), "B".to_string())]);
+    }
+
+    #[tokio::test]
+    async fn filter_drops_bad_pools_before_the_cache() {
+        // Filter: min_lp 1,000, min_volume 100, zero liquidity allowed
+        let collector = collector(vec![Arc::new(MockSource::new("A").with_pools("ETH", vec![
+            pool("0xgood", 3000.0, 50_000.0, 9_000.0),
+            pool("0xthin", 3000.0, 500.0, 9_000.0),
+            pool("0xquiet", 3000.0, 50_000.0, 10.0),
+            pool("0xfree", 0.0, 50_000.0, 9_000.0),
+        ]))]);
+
+        let result = collector.collect_all(&symbols(&["ETH"])).await;
+        assert_eq!(result.total, 1);
+        let addresses: Vec<String> = collector.get_cached_pools().iter().map(|p| p.pool_address.clone()).collect();
+        assert_eq!(addresses, ["0xgood"]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_calls_time_out_and_count_as_failures() {
+        let hanging = Arc::new(MockSource::hanging("Stuck"));
+        let mut collector = collector(vec![
+            hanging.clone(),
+            Arc::new(MockSource::new("Good").with_pools("ETH", vec![pool("0x1", 3000.0, 50_000.0, 9_000.0)])),
+        ]);
+        collector.set_fetch_timeout(Duration::from_secs(2));
+
+        let started = tokio::time::Instant::now();
+        let result = collector.collect_all(&symbols(&["ETH"])).await;
+
+        // MAX_RETRIES attempts of 2s, 500ms apart; the healthy source is unaffected
+        assert_eq!(hanging.calls(), MAX_RETRIES);
+        assert_eq!(started.elapsed(), Duration::from_secs(7));
+        assert_eq!((result.total, result.successful, result.failed), (1, 1, 1));
+        let snapshots = collector.get_stats().source_snapshots();
+        let stuck = snapshots.iter().find(|s| s.source == "Stuck").unwrap();
+        assert_eq!((stuck.requests, stuck.failed), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn slow_source_latency_is_recorded() {
+        let collector = collector(vec![
+            Arc::new(MockSource::new("Slow").with_delay(Duration::from_millis(60))),
+            Arc::new(MockSource::new("Fast")),
+        ]);
+        collector.collect_all(&symbols(&["ETH", "BTC"])).await;
+
+        let snapshots = collector.get_stats().source_snapshots();
+        let latency = |name: &str| snapshots.iter().find(|s| s.source == name).unwrap().latency.clone().unwrap();
+        let (slow, fast) = (latency("Slow"), latency("Fast"));
+        assert_eq!((slow.samples, fast.samples), (2, 2));
+        assert!(slow.min_ms >= 60.0, "{slow:?}");
+        assert!(slow.min_ms <= slow.avg_ms && slow.avg_ms <= slow.p95_ms && slow.p95_ms <= slow.max_ms);
+        assert!(fast.max_ms < slow.min_ms, "{fast:?} vs {slow:?}");
+    }
+
+    #[test]
+    fn latency_window_summarizes_and_keeps_the_latest_samples() {
+        let mut window = LatencyWindow::default();
+        assert!(window.summary().is_none());
+        for ms in 1..=20 {
+            window.record(Duration::from_millis(ms));
+        }
+        let summary = window.summary().unwrap();
+        assert_eq!((summary.samples, summary.min_ms, summary.max_ms), (20, 1.0, 20.0));
+        assert_eq!((summary.avg_ms, summary.p95_ms), (10.5, 19.0));
+
+        // Full: the oldest samples fall out
+        for _ in 0..LATENCY_SAMPLES {
+            window.record(Duration::from_millis(100));
+        }
+        let summary = window.summary().unwrap();
+        assert_eq!((summary.samples, summary.min_ms), (LATENCY_SAMPLES, 100.0));
+    }
+
+    #[test]
+    fn concurrency_limits_size_the_semaphore() {
+        let mut collector = collector(Vec::new());
+        assert_eq!((collector.symbol_concurrency(), collector.source_concurrency()), (20, 20));
+        assert_eq!(collector.semaphore.available_permits(), 20);
+
+        collector.set_concurrency(3, 7);
+        assert_eq!((collector.symbol_concurrency(), collector.source_concurrency()), (3, 7));
+        assert_eq!(collector.semaphore.available_permits(), 3);
+
+        // Zero would deadlock every fetch
+        collector.set_concurrency(0, 0);
+        assert_eq!((collector.symbol_concurrency(), collector.source_concurrency()), (1, 1));
+        assert_eq!(collector.semaphore.available_permits(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn one_permit_serializes_every_call() {
+        let symbols = symbols(&["ETH", "BTC"]);
+        let mut collector = collector(slow_sources(2, Duration::from_millis(50), &symbols));
+        collector.set_concurrency(1, 20);
+
+        let started = tokio::time::Instant::now();
+        collector.collect_all(&symbols).await;
+        // 2 sources x 2 symbols, one at a time
+        assert_eq!(started.elapsed(), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn concurrency_section_reaches_the_collector() {
+        let config = config("[concurrency]\nsymbol_concurrency = 4\nsource_concurrency = 2\n");
+        let collector = crate::gatherer::build_collector(&config, Arc::new(PoolCache::new(300)));
+        assert_eq!((collector.symbol_concurrency(), collector.source_concurrency()), (4, 2));
+        assert_eq!(collector.semaphore.available_permits(), 4);
+    }
+
+    #[tokio::test]
+    async fn non_finite_prices_are_dropped_at_ingestion() {
+        let collector = collector(vec![Arc::new(MockSource::new("A").with_pools("ETH", vec![
+            pool("0xnan", f64::NAN, 50_000.0, 9_000.0),
+            pool("0xgood", 3000.0, 50_000.0, 9_000.0),
+            pool("0xinf", f64::INFINITY, 50_000.0, 9_000.0),
+            pool("0xneginf", f64::NEG_INFINITY, 50_000.0, 9_000.0),
+        ]))]);
+
+        let result = collector.collect_all(&symbols(&["ETH"])).await;
+        assert_eq!(result.total, 1);
+        let cached = collector.get_cached_pools();
+        assert_eq!(cached.len(), 1);
+        assert_eq!(cached[0].pool_address, "0xgood");
+        assert!(cached.iter().all(|p| p.is_finite_price()));
+    }
+}
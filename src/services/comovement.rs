@@ -0,0 +1,158 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use parking_lot::Mutex;
+use crate::models::PoolData;
+use super::reconcile;
+
+/// Aligned samples needed before the check can reject a pair
+const MIN_SAMPLES: usize = 4;
+
+/// Relative change below which a pool counts as not moving
+const STATIC_EPSILON: f64 = 1e-9;
+
+/// Per-pool price history (one sample per collection cycle) used to reject
+/// pairs that don't move together, e.g. a stale pool against a live one
+pub struct PriceHistory {
+    window: usize,
+    min_correlation: f64,
+    inner: Mutex<HistoryInner>,
+}
+
+#[derive(Default)]
+struct HistoryInner {
+    cycle: u64,
+    /// pool identity -> (cycle, price), oldest first
+    series: HashMap<String, VecDeque<(u64, f64)>>,
+}
+
+impl PriceHistory {
+    pub fn new(window: usize, min_correlation: f64) -> Self {
+        Self {
+            window: window.max(MIN_SAMPLES),
+            min_correlation,
+            inner: Mutex::new(HistoryInner::default()),
+        }
+    }
+
+    /// Record one cycle of prices; pools unseen for a whole window are dropped
+    pub fn record(&self, pools: &[Arc<PoolData>]) {
+        let mut inner = self.inner.lock();
+        inner.cycle += 1;
+        let cycle = inner.cycle;
+
+        for pool in pools.iter().filter(|p| p.price_usd.is_finite() && p.price_usd > 0.0) {
+            let Some(key) = reconcile::pool_identity(pool) else { continue };
+            let series = inner.series.entry(key).or_default();
+            if series.back().is_some_and(|(c, _)| *c == cycle) {
+                continue;
+            }
+            series.push_back((cycle, pool.price_usd));
+            while series.len() > self.window {
+                series.pop_front();
+            }
+        }
+
+        let oldest = cycle.saturating_sub(self.window as u64);
+        inner.series.retain(|_, s| s.back().is_some_and(|(c, _)| *c > oldest));
+    }
+
+    /// Whether two pools have co-moved enough to be paired. Unknown pools and
+    /// short histories pass so startup isn't blocked.
+    pub fn co_moves(&self, a: &PoolData, b: &PoolData) -> bool {
+        let (Some(key_a), Some(key_b)) = (reconcile::pool_identity(a), reconcile::pool_identity(b)) else {
+            return true;
+        };
+        let inner = self.inner.lock();
+        let (Some(series_a), Some(series_b)) = (inner.series.get(&key_a), inner.series.get(&key_b)) else {
+            return true;
+        };
+
+        // Samples from cycles both pools were seen in
+        let prices_b: HashMap<u64, f64> = series_b.iter().copied().collect();
+        let aligned: Vec<(f64, f64)> = series_a.iter()
+            .filter_map(|(cycle, price)| prices_b.get(cycle).map(|other| (*price, *other)))
+            .collect();
+        if aligned.len() < MIN_SAMPLES {
+            return true;
+        }
+
+        let returns: Vec<(f64, f64)> = aligned.windows(2)
+            .map(|w| (w[1].0 / w[0].0 - 1.0, w[1].1 / w[0].1 - 1.0))
+            .collect();
+        match correlation(&returns) {
+            Some(corr) => corr >= self.min_correlation,
+            // One side never moved
+            None => false,
+        }
+    }
+}
+
+/// Pearson correlation; None when either side has no variance
+fn correlation(samples: &[(f64, f64)]) -> Option<f64> {
+    let n = samples.len() as f64;
+    let mean_a = samples.iter().map(|(a, _)| a).sum::<f64>() / n;
+    let mean_b = samples.iter().map(|(_, b)| b).sum::<f64>() / n;
+
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (a, b) in samples {
+        cov += (a - mean_a) * (b - mean_b);
+        var_a += (a - mean_a).powi(2);
+        var_b += (b - mean_b).powi(2);
+    }
+
+    let min_var = STATIC_EPSILON * STATIC_EPSILON;
+    if var_a <= min_var || var_b <= min_var {
+        return None;
+    }
+    Some(cov / (var_a.sqrt() * var_b.sqrt()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    fn pool(address: &str, price_usd: f64) -> Arc<PoolData> {
+        test_util::pool("ETH", "test", price_usd, 0).on_chain("polygon").at_address(address).shared()
+    }
+
+    /// Record `a[i]`/`b[i]` as one cycle each
+    fn history(a: &[f64], b: &[f64]) -> PriceHistory {
+        let history = PriceHistory::new(20, 0.3);
+        for (pa, pb) in a.iter().zip(b) {
+            history.record(&[pool("0xa", *pa), pool("0xb", *pb)]);
+        }
+        history
+    }
+
+    #[test]
+    fn static_pool_never_co_moves_with_a_live_one() {
+        let history = history(&[1000.0; 6], &[1000.0, 1004.0, 998.0, 1006.0, 1001.0, 1020.0]);
+        assert!(!history.co_moves(&pool("0xa", 1000.0), &pool("0xb", 1020.0)));
+    }
+
+    #[test]
+    fn pools_moving_together_pass() {
+        let history = history(
+            &[1000.0, 1004.0, 998.0, 1006.0, 1001.0, 1003.0],
+            &[1001.0, 1005.5, 999.0, 1008.0, 1002.0, 1020.0],
+        );
+        assert!(history.co_moves(&pool("0xa", 1003.0), &pool("0xb", 1020.0)));
+    }
+
+    #[test]
+    fn short_or_unknown_history_passes() {
+        let history = history(&[1000.0; 3], &[1000.0, 1004.0, 998.0]);
+        assert!(history.co_moves(&pool("0xa", 1000.0), &pool("0xb", 998.0)));
+        assert!(history.co_moves(&pool("0xa", 1000.0), &pool("0xc", 998.0)));
+        // Aggregator quotes have no identity to look up
+        assert!(history.co_moves(&pool("aggregated", 1000.0), &pool("0xb", 998.0)));
+    }
+
+    #[test]
+    fn correlation_of_opposite_moves_is_negative() {
+        let samples = [(0.01, -0.01), (-0.02, 0.02), (0.005, -0.005)];
+        assert!((correlation(&samples).unwrap() + 1.0).abs() < 1e-9);
+        assert_eq!(correlation(&[(0.0, 0.01), (0.0, -0.01)]), None);
+    }
+}
@@ -1,27 +1,250 @@
-use crate::models::{PoolData, ArbitrageAlert, alert::ArbType};
+use crate::config::CostConfig;
+use crate::models::{PoolData, ArbitrageAlert, alert::{self, ArbType}};
+use crate::sources::CexSource;
 use crate::sources::upbit::CexPrice;
-use std::collections::HashMap;
+use super::simulator::CostModel;
+use super::PoolFilter;
+use super::matcher::SymbolMatcher;
+use super::comovement::PriceHistory;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use parking_lot::{Mutex, RwLock};
+
+/// Finite and positive (NaN/inf/zero prices never produce alerts)
+fn is_usable_price(price: f64) -> bool {
+    price.is_finite() && price > 0.0
+}
+
+/// Fewest pools of one symbol for a meaningful median
+const MIN_POOLS_FOR_OUTLIERS: usize = 3;
+
+fn median_price(pools: &[&PoolData]) -> f64 {
+    let mut prices: Vec<f64> = pools.iter().map(|p| p.price_usd).collect();
+    prices.sort_by(|a, b| a.total_cmp(b));
+    let mid = prices.len() / 2;
+    if prices.len().is_multiple_of(2) {
+        (prices[mid - 1] + prices[mid]) / 2.0
+    } else {
+        prices[mid]
+    }
+}
+
+/// Width of the diff bucket (percent) used to tell "the same" opportunity apart
+const COOLDOWN_BUCKET_PCT: f64 = 0.5;
+
+/// Suppresses re-emitting the same opportunity (symbol + type + diff bucket) within a window
+pub struct AlertCooldown {
+    cooldown_secs: i64,
+    last_emitted: Mutex<HashMap<String, i64>>,
+}
+
+impl AlertCooldown {
+    pub fn new(cooldown_secs: u64) -> Self {
+        Self {
+            cooldown_secs: cooldown_secs as i64,
+            last_emitted: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn key(alert: &ArbitrageAlert) -> String {
+        let bucket = (alert.diff_pct / COOLDOWN_BUCKET_PCT).floor() as i64;
+        format!("{}:{:?}:{}", alert.symbol, alert.arb_type, bucket)
+    }
+
+    /// Keep only alerts not emitted within the window, and record them
+    pub fn filter(&self, alerts: Vec<ArbitrageAlert>, now: i64) -> Vec<ArbitrageAlert> {
+        if self.cooldown_secs <= 0 {
+            return alerts;
+        }
+        let mut last_emitted = self.last_emitted.lock();
+        last_emitted.retain(|_, ts| now - *ts < self.cooldown_secs);
+
+        alerts.into_iter()
+            .filter(|alert| {
+                let key = Self::key(alert);
+                if last_emitted.contains_key(&key) {
+                    return false;
+                }
+                last_emitted.insert(key, now);
+                true
+            })
+            .collect()
+    }
+}
 
 pub struct ArbitrageDetector {
-    threshold: f64,
+    /// Swappable at runtime (config reload)
+    threshold: RwLock<f64>,
+    costs: CostModel,
+    /// Alerts whose estimated profit falls below this are suppressed
+    min_profit_usd: f64,
+    matcher: SymbolMatcher,
+    cooldown: AlertCooldown,
+    /// Uppercase symbol -> threshold overriding `threshold`
+    per_symbol: HashMap<String, f64>,
+    /// DEX-DEX pairs must have co-moved (None = check off)
+    comovement: Option<PriceHistory>,
+    /// Pools fetched longer ago than this are ignored (0 = off)
+    max_age_secs: i64,
+    /// Max price ratio to the symbol median before a pool is dropped (0 = off)
+    outlier_factor: f64,
+    outliers_dropped: AtomicU64,
 }
 
 impl ArbitrageDetector {
     pub fn new(threshold: f64) -> Self {
-        Self { threshold }
+        Self::with_costs(threshold, CostModel::new(&CostConfig::default()))
+    }
+
+    /// Detector whose threshold applies to the spread net of fees and gas
+    pub fn with_costs(threshold: f64, costs: CostModel) -> Self {
+        Self {
+            threshold: RwLock::new(threshold),
+            costs,
+            min_profit_usd: 0.0,
+            matcher: SymbolMatcher::default(),
+            cooldown: AlertCooldown::new(0),
+            per_symbol: HashMap::new(),
+            comovement: None,
+            max_age_secs: 0,
+            outlier_factor: 0.0,
+            outliers_dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// Detector that suppresses repeats of the same opportunity within `cooldown_secs`
+    /// (applied by `suppress_repeats`; the `detect_*` methods always return the full view)
+    pub fn with_cooldown(threshold: f64, cooldown_secs: u64) -> Self {
+        let mut detector = Self::new(threshold);
+        detector.set_cooldown(cooldown_secs);
+        detector
+    }
+
+    /// Per-symbol threshold overrides (fractions, like `threshold`)
+    pub fn set_per_symbol_thresholds(&mut self, per_symbol: &HashMap<String, f64>) {
+        self.per_symbol = per_symbol.iter()
+            .map(|(symbol, threshold)| (symbol.to_uppercase(), *threshold))
+            .collect();
+    }
+
+    /// Effective threshold for a symbol
+    pub fn threshold_for(&self, symbol: &str) -> f64 {
+        let threshold = *self.threshold.read();
+        if self.per_symbol.is_empty() {
+            return threshold;
+        }
+        self.per_symbol.get(&symbol.to_uppercase()).copied().unwrap_or(threshold)
+    }
+
+    /// Require DEX-DEX pairs to have co-moved over the recorded history
+    pub fn set_comovement(&mut self, history: PriceHistory) {
+        self.comovement = Some(history);
+    }
+
+    /// Record one collection cycle of prices for the co-movement check
+    pub fn record_prices(&self, pools: &[Arc<PoolData>]) {
+        if let Some(history) = &self.comovement {
+            history.record(pools);
+        }
+    }
+
+    pub fn set_max_age(&mut self, max_age_secs: u64) {
+        self.max_age_secs = max_age_secs as i64;
+    }
+
+    /// Whether a pool's price is too old to compare against live prices
+    pub fn is_stale(&self, pool: &PoolData, now: i64) -> bool {
+        self.max_age_secs > 0 && now - pool.timestamp > self.max_age_secs
+    }
+
+    /// Pools usable for alerts: valid price and not stale
+    fn is_usable(&self, pool: &PoolData, now: i64) -> bool {
+        is_usable_price(pool.price_usd) && !self.is_stale(pool, now)
+    }
+
+    /// Drop pools priced more than `factor`x above or below their symbol's median (0 = off)
+    pub fn set_outlier_factor(&mut self, factor: f64) {
+        self.outlier_factor = factor;
+    }
+
+    /// Pools dropped as price outliers since startup (counted per detection run)
+    pub fn outliers_dropped(&self) -> u64 {
+        self.outliers_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Usable pools grouped by canonical symbol, minus price outliers. Sorted by
+    /// symbol so alerts come out in the same order for the same input.
+    fn usable_by_symbol<'a>(&self, pools: &'a [Arc<PoolData>]) -> BTreeMap<String, Vec<&'a PoolData>> {
+        let mut by_symbol: BTreeMap<String, Vec<&PoolData>> = BTreeMap::new();
+        // Skip NaN/inf/non-positive and stale prices so they can't win the min/max scan
+        let now = chrono::Utc::now().timestamp();
+        for pool in pools.iter().filter(|p| self.is_usable(p, now)) {
+            by_symbol.entry(self.matcher.canonical(pool)).or_default().push(pool.as_ref());
+        }
+
+        if self.outlier_factor > 1.0 {
+            let mut dropped = 0;
+            for (symbol, symbol_pools) in by_symbol.iter_mut() {
+                if symbol_pools.len() < MIN_POOLS_FOR_OUTLIERS {
+                    continue;
+                }
+                let median = median_price(symbol_pools);
+                let before = symbol_pools.len();
+                symbol_pools.retain(|pool| {
+                    let ratio = pool.price_usd / median;
+                    ratio <= self.outlier_factor && ratio >= 1.0 / self.outlier_factor
+                });
+                if symbol_pools.len() < before {
+                    tracing::debug!("{}: dropped {} outlier pools (median ${:.6})", symbol, before - symbol_pools.len(), median);
+                    dropped += before - symbol_pools.len();
+                }
+            }
+            self.outliers_dropped.fetch_add(dropped as u64, Ordering::Relaxed);
+        }
+        by_symbol
+    }
+
+    pub fn set_cooldown(&mut self, cooldown_secs: u64) {
+        self.cooldown = AlertCooldown::new(cooldown_secs);
+    }
+
+    /// Drop alerts already emitted within the cooldown window
+    pub fn suppress_repeats(&self, alerts: Vec<ArbitrageAlert>) -> Vec<ArbitrageAlert> {
+        self.cooldown.filter(alerts, chrono::Utc::now().timestamp())
+    }
+
+    /// Matching rules shared by DEX-DEX grouping and DEX-CEX matching
+    pub fn set_matcher(&mut self, matcher: SymbolMatcher) {
+        self.matcher = matcher;
+    }
+
+    pub fn set_min_profit(&mut self, min_profit_usd: f64) {
+        self.min_profit_usd = min_profit_usd;
+    }
+
+    /// Tradeable size of a pool; pools without LP data assume the reference trade size
+    fn tradeable_usd(&self, pool: &PoolData) -> f64 {
+        if pool.lp_reserve_usd > 0.0 {
+            PoolFilter::calculate_max_trade(pool)
+        } else {
+            self.costs.reference_trade_usd()
+        }
+    }
+
+    /// Gas for the given swap chains at the reference trade size: (USD, percent of trade)
+    fn gas_cost(&self, chains: &[&str]) -> (f64, f64) {
+        let gas_usd: f64 = chains.iter().map(|c| self.costs.gas_usd(c)).sum();
+        let reference = self.costs.reference_trade_usd();
+        let gas_pct = if reference > 0.0 { gas_usd / reference * 100.0 } else { 0.0 };
+        (gas_usd, gas_pct)
     }
 
     /// DEX-DEX arbitrage detection (Arc optimized)
     pub fn detect_dex_dex(&self, pools: &[Arc<PoolData>]) -> Vec<ArbitrageAlert> {
         let mut alerts = Vec::new();
 
-        let mut by_symbol: HashMap<&str, Vec<&PoolData>> = HashMap::new();
-        for pool in pools {
-            by_symbol.entry(&pool.symbol).or_default().push(pool.as_ref());
-        }
-
-        for (_symbol, symbol_pools) in by_symbol {
+        for (symbol, symbol_pools) in self.usable_by_symbol(pools) {
             if symbol_pools.len() < 2 {
                 continue;
             }
@@ -41,44 +264,106 @@ impl ArbitrageDetector {
             if min_pool.price_usd <= 0.0 {
                 continue;
             }
+            // All prices equal (min and max are the same entry) or one pool
+            // reported twice: no spread between two venues
+            if std::ptr::eq(min_pool, max_pool)
+                || (!min_pool.pool_address.is_empty() && min_pool.pool_address.eq_ignore_ascii_case(&max_pool.pool_address)) {
+                continue;
+            }
+
+            // A stale side "diverging" from a live one isn't an opportunity
+            if self.comovement.as_ref().is_some_and(|h| !h.co_moves(min_pool, max_pool)) {
+                continue;
+            }
+
+            // Net spread: gross - both swap fees - gas
+            let mut alert = ArbitrageAlert::from_pools(min_pool, max_pool);
+            let fees_pct = (self.costs.fee_rate(min_pool) + self.costs.fee_rate(max_pool)) * 100.0;
+            let (gas_usd, gas_pct) = self.gas_cost(&[&min_pool.chain, &max_pool.chain]);
+            alert.net_diff_pct = alert.diff_pct - fees_pct - gas_pct;
+            if !alert.net_diff_pct.is_finite() {
+                continue;
+            }
+            alert.estimated_gas_usd = gas_usd;
 
-            let diff_pct = (max_pool.price_usd - min_pool.price_usd) / min_pool.price_usd;
+            // Realized profit is bounded by the thinner pool
+            alert.tradeable_usd = self.tradeable_usd(min_pool).min(self.tradeable_usd(max_pool));
+            alert.estimated_profit_usd = alert.tradeable_usd * (alert.diff_pct - fees_pct) / 100.0 - gas_usd;
 
-            if diff_pct >= self.threshold {
-                alerts.push(ArbitrageAlert::from_pools(min_pool, max_pool));
+            if alert.net_diff_pct >= self.threshold_for(&symbol) * 100.0
+                && alert.estimated_profit_usd >= self.min_profit_usd {
+                alerts.push(alert);
             }
         }
 
         alerts
     }
 
-    /// DEX-CEX arbitrage detection (Arc optimized)
-    pub fn detect_dex_cex(&self, pools: &[Arc<PoolData>], cex_prices: &[CexPrice]) -> Vec<ArbitrageAlert> {
+    /// Max/min spread (percent) of every symbol with 2+ usable pools, regardless of threshold
+    pub fn symbol_gaps(&self, pools: &[Arc<PoolData>]) -> Vec<f64> {
+        let mut range: HashMap<String, (f64, f64, usize)> = HashMap::new();
+        for pool in pools.iter().filter(|p| is_usable_price(p.price_usd)) {
+            let entry = range.entry(self.matcher.canonical(pool)).or_insert((f64::MAX, f64::MIN, 0));
+            entry.0 = entry.0.min(pool.price_usd);
+            entry.1 = entry.1.max(pool.price_usd);
+            entry.2 += 1;
+        }
+
+        range.into_values()
+            .filter(|(_, _, count)| *count >= 2)
+            .map(|(min, max, _)| (max - min) / min * 100.0)
+            .collect()
+    }
+
+    /// DEX-CEX arbitrage detection across every exchange (Arc optimized)
+    pub fn detect_dex_cex(&self, pools: &[Arc<PoolData>], exchanges: &[Arc<dyn CexSource>]) -> Vec<ArbitrageAlert> {
+        if exchanges.is_empty() {
+            return Vec::new();
+        }
+        let by_symbol = self.usable_by_symbol(pools);
+        exchanges.iter()
+            .flat_map(|cex| self.detect_against(&by_symbol, cex.name(), &cex.get_all_prices()))
+            .collect()
+    }
+
+    /// DEX-CEX detection against a single exchange's prices
+    fn detect_against(&self, by_symbol: &BTreeMap<String, Vec<&PoolData>>, exchange: &str, cex_prices: &[CexPrice]) -> Vec<ArbitrageAlert> {
         let mut alerts = Vec::new();
+        let cex_source = exchange.to_lowercase();
 
-        let cex_map: HashMap<&str, &CexPrice> = cex_prices.iter()
-            .map(|p| (p.symbol.as_str(), p))
+        let cex_map: HashMap<String, &CexPrice> = cex_prices.iter()
+            .map(|p| (self.matcher.canonical_symbol(&p.symbol), p))
             .collect();
 
-        for pool in pools {
-            if let Some(cex) = cex_map.get(pool.symbol.as_str()) {
-                if pool.price_usd <= 0.0 || cex.price_usd <= 0.0 {
-                    continue;
-                }
+        for (symbol, symbol_pools) in by_symbol {
+            let Some(cex) = cex_map.get(symbol) else { continue };
+            if !is_usable_price(cex.price_usd) {
+                continue;
+            }
+            for pool in symbol_pools {
 
-                let (low, high, low_source, high_source) = if pool.price_usd < cex.price_usd {
+                let (low, high, low_source, high_source, action) = if pool.price_usd < cex.price_usd {
                     (pool.price_usd, cex.price_usd, 
                      format!("{}:{}", pool.dex, pool.pool_address),
-                     "upbit".to_string())
+                     cex_source.clone(),
+                     alert::trade_action(&alert::dex_venue(pool), exchange))
                 } else {
                     (cex.price_usd, pool.price_usd,
-                     "upbit".to_string(),
-                     format!("{}:{}", pool.dex, pool.pool_address))
+                     cex_source.clone(),
+                     format!("{}:{}", pool.dex, pool.pool_address),
+                     alert::trade_action(exchange, &alert::dex_venue(pool)))
                 };
 
-                let diff_pct = (high - low) / low;
+                let diff_pct = (high - low) / low * 100.0;
+                // Only the DEX leg pays a swap fee and gas
+                let (gas_usd, gas_pct) = self.gas_cost(&[&pool.chain]);
+                let fee_pct = self.costs.fee_rate(pool) * 100.0;
+                let net_diff_pct = diff_pct - fee_pct - gas_pct;
+                let tradeable_usd = self.tradeable_usd(pool);
+                let estimated_profit_usd = tradeable_usd * (diff_pct - fee_pct) / 100.0 - gas_usd;
 
-                if diff_pct >= self.threshold {
+                if net_diff_pct >= self.threshold_for(symbol) * 100.0
+                    && estimated_profit_usd >= self.min_profit_usd {
                     alerts.push(ArbitrageAlert {
                         symbol: pool.symbol.clone(),
                         arb_type: ArbType::DexToCex,
@@ -86,8 +371,14 @@ impl ArbitrageDetector {
                         low_source,
                         high_price: high,
                         high_source,
-                        diff_pct: diff_pct * 100.0,
+                        diff_pct,
+                        action,
+                        net_diff_pct,
+                        estimated_gas_usd: gas_usd,
+                        tradeable_usd,
+                        estimated_profit_usd,
                         timestamp: chrono::Utc::now().timestamp(),
+                        rung: None,
                     });
                 }
             }
@@ -96,8 +387,382 @@ impl ArbitrageDetector {
         alerts
     }
 
-    #[allow(dead_code)]
-    pub fn set_threshold(&mut self, threshold: f64) {
-        self.threshold = threshold;
+    pub fn set_threshold(&self, threshold: f64) {
+        *self.threshold.write() = threshold;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::mock::MockCex;
+    use crate::services::matcher::MatchMode;
+    use crate::test_util;
+
+    /// Fresh ETH pool at a 0.3% fee
+    fn pool(chain: &str, address: &str, price_usd: f64, lp: f64) -> Arc<PoolData> {
+        test_util::pool("ETH", "test", price_usd, chrono::Utc::now().timestamp())
+            .on_chain(chain)
+            .at_address(address)
+            .with_lp(lp)
+            .with_fee_tier(Some(0.003))
+            .shared()
+    }
+
+    /// Threshold in percent; default costs (0.3% fee, per-chain gas at a $1000 reference trade)
+    fn detector(threshold_pct: f64) -> ArbitrageDetector {
+        ArbitrageDetector::new(threshold_pct / 100.0)
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn gross_profitable_spread_that_loses_after_fees_is_dropped() {
+        // 0.5% gross against 0.6% of round-trip fees on cheap-gas Polygon
+        let pools = [pool("polygon", "0xa", 1000.0, 1e6), pool("polygon", "0xb", 1005.0, 1e6)];
+        assert!(detector(0.1).detect_dex_dex(&pools).is_empty());
+
+        // The same 0.5% clears a 0.1% threshold if only the gross spread counted
+        let gaps = detector(0.1).symbol_gaps(&pools);
+        assert!(close(gaps[0], 0.5));
+    }
+
+    #[test]
+    fn mainnet_gas_eats_a_two_percent_spread() {
+        // $15 gas per swap at a $1000 trade = 3%, plus 0.6% fees
+        let pools = [pool("ethereum", "0xa", 1000.0, 1e6), pool("ethereum", "0xb", 1020.0, 1e6)];
+        assert!(detector(0.1).detect_dex_dex(&pools).is_empty());
+    }
+
+    #[test]
+    fn net_spread_subtracts_fees_and_gas() {
+        let pools = [pool("polygon", "0xa", 1000.0, 1e6), pool("polygon", "0xb", 1010.0, 1e6)];
+        let alerts = detector(0.1).detect_dex_dex(&pools);
+        assert_eq!(alerts.len(), 1);
+        let alert = &alerts[0];
+        assert!(close(alert.diff_pct, 1.0));
+        // 1.0 - 0.3 - 0.3 - (2 x $0.05 / $1000 = 0.01%)
+        assert!(close(alert.net_diff_pct, 0.39), "{}", alert.net_diff_pct);
+        assert!(close(alert.estimated_gas_usd, 0.1));
+        assert_eq!(alert.low_source, "uniswap:0xa");
+
+        // A threshold above the net spread (but below gross) suppresses it
+        assert!(detector(0.5).detect_dex_dex(&pools).is_empty());
+    }
+
+    #[test]
+    fn profit_is_bounded_by_the_thinner_pool() {
+        // $10k LP -> $200 tradeable; the $10M side could take $200k
+        for (low_lp, high_lp) in [(10_000.0, 10_000_000.0), (10_000_000.0, 10_000.0)] {
+            let pools = [pool("polygon", "0xa", 1000.0, low_lp), pool("polygon", "0xb", 1020.0, high_lp)];
+            let alerts = detector(0.1).detect_dex_dex(&pools);
+            assert_eq!(alerts.len(), 1);
+            assert!(close(alerts[0].tradeable_usd, 200.0));
+            // $200 x (2% - 0.6% fees) - $0.10 gas
+            assert!(close(alerts[0].estimated_profit_usd, 2.7), "{}", alerts[0].estimated_profit_usd);
+        }
+    }
+
+    #[test]
+    fn profit_floor_suppresses_thin_opportunities() {
+        let thin = [pool("polygon", "0xa", 1000.0, 10_000.0), pool("polygon", "0xb", 1020.0, 10_000_000.0)];
+        let deep = [pool("polygon", "0xa", 1000.0, 1_000_000.0), pool("polygon", "0xb", 1020.0, 10_000_000.0)];
+        let mut detector = detector(0.1);
+        detector.set_min_profit(5.0);
+        assert!(detector.detect_dex_dex(&thin).is_empty());
+        // $20k tradeable x 1.4% - $0.10
+        let alerts = detector.detect_dex_dex(&deep);
+        assert!(close(alerts[0].estimated_profit_usd, 279.9));
+    }
+
+    #[test]
+    fn pool_without_lp_assumes_the_reference_trade() {
+        let pools = [pool("polygon", "0xa", 1000.0, 0.0), pool("polygon", "0xb", 1020.0, 10_000_000.0)];
+        assert!(close(detector(0.1).detect_dex_dex(&pools)[0].tradeable_usd, 1000.0));
+    }
+
+    #[test]
+    fn dex_cex_action_names_the_exchange() {
+        let exchanges: Vec<Arc<dyn CexSource>> = vec![Arc::new(MockCex::new("Upbit", &[("ETH", 1050.0)]))];
+        let cheap_dex = [pool("polygon", "0xa", 1000.0, 1e6)];
+        let alerts = detector(0.1).detect_dex_cex(&cheap_dex, &exchanges);
+        assert_eq!(alerts[0].action, "Buy on uniswap (0xa), sell on Upbit");
+        assert_eq!(alerts[0].high_source, "upbit");
+
+        let dear_dex = [pool("polygon", "0xa", 1100.0, 1e6)];
+        let alerts = detector(0.1).detect_dex_cex(&dear_dex, &exchanges);
+        assert_eq!(alerts[0].action, "Buy on Upbit, sell on uniswap (0xa)");
+    }
+
+    #[test]
+    fn shared_matcher_groups_aliases_for_both_detections() {
+        let weth = Arc::new(PoolData { symbol: "WETH".to_string(), ..(*pool("polygon", "0xa", 1000.0, 1e6)).clone() });
+        let pools = [weth, pool("polygon", "0xb", 1020.0, 1e6)];
+        let exchanges: Vec<Arc<dyn CexSource>> = vec![Arc::new(MockCex::new("Upbit", &[("ETH", 1050.0)]))];
+
+        // Exact matching keeps WETH apart from ETH
+        let exact = detector(0.1);
+        assert!(exact.detect_dex_dex(&pools).is_empty());
+        assert_eq!(exact.detect_dex_cex(&pools, &exchanges).len(), 1);
+
+        let mut alias = detector(0.1);
+        alias.set_matcher(SymbolMatcher::new(&crate::config::MatchingConfig {
+            mode: MatchMode::Alias,
+            ..Default::default()
+        }));
+        assert_eq!(alias.detect_dex_dex(&pools).len(), 1);
+        assert_eq!(alias.detect_dex_cex(&pools, &exchanges).len(), 2);
+    }
+
+    #[test]
+    fn cooldown_suppresses_the_same_opportunity_within_the_window() {
+        let pools = [pool("polygon", "0xa", 1000.0, 1e6), pool("polygon", "0xb", 1010.0, 1e6)];
+        let detector = ArbitrageDetector::with_cooldown(0.001, 60);
+        assert_eq!(detector.suppress_repeats(detector.detect_dex_dex(&pools)).len(), 1);
+        assert!(detector.suppress_repeats(detector.detect_dex_dex(&pools)).is_empty());
+
+        // No cooldown configured: every cycle re-emits
+        let detector = ArbitrageDetector::new(0.001);
+        assert_eq!(detector.suppress_repeats(detector.detect_dex_dex(&pools)).len(), 1);
+        assert_eq!(detector.suppress_repeats(detector.detect_dex_dex(&pools)).len(), 1);
+    }
+
+    #[test]
+    fn cooldown_expires_and_tells_diff_buckets_apart() {
+        let pools = [pool("polygon", "0xa", 1000.0, 1e6), pool("polygon", "0xb", 1010.0, 1e6)];
+        let alerts = detector(0.1).detect_dex_dex(&pools);
+        let cooldown = AlertCooldown::new(30);
+        assert_eq!(cooldown.filter(alerts.clone(), 1_000).len(), 1);
+        assert!(cooldown.filter(alerts.clone(), 1_029).is_empty());
+        // The gap widened into another 0.5% bucket: a new opportunity
+        let wider = vec![ArbitrageAlert { diff_pct: alerts[0].diff_pct + 0.6, ..alerts[0].clone() }];
+        assert_eq!(cooldown.filter(wider, 1_029).len(), 1);
+        assert_eq!(cooldown.filter(alerts, 1_030).len(), 1);
+    }
+
+    fn with_symbol(symbol: &str, pool: Arc<PoolData>) -> Arc<PoolData> {
+        Arc::new(PoolData { symbol: symbol.to_string(), ..(*pool).clone() })
+    }
+
+    #[test]
+    fn per_symbol_thresholds_tighten_and_loosen_the_default() {
+        // Every symbol nets 0.39% on polygon
+        let pools: Vec<Arc<PoolData>> = ["ETH", "BTC", "ARB"].iter()
+            .flat_map(|symbol| [
+                with_symbol(symbol, pool("polygon", &format!("0x{symbol}a"), 1000.0, 1e6)),
+                with_symbol(symbol, pool("polygon", &format!("0x{symbol}b"), 1010.0, 1e6)),
+            ])
+            .collect();
+        let symbols = |alerts: Vec<ArbitrageAlert>| alerts.into_iter().map(|a| a.symbol).collect::<Vec<_>>();
+
+        let mut detector = detector(0.3);
+        assert_eq!(symbols(detector.detect_dex_dex(&pools)), ["ARB", "BTC", "ETH"]);
+
+        // ETH needs more than the default, BTC (lowercase key) less
+        detector.set_per_symbol_thresholds(&HashMap::from([
+            ("ETH".to_string(), 0.005),
+            ("btc".to_string(), 0.001),
+        ]));
+        assert_eq!(symbols(detector.detect_dex_dex(&pools)), ["ARB", "BTC"]);
+        assert!(close(detector.threshold_for("eth"), 0.005));
+        assert!(close(detector.threshold_for("ARB"), 0.003));
+
+        // An override below the default lets an otherwise-filtered symbol through
+        detector.set_threshold(0.005);
+        assert_eq!(symbols(detector.detect_dex_dex(&pools)), ["BTC"]);
+
+        let exchanges: Vec<Arc<dyn CexSource>> = vec![Arc::new(MockCex::new("Upbit", &[("ETH", 1006.0), ("BTC", 1006.0)]))];
+        let cex_pools = [
+            with_symbol("ETH", pool("polygon", "0xe", 1000.0, 1e6)),
+            with_symbol("BTC", pool("polygon", "0xf", 1000.0, 1e6)),
+        ];
+        // 0.6% gross, 0.295% net: only BTC's 0.1% clears
+        assert_eq!(symbols(detector.detect_dex_cex(&cex_pools, &exchanges)), ["BTC"]);
+    }
+
+    #[test]
+    fn absent_per_symbol_map_keeps_the_global_threshold() {
+        let detector = detector(0.3);
+        for symbol in ["ETH", "BTC", ""] {
+            assert!(close(detector.threshold_for(symbol), 0.003));
+        }
+    }
+
+    #[test]
+    fn dex_cex_aggregates_across_exchanges() {
+        let exchanges: Vec<Arc<dyn CexSource>> = vec![
+            Arc::new(MockCex::new("Upbit", &[("ETH", 1050.0), ("BTC", 70_000.0)])),
+            Arc::new(MockCex::new("Binance", &[("ETH", 1040.0), ("SOL", 150.0)])),
+        ];
+        let pools = [pool("polygon", "0xa", 1000.0, 1e6)];
+        let alerts = detector(0.1).detect_dex_cex(&pools, &exchanges);
+        let venues: Vec<(&str, f64)> = alerts.iter().map(|a| (a.high_source.as_str(), a.high_price)).collect();
+        assert_eq!(venues, [("upbit", 1050.0), ("binance", 1040.0)]);
+        assert!(alerts.iter().all(|a| a.arb_type == ArbType::DexToCex && a.symbol == "ETH"));
+
+        // Without exchanges there is nothing to compare against
+        assert!(detector(0.1).detect_dex_cex(&pools, &[]).is_empty());
+    }
+
+    #[test]
+    fn stale_pool_diverging_from_a_live_one_is_not_an_alert() {
+        let mut detector = detector(0.1);
+        detector.set_comovement(PriceHistory::new(20, 0.3));
+        for live in [1000.0, 1004.0, 998.0, 1006.0, 1001.0] {
+            detector.record_prices(&[pool("polygon", "0xstale", 1000.0, 1e6), pool("polygon", "0xlive", live, 1e6)]);
+        }
+        let diverged = [pool("polygon", "0xstale", 1000.0, 1e6), pool("polygon", "0xlive", 1020.0, 1e6)];
+        detector.record_prices(&diverged);
+        assert!(detector.detect_dex_dex(&diverged).is_empty());
+
+        // The same 2% without the check is an alert
+        assert_eq!(ArbitrageDetector::new(0.001).detect_dex_dex(&diverged).len(), 1);
+    }
+
+    fn aged(pool: Arc<PoolData>, age_secs: i64) -> Arc<PoolData> {
+        Arc::new(PoolData { timestamp: pool.timestamp - age_secs, ..(*pool).clone() })
+    }
+
+    #[test]
+    fn expired_pools_are_left_out_of_both_detections() {
+        let mut detector = detector(0.1);
+        detector.set_max_age(60);
+        let exchanges: Vec<Arc<dyn CexSource>> = vec![Arc::new(MockCex::new("Upbit", &[("ETH", 1050.0)]))];
+
+        let fresh = [pool("polygon", "0xa", 1000.0, 1e6), aged(pool("polygon", "0xb", 1020.0, 1e6), 59)];
+        assert_eq!(detector.detect_dex_dex(&fresh).len(), 1);
+        assert_eq!(detector.detect_dex_cex(&fresh, &exchanges).len(), 2);
+
+        let expired = [pool("polygon", "0xa", 1000.0, 1e6), aged(pool("polygon", "0xb", 1020.0, 1e6), 61)];
+        assert!(detector.is_stale(&expired[1], chrono::Utc::now().timestamp()));
+        assert!(detector.detect_dex_dex(&expired).is_empty());
+        // Only the fresh pool is compared against the exchange
+        let alerts = detector.detect_dex_cex(&expired, &exchanges);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].low_source, "uniswap:0xa");
+    }
+
+    #[test]
+    fn max_age_zero_never_marks_pools_stale() {
+        let old = aged(pool("polygon", "0xa", 1000.0, 1e6), 86_400);
+        assert!(!detector(0.1).is_stale(&old, chrono::Utc::now().timestamp()));
+    }
+
+    #[test]
+    fn a_thousandfold_outlier_is_excluded_before_detection() {
+        let pools = [
+            pool("polygon", "0xa", 1000.0, 1e6),
+            pool("polygon", "0xb", 1002.0, 1e6),
+            pool("polygon", "0xc", 1010.0, 1e6),
+            pool("polygon", "0xd", 1015.0, 1e6),
+            // Decimal bug: 1000x the others
+            pool("polygon", "0xbad", 1_000_000.0, 1e6),
+        ];
+
+        // Off by default: the fake spread wins
+        let unfiltered = detector(0.1).detect_dex_dex(&pools);
+        assert_eq!(unfiltered[0].high_source, "uniswap:0xbad");
+
+        let mut filtered = detector(0.1);
+        filtered.set_outlier_factor(10.0);
+        let alerts = filtered.detect_dex_dex(&pools);
+        assert!(!alerts.is_empty());
+        assert!(alerts.iter().all(|a| !a.low_source.ends_with("0xbad") && !a.high_source.ends_with("0xbad")), "{alerts:?}");
+        assert_eq!((alerts[0].low_price, alerts[0].high_price), (1000.0, 1015.0));
+        assert_eq!(filtered.outliers_dropped(), 1);
+    }
+
+    #[test]
+    fn volatile_pools_within_the_factor_are_kept() {
+        // 4x the median is a thin, volatile pool, not a parsing bug
+        let pools = [
+            pool("polygon", "0xa", 1.0, 1e6),
+            pool("polygon", "0xb", 1.1, 1e6),
+            pool("polygon", "0xc", 4.0, 1e6),
+        ];
+        let mut detector = detector(0.1);
+        detector.set_outlier_factor(10.0);
+        let alerts = detector.detect_dex_dex(&pools);
+        assert_eq!(alerts[0].high_source, "uniswap:0xc");
+        assert_eq!(detector.outliers_dropped(), 0);
+    }
+
+    /// Alert fields that identify it (timestamps can tick between two runs)
+    fn identity(alerts: &[ArbitrageAlert]) -> Vec<(String, String, String)> {
+        alerts.iter().map(|a| (a.symbol.clone(), a.low_source.clone(), a.high_source.clone())).collect()
+    }
+
+    #[test]
+    fn alerts_come_out_in_the_same_symbol_order_every_run() {
+        let symbols = ["SOL", "ETH", "ARB", "BTC", "LINK", "UNI", "AAVE", "OP", "PEPE", "MKR"];
+        let pools: Vec<Arc<PoolData>> = symbols.iter().rev()
+            .flat_map(|symbol| [
+                with_symbol(symbol, pool("polygon", &format!("0x{symbol}hi"), 1020.0, 1e6)),
+                with_symbol(symbol, pool("polygon", &format!("0x{symbol}lo"), 1000.0, 1e6)),
+            ])
+            .collect();
+        let exchanges: Vec<Arc<dyn CexSource>> = vec![Arc::new(MockCex::new("Upbit",
+            &symbols.map(|symbol| (symbol, 1050.0))))];
+        let detector = detector(0.1);
+
+        let first = detector.detect_dex_dex(&pools);
+        let mut sorted = symbols.to_vec();
+        sorted.sort();
+        assert_eq!(first.iter().map(|a| a.symbol.as_str()).collect::<Vec<_>>(), sorted);
+        for _ in 0..5 {
+            assert_eq!(identity(&detector.detect_dex_dex(&pools)), identity(&first));
+        }
+
+        let first = detector.detect_dex_cex(&pools, &exchanges);
+        assert_eq!(first.len(), symbols.len() * 2);
+        assert!(first.windows(2).all(|w| w[0].symbol <= w[1].symbol), "{:?}", identity(&first));
+        for _ in 0..5 {
+            assert_eq!(identity(&detector.detect_dex_cex(&pools, &exchanges)), identity(&first));
+        }
+    }
+
+    #[test]
+    fn equal_prices_are_no_spread_even_at_a_zero_threshold() {
+        // No fees or gas, so a zero spread would otherwise pass the zero threshold
+        let costs = CostConfig { default_gas_usd: 0.0, gas_usd: HashMap::new(), ..CostConfig::default() };
+        let mut detector = ArbitrageDetector::with_costs(0.0, CostModel::new(&costs));
+        detector.set_min_profit(f64::MIN);
+        let fee_free = |address: &str, price: f64| Arc::new(PoolData { fee_tier: Some(0.0), ..(*pool("polygon", address, price, 1e6)).clone() });
+
+        let all_equal = [fee_free("0xa", 1000.0), fee_free("0xb", 1000.0), fee_free("0xc", 1000.0)];
+        assert!(detector.detect_dex_dex(&all_equal).is_empty());
+
+        // One pool reported twice (e.g. by two sources) is not two venues
+        let duplicated = [fee_free("0xABC", 1000.0), fee_free("0xabc", 1010.0)];
+        assert!(detector.detect_dex_dex(&duplicated).is_empty());
+
+        let alerts = detector.detect_dex_dex(&[fee_free("0xa", 1000.0), fee_free("0xb", 1000.001)]);
+        assert_eq!(alerts.len(), 1);
+        assert_ne!(alerts[0].low_source, alerts[0].high_source);
+    }
+
+    #[test]
+    fn a_nan_price_neither_panics_nor_becomes_a_side() {
+        let pools = [
+            pool("polygon", "0xnan", f64::NAN, 1e6),
+            pool("polygon", "0xa", 1000.0, 1e6),
+            pool("polygon", "0xinf", f64::INFINITY, 1e6),
+            pool("polygon", "0xb", 1020.0, 1e6),
+        ];
+        let mut detector = detector(0.1);
+        detector.set_outlier_factor(10.0);
+        let alerts = detector.detect_dex_dex(&pools);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!((alerts[0].low_source.as_str(), alerts[0].high_source.as_str()), ("uniswap:0xa", "uniswap:0xb"));
+        assert!(alerts[0].diff_pct.is_finite());
+
+        let gaps = detector.symbol_gaps(&pools);
+        assert_eq!(gaps.len(), 1);
+        assert!(close(gaps[0], 2.0));
+
+        // NaN alone leaves a single usable pool: nothing to compare
+        assert!(detector.detect_dex_dex(&pools[..2]).is_empty());
     }
 }
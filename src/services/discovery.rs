@@ -0,0 +1,220 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+use reqwest::Client;
+use serde::Deserialize;
+use crate::config::CollectorConfig;
+use crate::sources::http;
+
+/// Where the collected symbol universe comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolSource {
+    #[default]
+    Upbit,
+    Dex,
+    Both,
+}
+
+/// Token seen in DEX discovery results
+#[derive(Debug, Clone)]
+pub struct DiscoveredToken {
+    pub symbol: String,
+    pub liquidity_usd: f64,
+    pub volume_24h: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrendingResponse {
+    data: Vec<TrendingPool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrendingPool {
+    attributes: TrendingAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrendingAttributes {
+    name: String,
+    reserve_in_usd: Option<String>,
+    volume_usd: Option<TrendingVolume>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrendingVolume {
+    h24: Option<String>,
+}
+
+/// Seeds symbols from high-liquidity GeckoTerminal pools
+pub struct SymbolDiscovery {
+    client: Client,
+    base_url: String,
+    limit: usize,
+    min_liquidity: f64,
+    pages: u32,
+}
+
+impl SymbolDiscovery {
+    pub fn new(config: &CollectorConfig) -> Self {
+        Self {
+            client: http::shared_client(),
+            base_url: "https://api.geckoterminal.com/api/v2".to_string(),
+            limit: config.discovery_limit,
+            min_liquidity: config.discovery_min_liquidity,
+            pages: config.discovery_pages.max(1),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_base_url(mut self, base: &str) -> Self {
+        self.base_url = base.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fetch trending pools and return the top symbols by volume
+    pub async fn discover(&self) -> Vec<String> {
+        let mut tokens = Vec::new();
+
+        for page in 1..=self.pages {
+            let url = format!("{}/networks/trending_pools?page={}", self.base_url, page);
+            let req = self.client.get(&url)
+                .header("Accept", "application/json")
+                .timeout(Duration::from_secs(10));
+
+            match http::fetch_json::<TrendingResponse>("discovery", req).await {
+                Ok(data) => tokens.extend(data.data.into_iter().filter_map(Self::parse_pool)),
+                Err(e) => {
+                    tracing::warn!("⚠️ Symbol discovery page {} failed: {}", page, e);
+                    break;
+                }
+            }
+        }
+
+        rank_tokens(tokens, self.min_liquidity, self.limit)
+    }
+
+    fn parse_pool(pool: TrendingPool) -> Option<DiscoveredToken> {
+        // "PEPE / WETH 0.3%" -> "PEPE"
+        let base = pool.attributes.name.split('/').next()?.trim().to_uppercase();
+        if base.is_empty() || base.len() > 12 || !base.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+
+        let parse = |s: Option<&String>| s.and_then(|v| v.parse::<f64>().ok()).unwrap_or(0.0);
+        Some(DiscoveredToken {
+            symbol: base,
+            liquidity_usd: parse(pool.attributes.reserve_in_usd.as_ref()),
+            volume_24h: parse(pool.attributes.volume_usd.as_ref().and_then(|v| v.h24.as_ref())),
+        })
+    }
+}
+
+/// Dedup by symbol (keeping the best pool), drop thin ones, rank by volume and cap
+pub fn rank_tokens(tokens: Vec<DiscoveredToken>, min_liquidity: f64, limit: usize) -> Vec<String> {
+    let mut best: HashMap<String, DiscoveredToken> = HashMap::new();
+    for token in tokens {
+        if token.liquidity_usd < min_liquidity {
+            continue;
+        }
+        match best.get(&token.symbol) {
+            Some(existing) if existing.volume_24h >= token.volume_24h => {}
+            _ => {
+                best.insert(token.symbol.clone(), token);
+            }
+        }
+    }
+
+    let mut ranked: Vec<DiscoveredToken> = best.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.volume_24h.total_cmp(&a.volume_24h)
+            .then_with(|| b.liquidity_usd.total_cmp(&a.liquidity_usd))
+            .then_with(|| a.symbol.cmp(&b.symbol))
+    });
+
+    ranked.into_iter().take(limit).map(|t| t.symbol).collect()
+}
+
+/// Combine Upbit and DEX-discovered symbols according to `source`
+pub fn merge_symbols(upbit: &[String], discovered: &[String], source: SymbolSource) -> Vec<String> {
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut merged: Vec<String> = Vec::new();
+    let mut push = |symbol: &String| {
+        let upper = symbol.to_uppercase();
+        if seen.insert(upper.clone()) {
+            merged.push(upper);
+        }
+    };
+
+    match source {
+        SymbolSource::Upbit => upbit.iter().for_each(&mut push),
+        SymbolSource::Dex => discovered.iter().for_each(&mut push),
+        SymbolSource::Both => upbit.iter().chain(discovered.iter()).for_each(&mut push),
+    }
+
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::Query;
+    use axum::routing::get;
+    use crate::test_util::{serve, symbols};
+
+    fn trending(name: &str, reserve: &str, volume: &str) -> serde_json::Value {
+        serde_json::json!({
+            "attributes": { "name": name, "reserve_in_usd": reserve, "volume_usd": { "h24": volume } }
+        })
+    }
+
+    async fn mock_gecko() -> String {
+        let router = axum::Router::new().route("/networks/trending_pools", get(
+            |Query(q): Query<HashMap<String, u32>>| async move {
+                let data = match q.get("page") {
+                    Some(1) => vec![
+                        trending("PEPE / WETH 0.3%", "5000000", "9000000"),
+                        trending("ETH / USDC 0.05%", "90000000", "80000000"),
+                        trending("DUST / WETH", "100", "99999999"),
+                    ],
+                    Some(2) => vec![
+                        trending("WIF / SOL", "3000000", "4000000"),
+                        trending("PEPE / USDC", "1000000", "100"),
+                        trending("$$$ / WETH", "9000000", "9000000"),
+                    ],
+                    _ => vec![],
+                };
+                axum::Json(serde_json::json!({ "data": data }))
+            },
+        ));
+        serve(router).await
+    }
+
+    fn config(limit: usize) -> CollectorConfig {
+        CollectorConfig {
+            discovery_limit: limit,
+            discovery_min_liquidity: 10_000.0,
+            discovery_pages: 2,
+            ..CollectorConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn discovered_tokens_merge_with_upbit_list() {
+        let base = mock_gecko().await;
+        let discovered = SymbolDiscovery::new(&config(10)).with_base_url(&base).discover().await;
+        // Thin (DUST) and non-alphanumeric names dropped, PEPE deduped, ranked by volume
+        assert_eq!(discovered, ["ETH", "PEPE", "WIF"]);
+
+        let upbit = symbols(&["BTC", "eth", "XRP"]);
+        assert_eq!(merge_symbols(&upbit, &discovered, SymbolSource::Both), ["BTC", "ETH", "XRP", "PEPE", "WIF"]);
+        assert_eq!(merge_symbols(&upbit, &discovered, SymbolSource::Upbit), ["BTC", "ETH", "XRP"]);
+        assert_eq!(merge_symbols(&upbit, &discovered, SymbolSource::Dex), ["ETH", "PEPE", "WIF"]);
+    }
+
+    #[tokio::test]
+    async fn discovered_set_is_capped() {
+        let base = mock_gecko().await;
+        let discovered = SymbolDiscovery::new(&config(2)).with_base_url(&base).discover().await;
+        assert_eq!(discovered, ["ETH", "PEPE"]);
+    }
+}
@@ -1,11 +1,14 @@
-use crate::models::PoolData;
-use crate::config::FilterConfig;
+use std::collections::HashMap;
+use crate::models::{PoolData, canonical_chain};
+use crate::config::{ChainFilter, FilterConfig};
 
 #[derive(Clone)]
 pub struct PoolFilter {
     min_lp: f64,
     min_volume: f64,
-    min_tx_count: u32,
+    allow_zero_liquidity: bool,
+    /// lowercase chain -> overrides
+    per_chain: HashMap<String, ChainFilter>,
 }
 
 impl PoolFilter {
@@ -13,44 +16,48 @@ impl PoolFilter {
         Self {
             min_lp: config.min_lp,
             min_volume: config.min_volume,
-            min_tx_count: config.min_tx_count,
+            allow_zero_liquidity: config.allow_zero_liquidity,
+            per_chain: config.per_chain.iter()
+                .map(|(chain, f)| (canonical_chain(chain), f.clone()))
+                .collect(),
         }
     }
 
-    /// 풀 유효성 검사 (디버깅 모드 - 완화된 필터)
+    /// (min_lp, min_volume) for a chain, falling back to the defaults
+    pub fn thresholds_for(&self, chain: &str) -> (f64, f64) {
+        match self.per_chain.get(&canonical_chain(chain)) {
+            Some(f) => (f.min_lp.unwrap_or(self.min_lp), f.min_volume.unwrap_or(self.min_volume)),
+            None => (self.min_lp, self.min_volume),
+        }
+    }
+
+    /// 풀 유효성 검사
     pub fn is_valid(&self, pool: &PoolData) -> bool {
-        // 🔥 임시: 가격만 있으면 일단 통과 (Aggregator 테스트용)
-        if pool.price_usd > 0.0 {
-            tracing::trace!("    ✓ 가격 기반 필터 통과: {} @ {} (${:.4})", 
-                pool.symbol, pool.dex, pool.price_usd);
-            return true;
+        if !(pool.is_finite_price() && pool.price_usd > 0.0) {
+            return false;
         }
-        
-        // 원래 LP 기반 필터 (가격이 0일 때만 적용)
-        // LP가 충분한 경우
-        if pool.lp_reserve_usd >= self.min_lp {
-            let valid = pool.volume_24h >= self.min_volume;
-            if valid {
-                tracing::trace!("    ✓ LP 기반 필터 통과: {} (LP=${:.0}, Vol=${:.0})",
-                    pool.dex, pool.lp_reserve_usd, pool.volume_24h);
+
+        // LP/거래량 정보가 없는 풀 (어그리게이터 견적)
+        if pool.lp_reserve_usd <= 0.0 && pool.volume_24h <= 0.0 {
+            if self.allow_zero_liquidity {
+                tracing::trace!("    ✓ 어그리게이터 풀 허용: {} @ {} (${:.4})",
+                    pool.symbol, pool.dex, pool.price_usd);
             }
-            return valid;
+            return self.allow_zero_liquidity;
         }
 
-        // LP가 낮아도 거래량이 있으면 거래 가능
-        // (동적 LP 계산: LP의 2% 이상 거래 가능 시 유효)
-        let tradeable = pool.lp_reserve_usd * 0.02;
-        if tradeable >= 100.0 && pool.volume_24h >= self.min_volume {
-            tracing::trace!("    ✓ 동적 LP 필터 통과: {} (거래가능=${:.0})",
-                pool.dex, tradeable);
-            return true;
+        // LP와 거래량 모두 기준 이상 (체인별 기준 우선)
+        let (min_lp, min_volume) = self.thresholds_for(&pool.chain);
+        let valid = pool.lp_reserve_usd >= min_lp && pool.volume_24h >= min_volume;
+        if valid {
+            tracing::trace!("    ✓ LP 기반 필터 통과: {} (LP=${:.0}, Vol=${:.0})",
+                pool.dex, pool.lp_reserve_usd, pool.volume_24h);
         }
-
-        false
+        valid
     }
 
     /// 거래 가능 금액 계산
-    pub fn calculate_max_trade(&self, pool: &PoolData) -> f64 {
+    pub fn calculate_max_trade(pool: &PoolData) -> f64 {
         // LP의 2% (슬리피지 고려)
         pool.lp_reserve_usd * 0.02
     }
@@ -62,4 +69,89 @@ impl PoolFilter {
     pub fn set_min_volume(&mut self, min_volume: f64) {
         self.min_volume = min_volume;
     }
-}
\ No newline at end of file
+
+    pub fn set_allow_zero_liquidity(&mut self, allow: bool) {
+        self.allow_zero_liquidity = allow;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    fn filter(allow_zero_liquidity: bool) -> PoolFilter {
+        PoolFilter::new(&FilterConfig {
+            min_lp: 10_000.0,
+            min_volume: 1_000.0,
+            allow_zero_liquidity,
+            per_chain: HashMap::new(),
+        })
+    }
+
+    fn pool(chain: &str, lp: f64, volume: f64) -> PoolData {
+        test_util::pool("ETH", "test", 3000.0, 0).on_chain(chain).with_lp(lp).with_volume(volume)
+    }
+
+    #[test]
+    fn rich_pool_passes() {
+        assert!(filter(false).is_valid(&pool("ethereum", 5_000_000.0, 2_000_000.0)));
+    }
+
+    #[test]
+    fn thin_pool_is_rejected() {
+        let filter = filter(true);
+        assert!(!filter.is_valid(&pool("ethereum", 9_999.0, 50_000.0)));
+        assert!(!filter.is_valid(&pool("ethereum", 50_000.0, 999.0)));
+        // Exactly at the thresholds is enough
+        assert!(filter.is_valid(&pool("ethereum", 10_000.0, 1_000.0)));
+    }
+
+    #[test]
+    fn zero_liquidity_pool_follows_the_flag() {
+        let quote = pool("ethereum", 0.0, 0.0);
+        assert!(filter(true).is_valid(&quote));
+        assert!(!filter(false).is_valid(&quote));
+
+        let mut toggled = filter(true);
+        toggled.set_allow_zero_liquidity(false);
+        assert!(!toggled.is_valid(&quote));
+    }
+
+    #[test]
+    fn zero_or_negative_price_is_rejected() {
+        assert!(!filter(true).is_valid(&PoolData { price_usd: 0.0, ..pool("ethereum", 1e6, 1e6) }));
+        assert!(!filter(true).is_valid(&PoolData { price_usd: -1.0, ..pool("ethereum", 1e6, 1e6) }));
+    }
+
+    #[test]
+    fn mainnet_and_base_use_their_own_cutoffs() {
+        let config: FilterConfig = toml::from_str(r#"
+            min_lp = 10000
+            min_volume = 1000
+            [per_chain.ethereum]
+            min_lp = 50000
+            [per_chain.Base]
+            min_lp = 2000
+            min_volume = 200
+        "#).unwrap();
+        let filter = PoolFilter::new(&config);
+        assert_eq!(filter.thresholds_for("ethereum"), (50_000.0, 1_000.0));
+        assert_eq!(filter.thresholds_for("base"), (2_000.0, 200.0));
+        assert_eq!(filter.thresholds_for("polygon"), (10_000.0, 1_000.0));
+
+        // The same $20k pool is too thin for mainnet but fine on Base and on default chains
+        assert!(!filter.is_valid(&pool("ethereum", 20_000.0, 5_000.0)));
+        assert!(filter.is_valid(&pool("base", 20_000.0, 5_000.0)));
+        assert!(filter.is_valid(&pool("polygon", 20_000.0, 5_000.0)));
+        assert!(!filter.is_valid(&pool("base", 1_999.0, 5_000.0)));
+    }
+
+    #[test]
+    fn per_chain_is_optional() {
+        let config: FilterConfig = toml::from_str("min_lp = 1\nmin_volume = 2").unwrap();
+        assert!(config.per_chain.is_empty());
+        assert!(config.allow_zero_liquidity);
+        assert_eq!(PoolFilter::new(&config).thresholds_for("base"), (1.0, 2.0));
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+use serde::Serialize;
+use crate::models::PoolData;
+
+/// Symbol × source matrix of data age (seconds since the newest data point)
+#[derive(Debug, Clone, Serialize)]
+pub struct FreshnessMatrix {
+    pub generated_at: i64,
+    pub sources: Vec<String>,
+    /// symbol -> source -> age in seconds (`null` when the source has no data)
+    pub cells: BTreeMap<String, BTreeMap<String, Option<i64>>>,
+    /// collector source name -> seconds since its last successful fetch
+    pub source_last_update: BTreeMap<String, i64>,
+}
+
+/// Build the freshness matrix from cached pools
+pub fn freshness_matrix(
+    pools: &[Arc<PoolData>],
+    source_updates: &[(&'static str, i64)],
+    now: i64,
+) -> FreshnessMatrix {
+    let sources: BTreeSet<String> = pools.iter()
+        .map(|p| p.source.clone())
+        .collect();
+
+    let mut cells: BTreeMap<String, BTreeMap<String, Option<i64>>> = BTreeMap::new();
+    for pool in pools {
+        let row = cells.entry(pool.symbol.clone()).or_insert_with(|| {
+            sources.iter().map(|s| (s.clone(), None)).collect()
+        });
+
+        let age = (now - pool.timestamp).max(0);
+        let cell = row.entry(pool.source.clone()).or_insert(None);
+        *cell = Some(cell.map_or(age, |existing| existing.min(age)));
+    }
+
+    let source_last_update = source_updates.iter()
+        .map(|(name, ts)| (name.to_string(), (now - ts).max(0)))
+        .collect();
+
+    FreshnessMatrix {
+        generated_at: now,
+        sources: sources.into_iter().collect(),
+        cells,
+        source_last_update,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pool;
+
+    #[test]
+    fn matrix_reflects_ages_and_missing_cells() {
+        let now = 1_700_000_000;
+        let pools = vec![
+            pool("ETH", "dexscreener", 3000.0, now - 5).shared(),
+            pool("ETH", "dexscreener", 3001.0, now - 90).shared(),
+            pool("ETH", "gecko", 3002.0, now - 300).shared(),
+            pool("BTC", "gecko", 60000.0, now - 20).shared(),
+        ];
+        let matrix = freshness_matrix(&pools, &[("DexScreener", now - 7)], now);
+
+        assert_eq!(matrix.generated_at, now);
+        assert_eq!(matrix.sources, ["dexscreener", "gecko"]);
+        // Newest point per cell wins
+        assert_eq!(matrix.cells["ETH"]["dexscreener"], Some(5));
+        assert_eq!(matrix.cells["ETH"]["gecko"], Some(300));
+        // BTC has no DexScreener pool: present as a null cell, not missing
+        assert_eq!(matrix.cells["BTC"]["dexscreener"], None);
+        assert_eq!(matrix.cells["BTC"]["gecko"], Some(20));
+        assert_eq!(matrix.source_last_update["DexScreener"], 7);
+    }
+
+    #[test]
+    fn future_timestamps_count_as_fresh() {
+        let now = 1_700_000_000;
+        let matrix = freshness_matrix(&[pool("ETH", "gecko", 1.0, now + 30).shared()], &[], now);
+        assert_eq!(matrix.cells["ETH"]["gecko"], Some(0));
+    }
+}
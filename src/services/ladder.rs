@@ -0,0 +1,246 @@
+use serde::Deserialize;
+use crate::config::{ArbitrageConfig, NotifyConfig};
+use crate::models::ArbitrageAlert;
+use crate::services::notify::{Notifier, WebhookNotifier};
+use crate::sources::http;
+
+/// Where alerts of a ladder rung are delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertDestination {
+    Log,
+    Websocket,
+    Webhook,
+    Page,
+}
+
+/// Named threshold (fraction, 0.01 = 1%) with its destinations
+#[derive(Debug, Clone, Deserialize)]
+pub struct LadderRung {
+    pub name: String,
+    pub threshold: f64,
+    pub destinations: Vec<AlertDestination>,
+}
+
+/// Multi-threshold alerting ladder; an alert belongs to the highest rung it clears
+#[derive(Debug, Clone)]
+pub struct AlertLadder {
+    /// Sorted by threshold ascending
+    rungs: Vec<LadderRung>,
+}
+
+impl AlertLadder {
+    pub fn new(mut rungs: Vec<LadderRung>) -> Self {
+        rungs.retain(|r| r.threshold.is_finite());
+        rungs.sort_by(|a, b| a.threshold.total_cmp(&b.threshold));
+        Self { rungs }
+    }
+
+    /// Ladder from `[[arbitrage.ladder]]`, or a single rung at `threshold` when none is configured
+    pub fn from_config(config: &ArbitrageConfig) -> Self {
+        if config.ladder.is_empty() {
+            return Self::new(vec![LadderRung {
+                name: "default".to_string(),
+                threshold: config.threshold,
+                destinations: vec![AlertDestination::Log, AlertDestination::Websocket],
+            }]);
+        }
+        Self::new(config.ladder.clone())
+    }
+
+    /// Lowest threshold on the ladder (detection cut-off)
+    pub fn min_threshold(&self) -> f64 {
+        self.rungs.first().map(|r| r.threshold).unwrap_or(0.0)
+    }
+
+    /// Highest rung satisfied by a gap of `diff_pct` percent
+    pub fn rung_for(&self, diff_pct: f64) -> Option<&LadderRung> {
+        let diff = diff_pct / 100.0;
+        self.rungs.iter().rev().find(|r| diff >= r.threshold)
+    }
+
+    /// Tag alerts with the rung their net spread satisfies. Alerts below every rung
+    /// (passed a tighter per-symbol threshold) land on the lowest rung.
+    pub fn tag(&self, alerts: Vec<ArbitrageAlert>) -> Vec<ArbitrageAlert> {
+        alerts.into_iter()
+            .filter_map(|mut alert| {
+                let rung = self.rung_for(alert.net_diff_pct).or(self.rungs.first())?;
+                alert.rung = Some(rung.name.clone());
+                Some(alert)
+            })
+            .collect()
+    }
+
+    /// Destinations configured for a tagged alert's rung
+    pub fn destinations(&self, alert: &ArbitrageAlert) -> &[AlertDestination] {
+        alert.rung.as_deref()
+            .and_then(|name| self.rungs.iter().find(|r| r.name == name))
+            .map(|r| r.destinations.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn routes_to(&self, alert: &ArbitrageAlert, destination: AlertDestination) -> bool {
+        self.destinations(alert).contains(&destination)
+    }
+
+    /// Some rung delivers to `destination`
+    pub fn uses(&self, destination: AlertDestination) -> bool {
+        self.rungs.iter().any(|r| r.destinations.contains(&destination))
+    }
+}
+
+/// Delivers tagged alerts to log / webhook / page destinations.
+/// Websocket delivery happens in the `/ws` handler via `AlertLadder::routes_to`.
+/// Webhook and page go through `WebhookNotifier`, the same POST `[notify]` uses.
+pub struct AlertRouter {
+    ladder: AlertLadder,
+    /// `[notify].webhook_url`
+    webhook: Option<WebhookNotifier>,
+    /// `[arbitrage].page_url`
+    page: Option<WebhookNotifier>,
+}
+
+impl AlertRouter {
+    pub fn new(ladder: AlertLadder, arbitrage: &ArbitrageConfig, notify: &NotifyConfig) -> Self {
+        let notifier = |url: Option<String>| url.map(|url| WebhookNotifier::new(http::shared_client(), url));
+        Self {
+            ladder,
+            webhook: notifier(notify.webhook_url()),
+            page: notifier(non_empty(&arbitrage.page_url)),
+        }
+    }
+
+    pub fn ladder(&self) -> &AlertLadder {
+        &self.ladder
+    }
+
+    pub async fn dispatch(&self, alerts: &[ArbitrageAlert]) {
+        for alert in alerts {
+            for destination in self.ladder.destinations(alert) {
+                match destination {
+                    AlertDestination::Log => tracing::warn!(
+                        "🚨 [{}] {} {:.2}% net ({:.2}% gross, {} ${:.6} → {} ${:.6})",
+                        alert.rung.as_deref().unwrap_or("-"), alert.symbol, alert.net_diff_pct, alert.diff_pct,
+                        alert.low_source, alert.low_price, alert.high_source, alert.high_price
+                    ),
+                    AlertDestination::Websocket => {}
+                    AlertDestination::Webhook => Self::post("webhook", self.webhook.as_ref(), alert).await,
+                    AlertDestination::Page => Self::post("page", self.page.as_ref(), alert).await,
+                }
+            }
+        }
+    }
+
+    async fn post(kind: &str, notifier: Option<&WebhookNotifier>, alert: &ArbitrageAlert) {
+        let Some(notifier) = notifier else {
+            tracing::warn!("⚠️ {} destination has no URL configured ({})", kind, alert.symbol);
+            return;
+        };
+        if let Err(e) = notifier.notify(alert).await {
+            tracing::warn!("⚠️ {} delivery failed for {}: {}", kind, alert.symbol, e);
+        }
+    }
+}
+
+fn non_empty(url: &Option<String>) -> Option<String> {
+    url.as_deref().map(str::trim).filter(|u| !u.is_empty()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use axum::routing::post;
+    use crate::models::alert::ArbType;
+    use crate::test_util::serve;
+
+    fn rung(name: &str, threshold: f64, destinations: &[AlertDestination]) -> LadderRung {
+        LadderRung { name: name.to_string(), threshold, destinations: destinations.to_vec() }
+    }
+
+    fn ladder() -> AlertLadder {
+        // Deliberately out of order: the ladder sorts by threshold
+        AlertLadder::new(vec![
+            rung("page", 0.05, &[AlertDestination::Log, AlertDestination::Page]),
+            rung("notify", 0.01, &[AlertDestination::Websocket, AlertDestination::Webhook]),
+        ])
+    }
+
+    fn alert(net_diff_pct: f64) -> ArbitrageAlert {
+        ArbitrageAlert {
+            symbol: "ETH".to_string(),
+            arb_type: ArbType::DexToDex,
+            low_price: 3000.0,
+            low_source: "uniswap:0xa".to_string(),
+            high_price: 3000.0 * (1.0 + net_diff_pct / 100.0),
+            high_source: "sushiswap:0xb".to_string(),
+            diff_pct: net_diff_pct,
+            action: String::new(),
+            net_diff_pct,
+            estimated_gas_usd: 0.0,
+            tradeable_usd: 0.0,
+            estimated_profit_usd: 0.0,
+            timestamp: 0,
+            rung: None,
+        }
+    }
+
+    #[test]
+    fn two_percent_gap_lands_on_the_one_percent_rung_only() {
+        let ladder = ladder();
+        assert_eq!(ladder.min_threshold(), 0.01);
+        assert_eq!(ladder.rung_for(2.0).unwrap().name, "notify");
+        assert_eq!(ladder.rung_for(5.0).unwrap().name, "page");
+        assert!(ladder.rung_for(0.5).is_none());
+
+        let tagged = ladder.tag(vec![alert(2.0)]);
+        assert_eq!(tagged[0].rung.as_deref(), Some("notify"));
+        assert_eq!(ladder.destinations(&tagged[0]), [AlertDestination::Websocket, AlertDestination::Webhook]);
+        assert!(!ladder.routes_to(&tagged[0], AlertDestination::Page));
+        assert!(!ladder.routes_to(&tagged[0], AlertDestination::Log));
+    }
+
+    #[test]
+    fn below_every_rung_falls_back_to_the_lowest() {
+        let tagged = ladder().tag(vec![alert(0.5)]);
+        assert_eq!(tagged[0].rung.as_deref(), Some("notify"));
+    }
+
+    #[test]
+    fn default_ladder_is_a_single_rung_at_threshold() {
+        let config: ArbitrageConfig = toml::from_str("threshold = 0.02\nupdate_interval = 60").unwrap();
+        let ladder = AlertLadder::from_config(&config);
+        assert_eq!(ladder.min_threshold(), 0.02);
+        assert!(!ladder.uses(AlertDestination::Webhook));
+        assert_eq!(ladder.tag(vec![alert(3.0)])[0].rung.as_deref(), Some("default"));
+    }
+
+    #[tokio::test]
+    async fn dispatch_posts_only_to_the_rungs_destinations() {
+        let webhook_hits = Arc::new(AtomicUsize::new(0));
+        let page_hits = Arc::new(AtomicUsize::new(0));
+        let counter = |hits: &Arc<AtomicUsize>| {
+            let hits = hits.clone();
+            post(move |axum::Json(alert): axum::Json<ArbitrageAlert>| async move {
+                assert_eq!(alert.rung.as_deref(), Some("notify"));
+                hits.fetch_add(1, Ordering::SeqCst);
+            })
+        };
+        let base = serve(axum::Router::new()
+            .route("/webhook", counter(&webhook_hits))
+            .route("/page", counter(&page_hits)))
+            .await;
+
+        let arbitrage: ArbitrageConfig = toml::from_str(&format!(
+            "threshold = 0.01\nupdate_interval = 60\npage_url = \"{base}/page\""
+        )).unwrap();
+        let notify = NotifyConfig { webhook_url: Some(format!("{base}/webhook")), ..NotifyConfig::default() };
+        let router = AlertRouter::new(ladder(), &arbitrage, &notify);
+
+        let alerts = router.ladder().tag(vec![alert(2.0), alert(2.5)]);
+        router.dispatch(&alerts).await;
+        assert_eq!(webhook_hits.load(Ordering::SeqCst), 2);
+        assert_eq!(page_hits.load(Ordering::SeqCst), 0);
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::{HashMap, VecDeque};
+use parking_lot::RwLock;
+use serde::Serialize;
+use crate::models::timestamp::HasTimestamp;
+
+/// One recorded price (VWAP of the cycle)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct PricePoint {
+    pub timestamp: i64,
+    pub price_usd: f64,
+}
+
+impl HasTimestamp for PricePoint {
+    fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+}
+
+/// Rolling per-symbol price series kept in memory for charting (`/history/live/:symbol`).
+/// Bounded by `max_points` per symbol and `max_symbols` overall; when full, the
+/// symbol updated longest ago is evicted.
+pub struct LivePriceHistory {
+    max_points: usize,
+    max_symbols: usize,
+    /// Uppercased symbol -> points, oldest first
+    series: RwLock<HashMap<String, VecDeque<PricePoint>>>,
+}
+
+impl LivePriceHistory {
+    pub fn new(max_points: usize, max_symbols: usize) -> Self {
+        Self {
+            max_points: max_points.max(1),
+            max_symbols: max_symbols.max(1),
+            series: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Append one cycle of `{symbol: price}`; non-finite/non-positive prices are skipped
+    pub fn record(&self, prices: &HashMap<String, f64>, timestamp: i64) {
+        let mut series = self.series.write();
+        for (symbol, price) in prices.iter().filter(|(_, p)| p.is_finite() && **p > 0.0) {
+            let symbol = symbol.to_uppercase();
+            if !series.contains_key(&symbol) && series.len() >= self.max_symbols {
+                let oldest = series.iter()
+                    .min_by_key(|(_, points)| points.back().map_or(i64::MIN, |p| p.timestamp))
+                    .map(|(symbol, _)| symbol.clone());
+                if let Some(oldest) = oldest {
+                    series.remove(&oldest);
+                }
+            }
+
+            let points = series.entry(symbol).or_default();
+            if points.len() == self.max_points {
+                points.pop_front();
+            }
+            points.push_back(PricePoint { timestamp, price_usd: *price });
+        }
+    }
+
+    /// Points of a symbol (case-insensitive), oldest first; empty when unknown
+    pub fn get(&self, symbol: &str) -> Vec<PricePoint> {
+        self.series.read()
+            .get(&symbol.to_uppercase())
+            .map(|points| points.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
+    /// Symbols currently tracked
+    pub fn len(&self) -> usize {
+        self.series.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.series.read().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cycle(prices: &[(&str, f64)]) -> HashMap<String, f64> {
+        prices.iter().map(|(symbol, price)| (symbol.to_string(), *price)).collect()
+    }
+
+    fn prices(history: &LivePriceHistory, symbol: &str) -> Vec<f64> {
+        history.get(symbol).iter().map(|p| p.price_usd).collect()
+    }
+
+    #[test]
+    fn ring_drops_the_oldest_point_when_full() {
+        let history = LivePriceHistory::new(3, 10);
+        for (ts, price) in [(100, 1.0), (160, 2.0), (220, 3.0), (280, 4.0), (340, 5.0)] {
+            history.record(&cycle(&[("eth", price)]), ts);
+        }
+        assert_eq!(history.get("ETH"), [
+            PricePoint { timestamp: 220, price_usd: 3.0 },
+            PricePoint { timestamp: 280, price_usd: 4.0 },
+            PricePoint { timestamp: 340, price_usd: 5.0 },
+        ]);
+        assert_eq!(prices(&history, "Eth"), [3.0, 4.0, 5.0], "lookup is case-insensitive");
+    }
+
+    #[test]
+    fn full_history_evicts_the_symbol_updated_longest_ago() {
+        let history = LivePriceHistory::new(5, 2);
+        history.record(&cycle(&[("ETH", 3000.0)]), 100);
+        history.record(&cycle(&[("BTC", 60_000.0)]), 160);
+        history.record(&cycle(&[("ETH", 3010.0)]), 220);
+
+        // ETH was refreshed at 220, so BTC (160) makes room for SOL
+        history.record(&cycle(&[("SOL", 150.0)]), 280);
+        assert_eq!(history.len(), 2);
+        assert!(history.get("BTC").is_empty());
+        assert_eq!(prices(&history, "ETH"), [3000.0, 3010.0]);
+        assert_eq!(prices(&history, "SOL"), [150.0]);
+    }
+
+    #[test]
+    fn unusable_prices_are_skipped() {
+        let history = LivePriceHistory::new(5, 5);
+        history.record(&cycle(&[("ETH", 3000.0), ("BAD", f64::NAN), ("ZERO", 0.0), ("INF", f64::INFINITY)]), 100);
+        assert_eq!(history.len(), 1);
+        assert!(history.get("BAD").is_empty());
+        assert!(history.get("UNKNOWN").is_empty());
+    }
+}
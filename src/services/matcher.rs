@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use serde::Deserialize;
+use crate::config::MatchingConfig;
+use crate::models::PoolData;
+
+/// How pools are matched to each other and to CEX tickers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    /// Symbol strings must be identical
+    #[default]
+    Exact,
+    /// Case-insensitive, with aliases resolved (WETH -> ETH)
+    Alias,
+    /// Configured pool addresses first, then alias rules
+    Address,
+}
+
+/// Single place deciding which pools/tickers refer to the same asset
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMatcher {
+    mode: MatchMode,
+    /// uppercase alias -> uppercase canonical symbol
+    aliases: HashMap<String, String>,
+    /// lowercase pool address -> uppercase canonical symbol
+    addresses: HashMap<String, String>,
+}
+
+impl SymbolMatcher {
+    pub fn new(config: &MatchingConfig) -> Self {
+        Self {
+            mode: config.mode,
+            aliases: config.aliases.iter()
+                .map(|(alias, canonical)| (alias.to_uppercase(), canonical.to_uppercase()))
+                .collect(),
+            addresses: config.addresses.iter()
+                .map(|(address, symbol)| (address.to_lowercase(), symbol.to_uppercase()))
+                .collect(),
+        }
+    }
+
+    /// Canonical key for a bare symbol (CEX tickers, requests)
+    pub fn canonical_symbol(&self, symbol: &str) -> String {
+        match self.mode {
+            MatchMode::Exact => symbol.to_string(),
+            MatchMode::Alias | MatchMode::Address => {
+                let upper = symbol.to_uppercase();
+                self.aliases.get(&upper).cloned().unwrap_or(upper)
+            }
+        }
+    }
+
+    /// Canonical key for a pool
+    pub fn canonical(&self, pool: &PoolData) -> String {
+        if self.mode == MatchMode::Address {
+            if let Some(symbol) = self.addresses.get(&pool.pool_address.to_lowercase()) {
+                return symbol.clone();
+            }
+        }
+        self.canonical_symbol(&pool.symbol)
+    }
+
+    pub fn matches(&self, pool: &PoolData, symbol: &str) -> bool {
+        self.canonical(pool) == self.canonical_symbol(symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    fn matcher(mode: MatchMode) -> SymbolMatcher {
+        let mut config = MatchingConfig { mode, ..MatchingConfig::default() };
+        config.addresses.insert("0xPEGGED".to_string(), "usdc".to_string());
+        SymbolMatcher::new(&config)
+    }
+
+    fn pool(symbol: &str, address: &str) -> PoolData {
+        test_util::pool(symbol, "test", 1.0, 0).at_address(address)
+    }
+
+    #[test]
+    fn exact_mode_compares_symbols_verbatim() {
+        let matcher = matcher(MatchMode::Exact);
+        assert!(matcher.matches(&pool("ETH", "0x1"), "ETH"));
+        assert!(!matcher.matches(&pool("WETH", "0x1"), "ETH"));
+        assert!(!matcher.matches(&pool("eth", "0x1"), "ETH"));
+    }
+
+    #[test]
+    fn alias_mode_resolves_wrapped_natives() {
+        let matcher = matcher(MatchMode::Alias);
+        assert!(matcher.matches(&pool("WETH", "0x1"), "ETH"));
+        assert!(matcher.matches(&pool("eth", "0x1"), "weth"));
+        assert_eq!(matcher.canonical_symbol("wbtc"), "BTC");
+        // Configured addresses only count in address mode
+        assert_eq!(matcher.canonical(&pool("XYZ", "0xpegged")), "XYZ");
+    }
+
+    #[test]
+    fn unrelated_symbols_never_match() {
+        for mode in [MatchMode::Exact, MatchMode::Alias, MatchMode::Address] {
+            let matcher = matcher(mode);
+            assert!(!matcher.matches(&pool("WETH", "0x1"), "BTC"), "{mode:?}");
+            assert!(!matcher.matches(&pool("ETHX", "0x1"), "ETH"), "{mode:?}");
+        }
+    }
+
+    #[test]
+    fn address_mode_prefers_the_configured_address() {
+        let matcher = matcher(MatchMode::Address);
+        assert!(matcher.matches(&pool("XYZ", "0xPegged"), "USDC"));
+        // Other pools fall back to alias rules
+        assert!(matcher.matches(&pool("WETH", "0x1"), "ETH"));
+    }
+}
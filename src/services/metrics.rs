@@ -0,0 +1,133 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+
+/// Upper bounds (percent) of the gap histogram buckets; the last bucket is +Inf
+pub const GAP_BUCKETS: [f64; 4] = [0.5, 1.0, 2.0, 5.0];
+
+#[derive(Debug, Clone, Default)]
+struct GapCounts {
+    /// Non-cumulative counts, one per bucket plus the +Inf bucket
+    buckets: [u64; GAP_BUCKETS.len() + 1],
+    count: u64,
+    sum: f64,
+}
+
+/// Histogram of observed arbitrage gap sizes, reset every `window`
+pub struct GapHistogram {
+    window: Option<Duration>,
+    state: Mutex<(GapCounts, Instant)>,
+}
+
+impl GapHistogram {
+    /// `window_secs = 0` keeps counting forever
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window: (window_secs > 0).then(|| Duration::from_secs(window_secs)),
+            state: Mutex::new((GapCounts::default(), Instant::now())),
+        }
+    }
+
+    /// Bucket index for a gap in percent
+    pub fn bucket_index(gap_pct: f64) -> usize {
+        GAP_BUCKETS.iter()
+            .position(|upper| gap_pct < *upper)
+            .unwrap_or(GAP_BUCKETS.len())
+    }
+
+    pub fn observe(&self, gap_pct: f64) {
+        if !gap_pct.is_finite() {
+            return;
+        }
+        let gap_pct = gap_pct.abs();
+        let mut state = self.state.lock();
+        self.roll_window(&mut state);
+
+        let counts = &mut state.0;
+        counts.buckets[Self::bucket_index(gap_pct)] += 1;
+        counts.count += 1;
+        counts.sum += gap_pct;
+    }
+
+    pub fn observe_all(&self, gaps: &[f64]) {
+        for gap in gaps {
+            self.observe(*gap);
+        }
+    }
+
+    fn roll_window(&self, state: &mut (GapCounts, Instant)) {
+        if let Some(window) = self.window {
+            if state.1.elapsed() >= window {
+                *state = (GapCounts::default(), Instant::now());
+            }
+        }
+    }
+
+    /// Prometheus text exposition (cumulative `le` buckets)
+    pub fn render(&self, out: &mut String, name: &str) {
+        let counts = {
+            let mut state = self.state.lock();
+            self.roll_window(&mut state);
+            state.0.clone()
+        };
+
+        let _ = writeln!(out, "# HELP {} Observed arbitrage gap sizes in percent", name);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        let mut cumulative = 0;
+        for (i, upper) in GAP_BUCKETS.iter().enumerate() {
+            cumulative += counts.buckets[i];
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, upper, cumulative);
+        }
+        cumulative += counts.buckets[GAP_BUCKETS.len()];
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, cumulative);
+        let _ = writeln!(out, "{}_sum {}", name, counts.sum);
+        let _ = writeln!(out, "{}_count {}", name, counts.count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(histogram: &GapHistogram) -> [u64; GAP_BUCKETS.len() + 1] {
+        histogram.state.lock().0.buckets
+    }
+
+    #[test]
+    fn gaps_land_in_their_buckets() {
+        let histogram = GapHistogram::new(0);
+        // Upper bounds are exclusive: 0.5 goes to the 0.5–1% bucket
+        histogram.observe_all(&[0.1, 0.49, 0.5, 0.99, 1.5, -1.5, 2.0, 4.9, 5.0, 12.0, f64::NAN, f64::INFINITY]);
+        assert_eq!(buckets(&histogram), [2, 2, 2, 2, 2]);
+        assert_eq!(histogram.state.lock().0.count, 10);
+    }
+
+    #[test]
+    fn render_is_cumulative_prometheus_text() {
+        let histogram = GapHistogram::new(0);
+        histogram.observe_all(&[0.2, 1.5, 1.5, 7.0]);
+        let mut out = String::new();
+        histogram.render(&mut out, "gap_pct");
+        let expected = "\
+# HELP gap_pct Observed arbitrage gap sizes in percent
+# TYPE gap_pct histogram
+gap_pct_bucket{le=\"0.5\"} 1
+gap_pct_bucket{le=\"1\"} 1
+gap_pct_bucket{le=\"2\"} 3
+gap_pct_bucket{le=\"5\"} 3
+gap_pct_bucket{le=\"+Inf\"} 4
+gap_pct_sum 10.2
+gap_pct_count 4
+";
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn window_resets_the_counts() {
+        let histogram = GapHistogram::new(60);
+        histogram.observe(3.0);
+        histogram.state.lock().1 = Instant::now().checked_sub(Duration::from_secs(61)).unwrap();
+        histogram.observe(0.1);
+        assert_eq!(buckets(&histogram), [1, 0, 0, 0, 0]);
+    }
+}
@@ -3,11 +3,28 @@ pub mod detector;
 pub mod cache;
 pub mod filter;
 pub mod storage;
+#[cfg(feature = "sqlite")]
+pub mod sqlite_storage;
 pub mod price_monitor;
+pub mod freshness;
+pub mod simulator;
+pub mod discovery;
+pub mod new_pools;
+pub mod ladder;
+pub mod reconcile;
+pub mod metrics;
+pub mod selftest;
+pub mod matcher;
+pub mod comovement;
+pub mod aggregate;
+pub mod broadcast;
+pub mod gap_monitor;
+pub mod notify;
+pub mod live_history;
 
 pub use collector::PoolCollector;
 pub use detector::ArbitrageDetector;
 pub use cache::PoolCache;
 pub use filter::PoolFilter;
-pub use storage::LocalStorage;
+pub use storage::{LocalStorage, StorageBackend};
 pub use price_monitor::PriceMonitor;
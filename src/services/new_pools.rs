@@ -0,0 +1,123 @@
+use std::collections::{HashMap, HashSet};
+use crate::models::PoolData;
+
+/// One newly seen pool
+#[derive(Debug, Clone)]
+pub struct NewPool {
+    pub source: String,
+    pub chain: String,
+    pub dex: String,
+    pub pool_address: String,
+}
+
+/// New-pool alert for a symbol; `coalesced` counts pools suppressed during the cooldown
+#[derive(Debug, Clone)]
+pub struct NewPoolAlert {
+    pub symbol: String,
+    pub pool: NewPool,
+    pub coalesced: usize,
+}
+
+/// Tracks pool keys across cycles and emits per-symbol new-pool alerts with a cooldown
+pub struct NewPoolTracker {
+    seen: HashSet<String>,
+    /// The first cycle only seeds `seen` (everything would look new)
+    primed: bool,
+    cooldown_secs: i64,
+    last_alert: HashMap<String, i64>,
+    suppressed: HashMap<String, usize>,
+}
+
+impl NewPoolTracker {
+    pub fn new(cooldown_secs: u64) -> Self {
+        Self {
+            seen: HashSet::new(),
+            primed: false,
+            cooldown_secs: cooldown_secs as i64,
+            last_alert: HashMap::new(),
+            suppressed: HashMap::new(),
+        }
+    }
+
+    pub fn set_cooldown(&mut self, cooldown_secs: u64) {
+        self.cooldown_secs = cooldown_secs as i64;
+    }
+
+    /// Record a pool; returns an alert when it is new and its symbol is not cooling down
+    pub fn observe(&mut self, key: &str, pool: &PoolData, now: i64) -> Option<NewPoolAlert> {
+        if !self.seen.insert(key.to_string()) || !self.primed {
+            return None;
+        }
+
+        let symbol = pool.symbol.to_uppercase();
+        if let Some(last) = self.last_alert.get(&symbol) {
+            if now - last < self.cooldown_secs {
+                *self.suppressed.entry(symbol).or_insert(0) += 1;
+                return None;
+            }
+        }
+
+        self.last_alert.insert(symbol.clone(), now);
+        let coalesced = self.suppressed.remove(&symbol).unwrap_or(0);
+        Some(NewPoolAlert {
+            symbol,
+            pool: NewPool {
+                source: pool.source.clone(),
+                chain: pool.chain.clone(),
+                dex: pool.dex.clone(),
+                pool_address: pool.pool_address.clone(),
+            },
+            coalesced,
+        })
+    }
+
+    /// Mark the end of a collection cycle
+    pub fn finish_cycle(&mut self) {
+        self.primed = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pool;
+
+    fn observe(tracker: &mut NewPoolTracker, symbol: &str, source: &str, now: i64) -> Option<NewPoolAlert> {
+        let pool = pool(symbol, source, 1.0, now);
+        tracker.observe(&pool.dedup_key(), &pool, now)
+    }
+
+    fn primed(cooldown_secs: u64) -> NewPoolTracker {
+        let mut tracker = NewPoolTracker::new(cooldown_secs);
+        observe(&mut tracker, "ETH", "seed", 0);
+        tracker.finish_cycle();
+        tracker
+    }
+
+    #[test]
+    fn first_cycle_only_seeds() {
+        let mut tracker = NewPoolTracker::new(600);
+        assert!(observe(&mut tracker, "ETH", "a", 0).is_none());
+        tracker.finish_cycle();
+        // Already seen: still no alert
+        assert!(observe(&mut tracker, "ETH", "a", 10).is_none());
+    }
+
+    #[test]
+    fn burst_for_one_symbol_alerts_once_while_others_still_alert() {
+        let mut tracker = primed(600);
+        let first = observe(&mut tracker, "PEPE", "a", 100).expect("first new pool alerts");
+        assert_eq!((first.symbol.as_str(), first.coalesced), ("PEPE", 0));
+        for (i, source) in ["b", "c", "d"].iter().enumerate() {
+            assert!(observe(&mut tracker, "PEPE", source, 101 + i as i64).is_none());
+        }
+
+        let other = observe(&mut tracker, "WIF", "a", 102).expect("other symbol is not cooling down");
+        assert_eq!(other.symbol, "WIF");
+
+        // After the window the next pool alerts and reports what was coalesced
+        let later = observe(&mut tracker, "PEPE", "e", 700).unwrap();
+        assert_eq!(later.coalesced, 3);
+        assert_eq!(later.pool.source, "e");
+    }
+}
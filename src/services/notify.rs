@@ -0,0 +1,248 @@
+use std::time::Duration;
+use async_trait::async_trait;
+use reqwest::Client;
+use crate::config::NotifyConfig;
+use crate::models::ArbitrageAlert;
+use crate::services::ladder::{AlertDestination, AlertLadder};
+use crate::sources::http;
+
+/// Timeout of each webhook or Telegram delivery
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+const TELEGRAM_API: &str = "https://api.telegram.org";
+
+/// Push channel for one alert
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn notify(&self, alert: &ArbitrageAlert) -> Result<(), reqwest::Error>;
+}
+
+/// POSTs the alert JSON to a URL
+pub struct WebhookNotifier {
+    client: Client,
+    url: String,
+}
+
+impl WebhookNotifier {
+    pub fn new(client: Client, url: String) -> Self {
+        Self { client, url }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str { "webhook" }
+
+    async fn notify(&self, alert: &ArbitrageAlert) -> Result<(), reqwest::Error> {
+        // Webhook URLs often carry a secret path; keep it out of the logged error
+        self.client.post(&self.url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(alert)
+            .send().await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(reqwest::Error::without_url)?;
+        Ok(())
+    }
+}
+
+/// Telegram Bot API `sendMessage`
+pub struct TelegramNotifier {
+    client: Client,
+    /// Bot API root
+    api_base: String,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl TelegramNotifier {
+    pub fn new(client: Client, bot_token: String, chat_id: String) -> Self {
+        Self { client, api_base: TELEGRAM_API.to_string(), bot_token, chat_id }
+    }
+
+    /// Point the Bot API calls at another host (proxy, mock server)
+    pub fn set_api_base(&mut self, api_base: &str) {
+        self.api_base = api_base.trim_end_matches('/').to_string();
+    }
+
+    fn message(alert: &ArbitrageAlert) -> String {
+        format!(
+            "🚨 {} {:.2}% net ({:.2}% gross)\n{}\n{} ${:.6} → {} ${:.6}\n≈ ${:.2} profit on ${:.0}",
+            alert.symbol, alert.net_diff_pct, alert.diff_pct,
+            alert.action,
+            alert.low_source, alert.low_price, alert.high_source, alert.high_price,
+            alert.estimated_profit_usd, alert.tradeable_usd,
+        )
+    }
+}
+
+#[async_trait]
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &'static str { "telegram" }
+
+    async fn notify(&self, alert: &ArbitrageAlert) -> Result<(), reqwest::Error> {
+        // The bot token is part of the URL; errors must not carry it into the logs
+        let url = format!("{}/bot{}/sendMessage", self.api_base, self.bot_token);
+        self.client.post(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&serde_json::json!({
+                "chat_id": self.chat_id,
+                "text": Self::message(alert),
+                "disable_web_page_preview": true,
+            }))
+            .send().await
+            .and_then(|resp| resp.error_for_status())
+            .map_err(reqwest::Error::without_url)?;
+        Ok(())
+    }
+}
+
+/// Configured notifiers plus the `[notify]` threshold
+pub struct Notifications {
+    notifiers: Vec<Box<dyn Notifier>>,
+    /// Fraction (0.01 = 1%) of net spread
+    threshold: f64,
+}
+
+impl Notifications {
+    /// The webhook is left to `ladder` when one of its rungs routes to it
+    pub fn from_config(config: &NotifyConfig, ladder: &AlertLadder) -> Self {
+        let client = http::shared_client();
+        let non_empty = |v: &Option<String>| v.as_deref().map(str::trim).filter(|v| !v.is_empty()).map(str::to_string);
+
+        let mut notifiers: Vec<Box<dyn Notifier>> = Vec::new();
+        if let Some(url) = config.webhook_url().filter(|_| !ladder.uses(AlertDestination::Webhook)) {
+            notifiers.push(Box::new(WebhookNotifier::new(client.clone(), url)));
+        }
+        match (non_empty(&config.telegram_bot_token), non_empty(&config.telegram_chat_id)) {
+            (Some(token), Some(chat_id)) => {
+                notifiers.push(Box::new(TelegramNotifier::new(client, token, chat_id)));
+            }
+            (None, None) => {}
+            _ => tracing::warn!("⚠️ [notify] telegram needs both telegram_bot_token and telegram_chat_id; skipped"),
+        }
+
+        Self { notifiers, threshold: config.threshold }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.notifiers.is_empty()
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.notifiers.iter().map(|n| n.name()).collect()
+    }
+
+    /// Send alerts at or above the threshold to every notifier; failures are logged only
+    pub async fn send(&self, alerts: &[ArbitrageAlert]) {
+        for alert in alerts.iter().filter(|a| a.net_diff_pct >= self.threshold * 100.0) {
+            for notifier in &self.notifiers {
+                if let Err(e) = notifier.notify(alert).await {
+                    tracing::warn!("⚠️ {} notification failed for {}: {}", notifier.name(), alert.symbol, e);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use axum::routing::post;
+    use parking_lot::Mutex;
+    use crate::models::alert::ArbType;
+    use crate::test_util::serve;
+
+    fn alert(symbol: &str, net_diff_pct: f64) -> ArbitrageAlert {
+        ArbitrageAlert {
+            symbol: symbol.to_string(),
+            arb_type: ArbType::DexToCex,
+            low_price: 1.0,
+            low_source: "uniswap (0xabc1…)".to_string(),
+            high_price: 1.0234567,
+            high_source: "Upbit".to_string(),
+            diff_pct: net_diff_pct + 0.5,
+            action: "Buy on uniswap (0xabc1…), sell on Upbit".to_string(),
+            net_diff_pct,
+            estimated_gas_usd: 3.0,
+            tradeable_usd: 20_000.0,
+            estimated_profit_usd: 312.345,
+            timestamp: 0,
+            rung: None,
+        }
+    }
+
+    /// Records the symbols it was asked to send
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl Notifier for Recorder {
+        fn name(&self) -> &'static str { "recorder" }
+
+        async fn notify(&self, alert: &ArbitrageAlert) -> Result<(), reqwest::Error> {
+            self.0.lock().push(alert.symbol.clone());
+            Ok(())
+        }
+    }
+
+    /// POST mock answering `status`; collects the JSON bodies it receives
+    async fn mock_endpoint(path: &str, status: u16, bodies: Arc<Mutex<Vec<serde_json::Value>>>) -> String {
+        let router = axum::Router::new().route(path, post(
+            move |axum::Json(body): axum::Json<serde_json::Value>| async move {
+                bodies.lock().push(body);
+                axum::http::StatusCode::from_u16(status).unwrap()
+            },
+        ));
+        serve(router).await
+    }
+
+    #[tokio::test]
+    async fn only_alerts_at_the_threshold_are_sent() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let notifications = Notifications { notifiers: vec![Box::new(Recorder(sent.clone()))], threshold: 0.02 };
+
+        notifications.send(&[alert("LOW", 1.99), alert("EDGE", 2.0), alert("HIGH", 7.5)]).await;
+        assert_eq!(*sent.lock(), ["EDGE", "HIGH"]);
+    }
+
+    #[test]
+    fn telegram_message_lists_spread_route_and_profit() {
+        assert_eq!(
+            TelegramNotifier::message(&alert("ETH", 1.5)),
+            "🚨 ETH 1.50% net (2.00% gross)\n\
+             Buy on uniswap (0xabc1…), sell on Upbit\n\
+             uniswap (0xabc1…) $1.000000 → Upbit $1.023457\n\
+             ≈ $312.35 profit on $20000",
+        );
+    }
+
+    #[tokio::test]
+    async fn webhook_receives_the_alert_json() {
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let base = mock_endpoint("/hook", 200, bodies.clone()).await;
+        let webhook = WebhookNotifier::new(http::shared_client(), format!("{base}/hook"));
+
+        webhook.notify(&alert("ETH", 1.5)).await.unwrap();
+        let bodies = bodies.lock();
+        assert_eq!(bodies.len(), 1);
+        assert_eq!(bodies[0], serde_json::to_value(alert("ETH", 1.5)).unwrap());
+    }
+
+    #[tokio::test]
+    async fn failed_sends_do_not_leak_the_url() {
+        let bodies = Arc::new(Mutex::new(Vec::new()));
+        let base = mock_endpoint("/bot123:SECRET/sendMessage", 500, bodies.clone()).await;
+        let mut telegram = TelegramNotifier::new(http::shared_client(), "123:SECRET".to_string(), "42".to_string());
+        telegram.set_api_base(&base);
+
+        let error = telegram.notify(&alert("ETH", 1.5)).await.unwrap_err();
+        assert_eq!(error.status().map(|s| s.as_u16()), Some(500));
+        assert!(!error.to_string().contains("SECRET"), "{error}");
+        assert_eq!(bodies.lock()[0]["chat_id"], "42");
+
+        let hook = mock_endpoint("/hooks/SECRET", 500, Arc::new(Mutex::new(Vec::new()))).await;
+        let webhook = WebhookNotifier::new(http::shared_client(), format!("{hook}/hooks/SECRET"));
+        let error = webhook.notify(&alert("ETH", 1.5)).await.unwrap_err();
+        assert!(!error.to_string().contains("SECRET"), "{error}");
+    }
+}
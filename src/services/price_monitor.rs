@@ -6,7 +6,10 @@ use tokio::sync::Semaphore;
 use futures::stream::{self, StreamExt};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use crate::models::PoolData;
+use crate::models::{PoolData, cmp_desc_nan_last};
+use super::aggregate::PriceAggregation;
+use super::storage;
+use crate::sources::http;
 
 /// Pool info loaded from saved JSON files
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,29 +30,74 @@ pub struct PriceData {
     pub dex: String,
     pub pool_address: String,
     pub price_usd: f64,
+    /// 24h volume of the matched pair (VWAP weight)
+    pub volume_24h: f64,
     pub timestamp: u64,
 }
 
+/// chain -> (price, 24h volume)
+type ChainQuotes = HashMap<String, (f64, f64)>;
+
 /// Price monitor for real-time price tracking
 pub struct PriceMonitor {
     client: Client,
     pools: Vec<SavedPool>,
     semaphore: Arc<Semaphore>,
+    concurrency: usize,
+    aggregation: PriceAggregation,
+}
+
+/// Default concurrent lookups (`[concurrency] price_monitor_concurrency`)
+const DEFAULT_CONCURRENCY: usize = 50;
+
+impl Default for PriceMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PriceMonitor {
     /// Create new price monitor with 50 concurrent requests for speed
     pub fn new() -> Self {
         Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(5))
-                .build()
-                .unwrap(),
+            client: http::shared_client(),
             pools: Vec::new(),
-            semaphore: Arc::new(Semaphore::new(50)), // Increased for speed
+            semaphore: Arc::new(Semaphore::new(DEFAULT_CONCURRENCY)),
+            concurrency: DEFAULT_CONCURRENCY,
+            aggregation: PriceAggregation::default(),
         }
     }
 
+    /// Concurrent symbol lookups (at least 1)
+    pub fn set_concurrency(&mut self, concurrency: usize) {
+        self.concurrency = concurrency.max(1);
+        self.semaphore = Arc::new(Semaphore::new(self.concurrency));
+    }
+
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Per-symbol aggregation for display and downstream gap values
+    pub fn set_aggregation(&mut self, aggregation: PriceAggregation) {
+        self.aggregation = aggregation;
+    }
+
+    /// Aggregated price per symbol with its pool count
+    pub fn aggregate_by_symbol(&self, prices: &[PriceData]) -> Vec<(String, f64, usize)> {
+        let mut by_symbol: HashMap<String, Vec<(f64, f64)>> = HashMap::new();
+        for price in prices {
+            by_symbol.entry(price.symbol.clone()).or_default().push((price.price_usd, price.volume_24h));
+        }
+
+        by_symbol.into_iter()
+            .filter_map(|(symbol, samples)| {
+                let price = self.aggregation.apply(&samples)?;
+                Some((symbol, price, samples.len()))
+            })
+            .collect()
+    }
+
     /// Load saved pool data from JSON files with validation
     pub fn load_pools(&mut self, pools_dir: &Path) -> Result<usize, std::io::Error> {
         let mut loaded = 0;
@@ -59,26 +107,24 @@ impl PriceMonitor {
             let entry = entry?;
             let path = entry.path();
             
-            if path.extension().map(|e| e == "json").unwrap_or(false) {
-                if let Ok(content) = std::fs::read_to_string(&path) {
-                    if let Ok(pools) = serde_json::from_str::<Vec<PoolData>>(&content) {
-                        for pool in pools {
-                            // Validation: skip invalid pools
-                            if !Self::is_valid_pool(&pool) {
-                                skipped += 1;
-                                continue;
-                            }
-                            
-                            self.pools.push(SavedPool {
-                                symbol: pool.symbol,
-                                chain: pool.chain,
-                                dex: pool.dex,
-                                pool_address: pool.pool_address,
-                                pair: pool.pair,
-                                source: pool.source,
-                            });
-                            loaded += 1;
+            if storage::is_pool_file(&path) {
+                if let Some(pools) = storage::read_pools(&path) {
+                    for pool in pools {
+                        // Validation: skip invalid pools
+                        if !Self::is_valid_pool(&pool) {
+                            skipped += 1;
+                            continue;
                         }
+                        
+                        self.pools.push(SavedPool {
+                            symbol: pool.symbol,
+                            chain: pool.chain,
+                            dex: pool.dex,
+                            pool_address: pool.pool_address,
+                            pair: pool.pair,
+                            source: pool.source,
+                        });
+                        loaded += 1;
                     }
                 }
             }
@@ -102,8 +148,8 @@ impl PriceMonitor {
             return false;
         }
         
-        // Skip abnormal prices (too high = likely parsing error)
-        if pool.price_usd > 1_000_000_000.0 {
+        // Skip abnormal prices (NaN/inf, or too high = likely parsing error)
+        if !pool.is_finite_price() || pool.price_usd > 1_000_000_000.0 {
             return false;
         }
         
@@ -138,7 +184,7 @@ impl PriceMonitor {
         println!("   📡 {}개 심볼 조회 중...", symbols.len());
         
         // Fetch prices by symbol using DexScreener (batch)
-        let symbol_prices: Vec<(String, HashMap<String, f64>)> = stream::iter(symbols.into_iter())
+        let symbol_prices: Vec<(String, ChainQuotes)> = stream::iter(symbols)
             .map(|symbol| {
                 let client = self.client.clone();
                 let semaphore = self.semaphore.clone();
@@ -149,7 +195,7 @@ impl PriceMonitor {
                     (symbol, prices)
                 }
             })
-            .buffer_unordered(50)
+            .buffer_unordered(self.concurrency)
             .collect()
             .await;
         
@@ -158,7 +204,7 @@ impl PriceMonitor {
         println!("   ✓ {}/{} 심볼 가격 수신", success_count, symbol_prices.len());
         
         // Build price lookup
-        let mut price_map: HashMap<String, HashMap<String, f64>> = HashMap::new();
+        let mut price_map: HashMap<String, ChainQuotes> = HashMap::new();
         for (symbol, prices) in symbol_prices {
             price_map.insert(symbol, prices);
         }
@@ -171,18 +217,19 @@ impl PriceMonitor {
         
         self.pools.iter()
             .filter_map(|pool| {
-                let price = price_map.get(&pool.symbol)
+                let (price, volume_24h) = price_map.get(&pool.symbol)
                     .and_then(|chain_prices| chain_prices.get(&pool.chain))
                     .copied()
-                    .unwrap_or(0.0);
+                    .unwrap_or((0.0, 0.0));
                 
-                if price > 0.0 {
+                if price.is_finite() && price > 0.0 {
                     Some(PriceData {
                         symbol: pool.symbol.clone(),
                         chain: pool.chain.clone(),
                         dex: pool.dex.clone(),
                         pool_address: pool.pool_address.clone(),
                         price_usd: price,
+                        volume_24h,
                         timestamp,
                     })
                 } else {
@@ -192,63 +239,59 @@ impl PriceMonitor {
             .collect()
     }
 
-    /// Fetch prices for a symbol from DexScreener
-    async fn fetch_symbol_prices(client: &Client, symbol: &str) -> HashMap<String, f64> {
+    /// Fetch (price, 24h volume) per chain for a symbol from DexScreener
+    async fn fetch_symbol_prices(client: &Client, symbol: &str) -> ChainQuotes {
         let mut prices = HashMap::new();
         
         let url = format!("https://api.dexscreener.com/latest/dex/search?q={}", symbol);
         
-        match client.get(&url).send().await {
-            Ok(resp) => {
-                if resp.status().is_success() {
-                    match resp.json::<serde_json::Value>().await {
-                        Ok(data) => {
-                            if let Some(pairs) = data["pairs"].as_array() {
-                                for pair in pairs.iter().take(50) {
-                                    let chain_id = pair["chainId"].as_str().unwrap_or("").to_lowercase();
-                                    let price_str = pair["priceUsd"].as_str().unwrap_or("0");
-                                    let base_symbol = pair["baseToken"]["symbol"].as_str().unwrap_or("");
-                                    
-                                    // Case-insensitive symbol match
-                                    if base_symbol.to_uppercase() == symbol.to_uppercase() {
-                                        if let Ok(price) = price_str.parse::<f64>() {
-                                            if price > 0.0 && price < 1_000_000_000.0 {
-                                                // Store all chain variations
-                                                prices.entry(chain_id.clone()).or_insert(price);
-                                                
-                                                // Also store common aliases
-                                                match chain_id.as_str() {
-                                                    "ethereum" => { prices.entry("eth".to_string()).or_insert(price); },
-                                                    "bsc" | "binance" => { 
-                                                        prices.entry("bsc".to_string()).or_insert(price);
-                                                        prices.entry("binance".to_string()).or_insert(price);
-                                                    },
-                                                    "polygon" | "polygon_pos" | "matic" => {
-                                                        prices.entry("polygon".to_string()).or_insert(price);
-                                                        prices.entry("matic".to_string()).or_insert(price);
-                                                    },
-                                                    "arbitrum" | "arbitrum_one" => {
-                                                        prices.entry("arbitrum".to_string()).or_insert(price);
-                                                    },
-                                                    "optimism" => { prices.entry("optimism".to_string()).or_insert(price); },
-                                                    "base" => { prices.entry("base".to_string()).or_insert(price); },
-                                                    "avalanche" | "avax" => {
-                                                        prices.entry("avalanche".to_string()).or_insert(price);
-                                                        prices.entry("avax".to_string()).or_insert(price);
-                                                    },
-                                                    _ => {},
-                                                }
-                                            }
+        if let Ok(resp) = client.get(&url).timeout(Duration::from_secs(5)).send().await {
+            if resp.status().is_success() {
+                if let Ok(data) = resp.json::<serde_json::Value>().await {
+                    if let Some(pairs) = data["pairs"].as_array() {
+                        for pair in pairs.iter().take(50) {
+                            let chain_id = pair["chainId"].as_str().unwrap_or("").to_lowercase();
+                            let price_str = pair["priceUsd"].as_str().unwrap_or("0");
+                            let base_symbol = pair["baseToken"]["symbol"].as_str().unwrap_or("");
+                            
+                            // Case-insensitive symbol match
+                            if base_symbol.to_uppercase() == symbol.to_uppercase() {
+                                if let Ok(price) = price_str.parse::<f64>() {
+                                    if price > 0.0 && price < 1_000_000_000.0 {
+                                        let volume = pair["volume"]["h24"].as_f64().unwrap_or(0.0);
+                                        let quote = (price, volume);
+                                        // Store all chain variations
+                                        prices.entry(chain_id.clone()).or_insert(quote);
+                                        
+                                        // Also store common aliases
+                                        match chain_id.as_str() {
+                                            "ethereum" => { prices.entry("eth".to_string()).or_insert(quote); },
+                                            "bsc" | "binance" => { 
+                                                prices.entry("bsc".to_string()).or_insert(quote);
+                                                prices.entry("binance".to_string()).or_insert(quote);
+                                            },
+                                            "polygon" | "polygon_pos" | "matic" => {
+                                                prices.entry("polygon".to_string()).or_insert(quote);
+                                                prices.entry("matic".to_string()).or_insert(quote);
+                                            },
+                                            "arbitrum" | "arbitrum_one" => {
+                                                prices.entry("arbitrum".to_string()).or_insert(quote);
+                                            },
+                                            "optimism" => { prices.entry("optimism".to_string()).or_insert(quote); },
+                                            "base" => { prices.entry("base".to_string()).or_insert(quote); },
+                                            "avalanche" | "avax" => {
+                                                prices.entry("avalanche".to_string()).or_insert(quote);
+                                                prices.entry("avax".to_string()).or_insert(quote);
+                                            },
+                                            _ => {},
                                         }
                                     }
                                 }
                             }
-                        },
-                        Err(_) => {},
+                        }
                     }
                 }
-            },
-            Err(_) => {},
+            }
         }
         
         prices
@@ -257,7 +300,8 @@ impl PriceMonitor {
     /// Run continuous price monitoring loop
     pub async fn run_continuous(&self, interval_secs: u64) {
         println!("\n🔄 가격 모니터링 시작 ({}초 간격)", interval_secs);
-        println!("📊 총 {}개 풀, {}개 심볼 추적 중", self.pool_count(), self.get_symbols().len());
+        println!("📊 총 {}개 풀, {}개 심볼 추적 중 (집계: {})",
+            self.pool_count(), self.get_symbols().len(), self.aggregation.label());
         println!("─────────────────────────────────────────");
         
         loop {
@@ -265,12 +309,6 @@ impl PriceMonitor {
             
             let prices = self.fetch_all_prices().await;
             
-            // Group by symbol and calculate average
-            let mut by_symbol: HashMap<String, Vec<&PriceData>> = HashMap::new();
-            for price in prices.iter() {
-                by_symbol.entry(price.symbol.clone()).or_default().push(price);
-            }
-            
             // Print summary
             let elapsed = start.elapsed();
             println!("\n⏱️  {} - {}개 가격 수집 ({:.2}초)", 
@@ -280,13 +318,8 @@ impl PriceMonitor {
             );
             
             // Print symbols with prices (sorted by price descending)
-            let mut symbol_prices: Vec<(String, f64, usize)> = by_symbol.iter()
-                .map(|(sym, p)| {
-                    let avg = p.iter().map(|x| x.price_usd).sum::<f64>() / p.len() as f64;
-                    (sym.clone(), avg, p.len())
-                })
-                .collect();
-            symbol_prices.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+            let mut symbol_prices = self.aggregate_by_symbol(&prices);
+            symbol_prices.sort_by(|a, b| cmp_desc_nan_last(a.1, b.1));
             
             for (symbol, avg_price, pool_count) in symbol_prices.iter().take(15) {
                 println!("  {:8} ${:>12.4} ({:>2} 풀)", symbol, avg_price, pool_count);
@@ -304,3 +337,73 @@ impl PriceMonitor {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(symbol: &str, price_usd: f64) -> PriceData {
+        PriceData {
+            symbol: symbol.to_string(),
+            chain: "ethereum".to_string(),
+            dex: "uniswap".to_string(),
+            pool_address: format!("0x{price_usd}"),
+            price_usd,
+            volume_24h: 1000.0,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn configured_aggregation_is_applied_per_symbol() {
+        let prices: Vec<PriceData> = [2990.0, 3000.0, 3010.0, 30_000.0].iter()
+            .map(|p| price("ETH", *p))
+            .chain([price("BTC", 65_000.0)])
+            .collect();
+        let by_symbol = |monitor: &PriceMonitor| -> HashMap<String, (f64, usize)> {
+            monitor.aggregate_by_symbol(&prices).into_iter().map(|(s, p, n)| (s, (p, n))).collect()
+        };
+
+        let mut monitor = PriceMonitor::new();
+        assert_eq!(by_symbol(&monitor)["ETH"], (3005.0, 4));
+        assert_eq!(by_symbol(&monitor)["BTC"], (65_000.0, 1));
+
+        monitor.set_aggregation(PriceAggregation::Mean);
+        assert_eq!(by_symbol(&monitor)["ETH"], (9750.0, 4));
+    }
+
+    #[test]
+    fn concurrency_sizes_the_lookup_semaphore() {
+        let mut monitor = PriceMonitor::new();
+        assert_eq!((monitor.concurrency(), monitor.semaphore.available_permits()), (50, 50));
+        monitor.set_concurrency(4);
+        assert_eq!((monitor.concurrency(), monitor.semaphore.available_permits()), (4, 4));
+        monitor.set_concurrency(0);
+        assert_eq!((monitor.concurrency(), monitor.semaphore.available_permits()), (1, 1));
+    }
+
+    #[test]
+    fn non_finite_prices_fail_validation() {
+        let pool = |price_usd: f64| PoolData {
+            symbol: "ETH".to_string(),
+            pair: "WETH/USDC".to_string(),
+            pool_address: "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640".to_string(),
+            price_usd,
+            ..PoolData::default()
+        };
+        assert!(PriceMonitor::is_valid_pool(&pool(3000.0)));
+        for bad in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 2e9] {
+            assert!(!PriceMonitor::is_valid_pool(&pool(bad)), "{bad}");
+        }
+    }
+
+    #[test]
+    fn a_nan_price_does_not_break_aggregation_or_sorting() {
+        let prices = [price("ETH", f64::NAN), price("ETH", 3000.0), price("BTC", f64::NAN), price("SOL", 150.0)];
+        let mut symbol_prices = PriceMonitor::new().aggregate_by_symbol(&prices);
+        symbol_prices.sort_by(|a, b| cmp_desc_nan_last(a.1, b.1));
+        // BTC had nothing usable; ETH ignores its NaN sample
+        let got: Vec<(&str, f64)> = symbol_prices.iter().map(|(s, p, _)| (s.as_str(), *p)).collect();
+        assert_eq!(got, [("ETH", 3000.0), ("SOL", 150.0)]);
+    }
+}
@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use crate::models::PoolData;
+
+/// Per-chain preferred source ordering (first = most trusted)
+#[derive(Debug, Clone, Default)]
+pub struct SourcePriority {
+    per_chain: HashMap<String, Vec<String>>,
+}
+
+impl SourcePriority {
+    pub fn new(per_chain: &HashMap<String, Vec<String>>) -> Self {
+        Self {
+            per_chain: per_chain.iter()
+                .map(|(chain, sources)| {
+                    (chain.to_lowercase(), sources.iter().map(|s| s.to_lowercase()).collect())
+                })
+                .collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.per_chain.is_empty()
+    }
+
+    /// Position of `source` in the chain's ordering (`None` = not listed)
+    pub fn rank(&self, chain: &str, source: &str) -> Option<usize> {
+        let order = self.per_chain.get(&chain.to_lowercase())?;
+        let source = source.to_lowercase();
+        order.iter().position(|s| *s == source)
+    }
+
+    /// Whether `candidate`'s data should replace `current`'s for a pool on `chain`.
+    /// Unlisted sources rank last; two unlisted sources are never preferred over each other.
+    pub fn prefers(&self, chain: &str, candidate: &str, current: &str) -> bool {
+        match (self.rank(chain, candidate), self.rank(chain, current)) {
+            (Some(a), Some(b)) => a < b,
+            (Some(_), None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Identity of the on-chain pool independent of the reporting source.
+/// Aggregator quotes have no pool address and are never reconciled.
+pub fn pool_identity(pool: &PoolData) -> Option<String> {
+    let address = pool.pool_address.trim();
+    if address.is_empty() || address.eq_ignore_ascii_case("aggregated") {
+        return None;
+    }
+    Some(pool.dedup_key())
+}
+
+/// The report currently kept for a pool identity during a collection cycle
+#[derive(Debug, Clone)]
+pub struct Claim {
+    pub source: String,
+    pub has_lp: bool,
+    pub has_volume: bool,
+}
+
+impl Claim {
+    pub fn of(pool: &PoolData) -> Self {
+        Self {
+            source: pool.source.clone(),
+            has_lp: pool.lp_reserve_usd > 0.0,
+            has_volume: pool.volume_24h > 0.0,
+        }
+    }
+}
+
+/// Whether `candidate` should replace the kept report for the same pool:
+/// configured source priority first, then richer data (non-zero LP, then volume).
+/// Equally rich reports replace (newer data wins).
+pub fn should_replace(priority: &SourcePriority, kept: &Claim, candidate: &PoolData) -> bool {
+    if priority.prefers(&candidate.chain, &candidate.source, &kept.source) {
+        return true;
+    }
+    if priority.prefers(&candidate.chain, &kept.source, &candidate.source) {
+        return false;
+    }
+    let candidate = Claim::of(candidate);
+    (candidate.has_lp, candidate.has_volume) >= (kept.has_lp, kept.has_volume)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn priority() -> SourcePriority {
+        SourcePriority::new(&HashMap::from([
+            ("Ethereum".to_string(), vec!["Gecko".to_string(), "dexscreener".to_string()]),
+            ("base".to_string(), vec!["dexscreener".to_string(), "gecko".to_string()]),
+        ]))
+    }
+
+    fn report(chain: &str, source: &str, price_usd: f64) -> PoolData {
+        PoolData {
+            symbol: "ETH".to_string(),
+            chain: chain.to_string(),
+            pool_address: "0xpool".to_string(),
+            price_usd,
+            lp_reserve_usd: 1_000_000.0,
+            volume_24h: 100_000.0,
+            source: source.to_string(),
+            ..PoolData::default()
+        }
+    }
+
+    /// Price kept after seeing the reports in order, as the collector does
+    fn chosen(priority: &SourcePriority, reports: &[PoolData]) -> f64 {
+        let mut kept = &reports[0];
+        for candidate in &reports[1..] {
+            if should_replace(priority, &Claim::of(kept), candidate) {
+                kept = candidate;
+            }
+        }
+        kept.price_usd
+    }
+
+    #[test]
+    fn chain_ordering_picks_the_preferred_source() {
+        let priority = priority();
+        for chain in ["ethereum", "base"] {
+            let gecko = report(chain, "gecko", 3000.0);
+            let dexscreener = report(chain, "dexscreener", 3010.0);
+            let expected = if chain == "ethereum" { 3000.0 } else { 3010.0 };
+            // Arrival order doesn't matter
+            assert_eq!(chosen(&priority, &[gecko.clone(), dexscreener.clone()]), expected, "{chain}");
+            assert_eq!(chosen(&priority, &[dexscreener, gecko]), expected, "{chain}");
+        }
+    }
+
+    #[test]
+    fn unlisted_chain_falls_back_to_richer_data() {
+        let priority = priority();
+        let rich = report("polygon", "gecko", 1.0);
+        let thin = PoolData { lp_reserve_usd: 0.0, ..report("polygon", "dexscreener", 2.0) };
+        assert_eq!(chosen(&priority, &[rich.clone(), thin.clone()]), 1.0);
+        assert_eq!(chosen(&priority, &[thin, rich]), 1.0);
+        assert_eq!(priority.rank("polygon", "gecko"), None);
+        assert_eq!(priority.rank("ETHEREUM", "GECKO"), Some(0));
+    }
+
+    #[test]
+    fn aggregator_quotes_have_no_identity() {
+        assert_eq!(pool_identity(&report("ethereum", "gecko", 1.0)).as_deref(), Some("ethereum:0xpool"));
+        assert!(pool_identity(&PoolData { pool_address: "aggregated".to_string(), ..PoolData::default() }).is_none());
+        assert!(pool_identity(&PoolData { pool_address: " ".to_string(), ..PoolData::default() }).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use futures::future::join_all;
+use serde::Serialize;
+use crate::models::PoolData;
+use crate::sources::{PoolSource, SourceError};
+
+/// Sources whose failure means the collector is effectively blind
+pub const PRIMARY_SOURCES: [&str; 2] = ["DexScreener", "GeckoTerminal"];
+
+/// Symbol every source should know
+pub const PROBE_SYMBOL: &str = "ETH";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbeStatus {
+    /// Reachable, parseable and returned pools
+    Ok(usize),
+    /// Reachable and parseable but no pools for the probe symbol
+    Empty,
+    RateLimited,
+    ParseError(String),
+    Unreachable(String),
+    Timeout,
+}
+
+impl ProbeStatus {
+    pub fn is_ok(&self) -> bool {
+        matches!(self, ProbeStatus::Ok(_))
+    }
+
+    fn from_result(result: Result<Result<usize, SourceError>, tokio::time::error::Elapsed>) -> Self {
+        match result {
+            Ok(Ok(0)) => ProbeStatus::Empty,
+            Ok(Ok(count)) => ProbeStatus::Ok(count),
+            Ok(Err(SourceError::RateLimit(_))) => ProbeStatus::RateLimited,
+            Ok(Err(SourceError::Parse(e))) => ProbeStatus::ParseError(e),
+            Ok(Err(e)) => ProbeStatus::Unreachable(e.to_string()),
+            Err(_) => ProbeStatus::Timeout,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    pub source: &'static str,
+    pub primary: bool,
+    pub status: ProbeStatus,
+    pub elapsed_ms: u128,
+}
+
+#[derive(Debug, Clone)]
+pub struct SelfTestReport {
+    pub results: Vec<ProbeResult>,
+}
+
+impl SelfTestReport {
+    /// Non-zero when every primary source failed
+    pub fn exit_code(&self) -> i32 {
+        let primaries: Vec<&ProbeResult> = self.results.iter().filter(|r| r.primary).collect();
+        if !primaries.is_empty() && primaries.iter().all(|r| !r.status.is_ok()) {
+            1
+        } else {
+            0
+        }
+    }
+
+    pub fn print(&self) {
+        println!("\n🩺 소스 연결 점검 ({})", PROBE_SYMBOL);
+        println!("─────────────────────────────────────────");
+        for r in &self.results {
+            let icon = match r.status {
+                ProbeStatus::Ok(_) => "✅",
+                ProbeStatus::Empty | ProbeStatus::RateLimited => "⚠️",
+                _ => "❌",
+            };
+            let detail = match &r.status {
+                ProbeStatus::Ok(count) => format!("{}개 풀", count),
+                ProbeStatus::Empty => "연결/파싱 OK, 데이터 없음".to_string(),
+                ProbeStatus::RateLimited => "rate limit".to_string(),
+                ProbeStatus::ParseError(e) => format!("파싱 실패: {}", e),
+                ProbeStatus::Unreachable(e) => format!("연결 실패: {}", e),
+                ProbeStatus::Timeout => "타임아웃".to_string(),
+            };
+            println!("  {} {:14}{} {} [{}ms]",
+                icon, r.source, if r.primary { "*" } else { " " }, detail, r.elapsed_ms);
+        }
+        println!("  (* = 주요 소스)");
+    }
+}
+
+/// Probe each source once with a known symbol
+pub async fn run(sources: &[Arc<dyn PoolSource>], timeout: Duration) -> SelfTestReport {
+    let probes = sources.iter().map(|source| async move {
+        let start = Instant::now();
+        let result = tokio::time::timeout(timeout, async {
+            source.fetch_pools(PROBE_SYMBOL).await.map(|pools| pools.len())
+        })
+        .await;
+
+        ProbeResult {
+            source: source.name(),
+            primary: PRIMARY_SOURCES.contains(&source.name()),
+            status: ProbeStatus::from_result(result),
+            elapsed_ms: start.elapsed().as_millis(),
+        }
+    });
+
+    SelfTestReport {
+        results: join_all(probes).await,
+    }
+}
+
+/// One source's live answer for a symbol (`/sources/debug/:symbol`, `debug <symbol>`)
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceDebug {
+    pub source: &'static str,
+    pub pool_count: usize,
+    pub elapsed_ms: u128,
+    /// First few pools as returned (before filtering)
+    pub pools: Vec<PoolData>,
+    /// Set when the call failed or timed out
+    pub error: Option<String>,
+}
+
+/// Fetch `symbol` from every source concurrently, keeping up to `sample` pools each
+pub async fn debug_symbol(sources: &[Arc<dyn PoolSource>], symbol: &str, timeout: Duration, sample: usize) -> Vec<SourceDebug> {
+    let calls = sources.iter().map(|source| async move {
+        let start = Instant::now();
+        let result = tokio::time::timeout(timeout, source.fetch_pools(symbol)).await;
+        let elapsed_ms = start.elapsed().as_millis();
+
+        let (pool_count, pools, error) = match result {
+            Ok(Ok(pools)) => (pools.len(), pools.into_iter().take(sample).collect(), None),
+            Ok(Err(e)) => (0, Vec::new(), Some(e.to_string())),
+            Err(_) => (0, Vec::new(), Some(format!("timeout after {}ms", timeout.as_millis()))),
+        };
+        SourceDebug { source: source.name(), pool_count, elapsed_ms, pools, error }
+    });
+    join_all(calls).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sources::mock::MockSource;
+
+    const TIMEOUT: Duration = Duration::from_millis(200);
+
+    fn eth_pools(count: usize) -> Vec<PoolData> {
+        (0..count).map(|i| PoolData { symbol: "ETH".to_string(), pool_address: format!("0x{i}"), ..PoolData::default() }).collect()
+    }
+
+    fn status(report: &SelfTestReport, source: &str) -> ProbeStatus {
+        report.results.iter().find(|r| r.source == source).unwrap().status.clone()
+    }
+
+    #[tokio::test]
+    async fn report_classifies_each_source_state() {
+        let sources: Vec<Arc<dyn PoolSource>> = vec![
+            Arc::new(MockSource::new("DexScreener").with_pools("ETH", eth_pools(3))),
+            Arc::new(MockSource::new("GeckoTerminal")),
+            Arc::new(MockSource::failing("Matcha", SourceError::RateLimit(None))),
+            Arc::new(MockSource::failing("OpenOcean", SourceError::Parse("expected value".to_string()))),
+            Arc::new(MockSource::failing("ParaSwap", SourceError::Http(502))),
+            Arc::new(MockSource::new("Jupiter").with_delay(TIMEOUT * 5)),
+        ];
+        let report = run(&sources, TIMEOUT).await;
+
+        assert_eq!(report.results.len(), 6);
+        assert_eq!(status(&report, "DexScreener"), ProbeStatus::Ok(3));
+        assert_eq!(status(&report, "GeckoTerminal"), ProbeStatus::Empty);
+        assert_eq!(status(&report, "Matcha"), ProbeStatus::RateLimited);
+        assert_eq!(status(&report, "OpenOcean"), ProbeStatus::ParseError("expected value".to_string()));
+        assert_eq!(status(&report, "ParaSwap"), ProbeStatus::Unreachable("HTTP 502".to_string()));
+        assert_eq!(status(&report, "Jupiter"), ProbeStatus::Timeout);
+        let primaries: Vec<&str> = report.results.iter().filter(|r| r.primary).map(|r| r.source).collect();
+        assert_eq!(primaries, ["DexScreener", "GeckoTerminal"]);
+        // One primary answered
+        assert_eq!(report.exit_code(), 0);
+    }
+
+    #[tokio::test]
+    async fn exit_code_is_nonzero_only_when_every_primary_fails() {
+        let all_primaries_down: Vec<Arc<dyn PoolSource>> = vec![
+            Arc::new(MockSource::failing("DexScreener", SourceError::Network("refused".to_string()))),
+            Arc::new(MockSource::new("GeckoTerminal")),
+            Arc::new(MockSource::new("Jupiter").with_pools("ETH", eth_pools(1))),
+        ];
+        assert_eq!(run(&all_primaries_down, TIMEOUT).await.exit_code(), 1);
+
+        // Without primary sources configured there is nothing to fail on
+        let secondary_only: Vec<Arc<dyn PoolSource>> = vec![Arc::new(MockSource::failing("Jupiter", SourceError::Http(500)))];
+        assert_eq!(run(&secondary_only, TIMEOUT).await.exit_code(), 0);
+    }
+
+    #[tokio::test]
+    async fn debug_report_lists_every_source_with_its_outcome() {
+        let sources: Vec<Arc<dyn PoolSource>> = vec![
+            Arc::new(MockSource::new("DexScreener").with_pools("ETH", eth_pools(5))),
+            Arc::new(MockSource::failing("Matcha", SourceError::Http(502))),
+            Arc::new(MockSource::hanging("Jupiter")),
+        ];
+        let report = debug_symbol(&sources, "eth", TIMEOUT, 3).await;
+        let names: Vec<&str> = report.iter().map(|r| r.source).collect();
+        assert_eq!(names, ["DexScreener", "Matcha", "Jupiter"]);
+
+        // The count is the full answer, the sample is capped
+        let ok = &report[0];
+        assert_eq!((ok.pool_count, ok.pools.len(), ok.error.as_deref()), (5, 3, None));
+        assert_eq!(ok.pools[0].pool_address, "0x0");
+
+        let failed = &report[1];
+        assert_eq!((failed.pool_count, failed.pools.len()), (0, 0));
+        assert_eq!(failed.error.as_deref(), Some(SourceError::Http(502).to_string().as_str()));
+
+        let stuck = &report[2];
+        assert_eq!(stuck.error.as_deref(), Some("timeout after 200ms"));
+        assert!(stuck.elapsed_ms >= 200);
+    }
+}
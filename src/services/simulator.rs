@@ -0,0 +1,277 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use crate::config::CostConfig;
+use crate::models::PoolData;
+
+/// Fee / slippage / gas assumptions used to turn quoted prices into executable ones
+#[derive(Debug, Clone)]
+pub struct CostModel {
+    default_fee: f64,
+    zero_lp_slippage: f64,
+    default_gas_usd: f64,
+    gas_usd: HashMap<String, f64>,
+    reference_trade_usd: f64,
+}
+
+impl CostModel {
+    pub fn new(config: &CostConfig) -> Self {
+        Self {
+            default_fee: config.default_fee,
+            zero_lp_slippage: config.zero_lp_slippage,
+            default_gas_usd: config.default_gas_usd,
+            gas_usd: config.gas_usd.iter()
+                .map(|(chain, gas)| (chain.to_lowercase(), *gas))
+                .collect(),
+            reference_trade_usd: config.reference_trade_usd,
+        }
+    }
+
+    /// Trade size used to turn gas into a fraction of the spread
+    pub fn reference_trade_usd(&self) -> f64 {
+        self.reference_trade_usd
+    }
+
+    /// Swap fee as a fraction (pool fee tier when known)
+    pub fn fee_rate(&self, pool: &PoolData) -> f64 {
+        pool.fee_tier.unwrap_or(self.default_fee)
+    }
+
+    /// Estimated gas cost of one swap on `chain`, in USD
+    pub fn gas_usd(&self, chain: &str) -> f64 {
+        self.gas_usd.get(&chain.to_lowercase())
+            .copied()
+            .unwrap_or(self.default_gas_usd)
+    }
+
+    /// Price impact fraction for trading `trade_usd` against a constant-product pool
+    /// (one side of the pool holds roughly half the LP value)
+    pub fn slippage_rate(&self, pool: &PoolData, trade_usd: f64) -> f64 {
+        if pool.lp_reserve_usd <= 0.0 {
+            return self.zero_lp_slippage;
+        }
+        let side_reserve = pool.lp_reserve_usd / 2.0;
+        trade_usd / (side_reserve + trade_usd)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SimulationRequest {
+    pub symbol: String,
+    pub amount_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedLeg {
+    pub source: String,
+    pub dex: String,
+    pub chain: String,
+    pub pool_address: String,
+    pub quoted_price: f64,
+    pub execution_price: f64,
+    pub fee_usd: f64,
+    pub slippage_usd: f64,
+    pub gas_usd: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedRoute {
+    pub buy: SimulatedLeg,
+    pub sell: SimulatedLeg,
+    pub tokens: f64,
+    pub proceeds_usd: f64,
+    pub total_fees_usd: f64,
+    pub total_slippage_usd: f64,
+    pub total_gas_usd: f64,
+    pub net_pnl_usd: f64,
+    pub net_pct: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationResult {
+    pub symbol: String,
+    pub amount_usd: f64,
+    pub opportunity: bool,
+    pub route: Option<SimulatedRoute>,
+    pub message: String,
+}
+
+/// Simulate buying `amount_usd` on one pool and selling on another
+pub fn simulate(costs: &CostModel, pools: &[Arc<PoolData>], req: &SimulationRequest) -> SimulationResult {
+    let symbol = req.symbol.to_uppercase();
+    let candidates: Vec<&PoolData> = pools.iter()
+        .map(|p| p.as_ref())
+        .filter(|p| p.symbol.eq_ignore_ascii_case(&symbol))
+        .filter(|p| p.price_usd.is_finite() && p.price_usd > 0.0)
+        .collect();
+
+    let no_route = |message: String| SimulationResult {
+        symbol: symbol.clone(),
+        amount_usd: req.amount_usd,
+        opportunity: false,
+        route: None,
+        message,
+    };
+
+    if !(req.amount_usd.is_finite() && req.amount_usd > 0.0) {
+        return no_route("amount_usd must be positive".to_string());
+    }
+    if candidates.len() < 2 {
+        return no_route(format!("no opportunity: only {} pool(s) cached", candidates.len()));
+    }
+
+    let mut best: Option<SimulatedRoute> = None;
+    for buy in &candidates {
+        for sell in &candidates {
+            if std::ptr::eq(*buy, *sell) || buy.pool_address == sell.pool_address {
+                continue;
+            }
+            let route = simulate_route(costs, buy, sell, req.amount_usd);
+            if best.as_ref().is_none_or(|b| route.net_pnl_usd > b.net_pnl_usd) {
+                best = Some(route);
+            }
+        }
+    }
+
+    match best {
+        Some(route) if route.net_pnl_usd > 0.0 => SimulationResult {
+            symbol,
+            amount_usd: req.amount_usd,
+            opportunity: true,
+            message: format!("net ${:.2} ({:+.3}%)", route.net_pnl_usd, route.net_pct),
+            route: Some(route),
+        },
+        Some(route) => SimulationResult {
+            symbol,
+            amount_usd: req.amount_usd,
+            opportunity: false,
+            message: format!("no opportunity: best route nets ${:.2}", route.net_pnl_usd),
+            route: Some(route),
+        },
+        None => no_route("no opportunity: no distinct pool pair".to_string()),
+    }
+}
+
+fn simulate_route(costs: &CostModel, buy: &PoolData, sell: &PoolData, amount_usd: f64) -> SimulatedRoute {
+    // Buy leg: fee taken from input, price impact raises the execution price
+    let buy_fee = amount_usd * costs.fee_rate(buy);
+    let buy_in = amount_usd - buy_fee;
+    let buy_slip_rate = costs.slippage_rate(buy, buy_in);
+    let buy_exec = buy.price_usd * (1.0 + buy_slip_rate);
+    let tokens = buy_in / buy_exec;
+    let buy_slippage = buy_in - tokens * buy.price_usd;
+
+    // Sell leg: price impact lowers the execution price, fee taken from output
+    let sell_value = tokens * sell.price_usd;
+    let sell_slip_rate = costs.slippage_rate(sell, sell_value);
+    let sell_exec = sell.price_usd * (1.0 - sell_slip_rate);
+    let gross_out = tokens * sell_exec;
+    let sell_slippage = sell_value - gross_out;
+    let sell_fee = gross_out * costs.fee_rate(sell);
+    let proceeds = gross_out - sell_fee;
+
+    let buy_gas = costs.gas_usd(&buy.chain);
+    let sell_gas = costs.gas_usd(&sell.chain);
+    let total_gas = buy_gas + sell_gas;
+    let net = proceeds - amount_usd - total_gas;
+
+    SimulatedRoute {
+        buy: leg(buy, buy_exec, buy_fee, buy_slippage, buy_gas),
+        sell: leg(sell, sell_exec, sell_fee, sell_slippage, sell_gas),
+        tokens,
+        proceeds_usd: proceeds,
+        total_fees_usd: buy_fee + sell_fee,
+        total_slippage_usd: buy_slippage + sell_slippage,
+        total_gas_usd: total_gas,
+        net_pnl_usd: net,
+        net_pct: net / amount_usd * 100.0,
+    }
+}
+
+fn leg(pool: &PoolData, execution_price: f64, fee_usd: f64, slippage_usd: f64, gas_usd: f64) -> SimulatedLeg {
+    SimulatedLeg {
+        source: pool.source.clone(),
+        dex: pool.dex.clone(),
+        chain: pool.chain.clone(),
+        pool_address: pool.pool_address.clone(),
+        quoted_price: pool.price_usd,
+        execution_price,
+        fee_usd,
+        slippage_usd,
+        gas_usd,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::pool;
+
+    fn costs() -> CostModel {
+        CostModel::new(&CostConfig {
+            gas_usd: [("ethereum".to_string(), 10.0)].into_iter().collect(),
+            ..CostConfig::default()
+        })
+    }
+
+    fn venue(source: &str, price: f64, lp: f64, fee: f64) -> Arc<PoolData> {
+        pool("ETH", source, price, 0).with_lp(lp).with_fee_tier(Some(fee)).shared()
+    }
+
+    fn request(amount_usd: f64) -> SimulationRequest {
+        SimulationRequest { symbol: "eth".to_string(), amount_usd }
+    }
+
+    #[test]
+    fn buys_cheapest_sells_dearest_with_manual_pnl() {
+        let pools = vec![
+            venue("mid", 3000.0, 8_000_000.0, 0.003),
+            venue("cheap", 2900.0, 2_000_000.0, 0.003),
+            venue("dear", 3100.0, 4_000_000.0, 0.0005),
+        ];
+        let result = simulate(&costs(), &pools, &request(1000.0));
+        assert!(result.opportunity, "{}", result.message);
+        assert_eq!(result.symbol, "ETH");
+        let route = result.route.unwrap();
+        assert_eq!(route.buy.source, "cheap");
+        assert_eq!(route.sell.source, "dear");
+
+        // Same numbers by hand: 0.3% fee in, x/(R/2 + x) impact each way, 0.05% fee out, $10 gas per leg
+        let buy_in = 1000.0 - 3.0;
+        let buy_exec = 2900.0 * (1.0 + buy_in / (1_000_000.0 + buy_in));
+        let tokens = buy_in / buy_exec;
+        let sell_value = tokens * 3100.0;
+        let gross = tokens * 3100.0 * (1.0 - sell_value / (2_000_000.0 + sell_value));
+        let proceeds = gross * (1.0 - 0.0005);
+        let net = proceeds - 1000.0 - 20.0;
+
+        assert!((route.tokens - tokens).abs() < 1e-12);
+        assert!((route.buy.execution_price - buy_exec).abs() < 1e-9);
+        assert!((route.total_fees_usd - (3.0 + gross * 0.0005)).abs() < 1e-9);
+        assert_eq!(route.total_gas_usd, 20.0);
+        assert!((route.proceeds_usd - proceeds).abs() < 1e-9);
+        assert!((route.net_pnl_usd - net).abs() < 1e-9);
+        assert!((route.net_pct - net / 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn spread_eaten_by_costs_is_no_opportunity() {
+        let pools = vec![
+            venue("a", 3000.0, 1_000_000.0, 0.003),
+            venue("b", 3010.0, 1_000_000.0, 0.003),
+        ];
+        let result = simulate(&costs(), &pools, &request(1000.0));
+        assert!(!result.opportunity);
+        assert!(result.message.starts_with("no opportunity"), "{}", result.message);
+        // Best route still reported so the caller can see by how much it misses
+        assert!(result.route.unwrap().net_pnl_usd < 0.0);
+    }
+
+    #[test]
+    fn rejects_bad_amount_and_single_pool() {
+        let pools = vec![venue("a", 3000.0, 1_000_000.0, 0.003)];
+        assert_eq!(simulate(&costs(), &pools, &request(-5.0)).message, "amount_usd must be positive");
+        let single = simulate(&costs(), &pools, &request(100.0));
+        assert!(!single.opportunity && single.route.is_none());
+    }
+}
@@ -0,0 +1,226 @@
+use std::path::Path;
+use chrono::NaiveDate;
+use parking_lot::Mutex;
+use rusqlite::{params, Connection};
+use crate::config::AlertFileFormat;
+use crate::models::{ArbitrageAlert, PoolData};
+use super::storage::StorageBackend;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS pools (
+        chain          TEXT    NOT NULL,
+        pool_address   TEXT    NOT NULL,
+        timestamp      INTEGER NOT NULL,
+        symbol         TEXT    NOT NULL,
+        dex            TEXT    NOT NULL,
+        pair           TEXT    NOT NULL,
+        price_usd      REAL    NOT NULL,
+        lp_reserve_usd REAL    NOT NULL,
+        volume_24h     REAL    NOT NULL,
+        fee_tier       REAL,
+        fee_estimated  INTEGER NOT NULL DEFAULT 0,
+        base_token     TEXT,
+        quote_token    TEXT,
+        source         TEXT    NOT NULL,
+        PRIMARY KEY (chain, pool_address, timestamp)
+    );
+    CREATE INDEX IF NOT EXISTS idx_pools_symbol ON pools (symbol);
+    CREATE INDEX IF NOT EXISTS idx_pools_timestamp ON pools (timestamp);
+    CREATE TABLE IF NOT EXISTS alerts (
+        timestamp INTEGER NOT NULL,
+        symbol    TEXT    NOT NULL,
+        alert     TEXT    NOT NULL
+    );
+";
+
+/// Pools (and alerts) in a single SQLite database; a pool re-saved with the
+/// same timestamp (e.g. still cached) overwrites its earlier row
+pub struct SqliteStorage {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStorage {
+    pub fn open(path: &str) -> rusqlite::Result<Self> {
+        if let Some(dir) = Path::new(path).parent() {
+            std::fs::create_dir_all(dir).ok();
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(SCHEMA)?;
+        migrate(&conn)?;
+        tracing::info!("✓ SQLite storage: {}", path);
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Upsert pools in one transaction
+    pub fn insert_pools(&self, pools: &[PoolData]) -> rusqlite::Result<usize> {
+        let mut conn = self.conn.lock();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT OR REPLACE INTO pools
+                 (chain, pool_address, timestamp, symbol, dex, pair, price_usd, lp_reserve_usd, volume_24h, fee_tier, fee_estimated,
+                  base_token, quote_token, source)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            )?;
+            for pool in pools {
+                stmt.execute(params![
+                    pool.chain, pool.pool_address, pool.timestamp, pool.symbol, pool.dex, pool.pair,
+                    pool.price_usd, pool.lp_reserve_usd, pool.volume_24h, pool.fee_tier,
+                    pool.fee_estimated, pool.base_token, pool.quote_token, pool.source,
+                ])?;
+            }
+        }
+        tx.commit()?;
+        Ok(pools.len())
+    }
+
+    /// Rows of `symbol` with `from <= timestamp <= to` (unix seconds), oldest first
+    pub fn query_range(&self, symbol: &str, from: i64, to: i64) -> rusqlite::Result<Vec<PoolData>> {
+        let conn = self.conn.lock();
+        let mut stmt = conn.prepare_cached(
+            "SELECT symbol, chain, dex, pool_address, pair, price_usd, lp_reserve_usd, volume_24h, fee_tier, fee_estimated,
+                    base_token, quote_token, source, timestamp
+             FROM pools WHERE symbol = ?1 AND timestamp BETWEEN ?2 AND ?3
+             ORDER BY timestamp",
+        )?;
+        let rows = stmt.query_map(params![symbol, from, to], |row| {
+            Ok(PoolData {
+                symbol: row.get(0)?,
+                chain: row.get(1)?,
+                dex: row.get(2)?,
+                pool_address: row.get(3)?,
+                pair: row.get(4)?,
+                price_usd: row.get(5)?,
+                lp_reserve_usd: row.get(6)?,
+                volume_24h: row.get(7)?,
+                fee_tier: row.get(8)?,
+                fee_estimated: row.get(9)?,
+                base_token: row.get(10)?,
+                quote_token: row.get(11)?,
+                source: row.get(12)?,
+                timestamp: row.get(13)?,
+            })
+        })?;
+        rows.collect()
+    }
+}
+
+/// Columns added after the first schema (name, declaration)
+const ADDED_COLUMNS: &[(&str, &str)] = &[
+    ("fee_estimated", "INTEGER NOT NULL DEFAULT 0"),
+    ("base_token", "TEXT"),
+    ("quote_token", "TEXT"),
+];
+
+/// Add missing `ADDED_COLUMNS` to databases created before them
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    for (column, decl) in ADDED_COLUMNS {
+        let exists = conn.prepare("SELECT 1 FROM pragma_table_info('pools') WHERE name = ?1")?
+            .exists([column])?;
+        if !exists {
+            conn.execute_batch(&format!("ALTER TABLE pools ADD COLUMN {} {}", column, decl))?;
+        }
+    }
+    Ok(())
+}
+
+impl StorageBackend for SqliteStorage {
+    fn save_cycle(&self, pools: &[PoolData]) {
+        match self.insert_pools(pools) {
+            Ok(count) => tracing::debug!("💾 Saved {} pools to SQLite", count),
+            Err(e) => tracing::warn!("⚠️ Failed to save pools to SQLite: {}", e),
+        }
+    }
+
+    fn save_snapshot(&self, pools: &[PoolData]) {
+        self.save_cycle(pools);
+    }
+
+    /// Alerts are stored as JSON rows; the file format setting doesn't apply
+    fn save_alerts(&self, alerts: &[ArbitrageAlert], _format: AlertFileFormat) {
+        if alerts.is_empty() {
+            return;
+        }
+        let now = chrono::Utc::now().timestamp();
+        let result = (|| -> rusqlite::Result<()> {
+            let mut conn = self.conn.lock();
+            let tx = conn.transaction()?;
+            {
+                let mut stmt = tx.prepare_cached("INSERT INTO alerts (timestamp, symbol, alert) VALUES (?1, ?2, ?3)")?;
+                for alert in alerts {
+                    let json = serde_json::to_string(alert).unwrap_or_default();
+                    stmt.execute(params![now, alert.symbol, json])?;
+                }
+            }
+            tx.commit()
+        })();
+        if let Err(e) = result {
+            tracing::warn!("⚠️ Failed to save alerts to SQLite: {}", e);
+        }
+    }
+
+    fn load_symbol_range(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Vec<PoolData> {
+        let start = from.and_hms_opt(0, 0, 0).map(|t| t.and_utc().timestamp()).unwrap_or(i64::MIN);
+        let end = to.and_hms_opt(23, 59, 59).map(|t| t.and_utc().timestamp()).unwrap_or(i64::MAX);
+        self.query_range(symbol, start, end).unwrap_or_else(|e| {
+            tracing::warn!("⚠️ SQLite range query failed: {}", e);
+            Vec::new()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{self, temp_dir};
+
+    fn storage() -> SqliteStorage {
+        SqliteStorage::open(temp_dir("sqlite").join("pools.db").to_str().unwrap()).unwrap()
+    }
+
+    /// Optional columns filled in, so the round trip covers them
+    fn pool(symbol: &str, address: &str, price_usd: f64, timestamp: i64) -> PoolData {
+        test_util::pool(symbol, "test", price_usd, timestamp)
+            .at_address(address)
+            .with_fee_tier(Some(0.0005))
+            .with_tokens(Some("0xbase".to_string()), None)
+    }
+
+    #[test]
+    fn insert_then_range_query() {
+        let storage = storage();
+        let pools = [
+            pool("ETH", "0x1", 3000.0, 100),
+            pool("ETH", "0x2", 3010.0, 200),
+            pool("ETH", "0x1", 3020.0, 300),
+            pool("BTC", "0x3", 60_000.0, 200),
+        ];
+        assert_eq!(storage.insert_pools(&pools).unwrap(), 4);
+
+        let rows = storage.query_range("ETH", 100, 200).unwrap();
+        assert_eq!(rows, pools[..2], "all columns round-trip, oldest first");
+        assert_eq!(storage.query_range("ETH", 0, i64::MAX).unwrap().len(), 3);
+        assert_eq!(storage.query_range("BTC", 201, 400).unwrap(), []);
+
+        // Same (chain, address, timestamp) replaces the row
+        storage.insert_pools(&[pool("ETH", "0x1", 2999.0, 100)]).unwrap();
+        let rows = storage.query_range("ETH", 100, 100).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].price_usd, 2999.0);
+    }
+
+    #[test]
+    fn date_range_covers_whole_days() {
+        let storage = storage();
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let at = |d: u32, secs: i64| day(d).and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() + secs;
+        storage.save_cycle(&[
+            pool("ETH", "0x1", 3000.0, at(1, 0)),
+            pool("ETH", "0x1", 3100.0, at(2, 86_399)),
+            pool("ETH", "0x1", 3200.0, at(3, 0)),
+        ]);
+
+        let prices: Vec<f64> = storage.load_symbol_range("ETH", day(1), day(2)).iter().map(|p| p.price_usd).collect();
+        assert_eq!(prices, [3000.0, 3100.0]);
+    }
+}
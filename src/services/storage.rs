@@ -1,13 +1,81 @@
-use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
-use std::path::PathBuf;
-use chrono::{Utc, Datelike};
-use crate::models::PoolData;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use chrono::{NaiveDate, NaiveDateTime, Utc, Datelike};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde_json::Value;
+use crate::config::{AlertFileFormat, StorageBackendKind, StorageConfig};
+use crate::models::{ArbitrageAlert, PoolData};
+use super::gap_monitor::Gap;
+
+/// Persistence surface shared by the JSON file store and the SQLite store
+pub trait StorageBackend: Send + Sync {
+    /// Persist one collection cycle's pools
+    fn save_cycle(&self, pools: &[PoolData]);
+    /// One-off write of the current pools (shutdown)
+    fn save_snapshot(&self, pools: &[PoolData]);
+    fn save_alerts(&self, alerts: &[ArbitrageAlert], format: AlertFileFormat);
+    /// Pools of `symbol` stored between `from` and `to` (inclusive), sorted by timestamp
+    fn load_symbol_range(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Vec<PoolData>;
+}
+
+/// Open the backend selected by `[storage] backend`
+pub fn open(config: &StorageConfig) -> Result<Arc<dyn StorageBackend>, Box<dyn std::error::Error>> {
+    match config.backend {
+        StorageBackendKind::Json => {
+            let mut storage = LocalStorage::new(&config.data_dir);
+            storage.set_fields(&config.fields);
+            storage.set_compress(config.compress);
+            storage.set_csv_snapshots(config.csv_snapshots);
+            storage.set_retention(config.max_snapshot_age_days, config.max_snapshot_files);
+            Ok(Arc::new(storage))
+        }
+        #[cfg(feature = "sqlite")]
+        StorageBackendKind::Sqlite => Ok(Arc::new(super::sqlite_storage::SqliteStorage::open(&config.sqlite_path)?)),
+        #[cfg(not(feature = "sqlite"))]
+        StorageBackendKind::Sqlite => Err(format!(
+            "storage backend \"sqlite\" ({}) requires building with --features sqlite", config.sqlite_path
+        ).into()),
+    }
+}
 
 pub struct LocalStorage {
-    data_dir: PathBuf,
     pools_dir: PathBuf,
     snapshots_dir: PathBuf,
+    alerts_dir: PathBuf,
+    gaps_dir: PathBuf,
+    /// Field allowlist for written pools (empty = full objects)
+    fields: Vec<String>,
+    /// Write snapshots as .json.gz
+    compress: bool,
+    /// Also write a CSV copy of each cycle's snapshot
+    csv_snapshots: bool,
+    /// Snapshot retention (0 = unlimited)
+    max_snapshot_age_days: u64,
+    max_snapshot_files: usize,
+}
+
+/// Whether `path` is a pool file readable by [`read_pools`] (.json or .json.gz)
+pub fn is_pool_file(path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    name.ends_with(".json") || name.ends_with(".json.gz")
+}
+
+/// Symbols usable in a per-symbol file name: 1-20 uppercase ASCII letters or digits
+/// (`^[A-Z0-9]{1,20}$`), so no separators or `..` can reach the path
+pub fn is_valid_symbol(symbol: &str) -> bool {
+    (1..=20).contains(&symbol.len()) && symbol.bytes().all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+}
+
+/// Read a pool array from .json or .json.gz (by extension)
+pub fn read_pools(path: &Path) -> Option<Vec<PoolData>> {
+    let reader: Box<dyn Read> = match File::open(path) {
+        Ok(file) if path.extension().is_some_and(|e| e == "gz") => Box::new(GzDecoder::new(file)),
+        Ok(file) => Box::new(file),
+        Err(_) => return None,
+    };
+    serde_json::from_reader(BufReader::new(reader)).ok()
 }
 
 impl LocalStorage {
@@ -15,63 +83,299 @@ impl LocalStorage {
         let data_dir = PathBuf::from(base_dir);
         let pools_dir = data_dir.join("pools");
         let snapshots_dir = data_dir.join("snapshots");
+        let alerts_dir = data_dir.join("alerts");
+        let gaps_dir = data_dir.join("gaps");
 
         // Create directories if not exist
         fs::create_dir_all(&pools_dir).ok();
         fs::create_dir_all(&snapshots_dir).ok();
 
         Self {
-            data_dir,
             pools_dir,
             snapshots_dir,
+            alerts_dir,
+            gaps_dir,
+            fields: Vec::new(),
+            compress: false,
+            csv_snapshots: false,
+            max_snapshot_age_days: 0,
+            max_snapshot_files: 0,
+        }
+    }
+
+    /// Where gap history goes (default `<data_dir>/gaps`)
+    pub fn set_gaps_dir(&mut self, dir: impl Into<PathBuf>) {
+        self.gaps_dir = dir.into();
+    }
+
+    pub fn set_csv_snapshots(&mut self, enabled: bool) {
+        self.csv_snapshots = enabled;
+    }
+
+    /// Snapshot retention used by [`Self::prune_snapshots`] (0 = no limit)
+    pub fn set_retention(&mut self, max_age_days: u64, max_files: usize) {
+        self.max_snapshot_age_days = max_age_days;
+        self.max_snapshot_files = max_files;
+    }
+
+    /// Gzip snapshots (full_*.json.gz)
+    pub fn set_compress(&mut self, compress: bool) {
+        self.compress = compress;
+    }
+
+    /// Only write these PoolData fields (empty = all)
+    pub fn set_fields(&mut self, fields: &[String]) {
+        let known = serde_json::to_value(PoolData::default()).unwrap_or(Value::Null);
+        for field in fields {
+            if known.get(field).is_none() {
+                tracing::warn!("⚠️ Unknown storage field ignored: {}", field);
+            }
+        }
+        self.fields = fields.to_vec();
+    }
+
+    /// Write pools, projected to the field allowlist when set
+    fn write_pools<W: Write>(&self, writer: W, pools: &[PoolData]) -> serde_json::Result<()> {
+        if self.fields.is_empty() {
+            return serde_json::to_writer_pretty(writer, pools);
         }
+
+        let projected: Vec<Value> = pools.iter()
+            .filter_map(|pool| serde_json::to_value(pool).ok())
+            .map(|value| match value {
+                Value::Object(mut map) => {
+                    map.retain(|key, _| self.fields.iter().any(|f| f == key));
+                    Value::Object(map)
+                }
+                other => other,
+            })
+            .collect();
+        serde_json::to_writer_pretty(writer, &projected)
+    }
+
+    /// Dated per-symbol file name: "ETH_2024-01-31.json"
+    /// Callers check `is_valid_symbol` first; the symbol becomes part of a path
+    fn symbol_filename(symbol: &str, date: NaiveDate) -> String {
+        format!("{}_{}-{:02}-{:02}.json", symbol, date.year(), date.month(), date.day())
     }
 
     /// Save pools for a specific symbol
     pub fn save_symbol_pools(&self, symbol: &str, pools: &[PoolData]) {
-        let now = Utc::now();
-        let filename = format!("{}_{}-{:02}-{:02}.json", 
-            symbol, now.year(), now.month(), now.day());
+        if !is_valid_symbol(symbol) {
+            tracing::debug!("Skipping per-symbol file for unsafe symbol {:?}", symbol);
+            return;
+        }
+        let filename = Self::symbol_filename(symbol, Utc::now().date_naive());
         let path = self.pools_dir.join(&filename);
 
         if let Ok(file) = File::create(&path) {
             let writer = BufWriter::new(file);
-            if serde_json::to_writer_pretty(writer, pools).is_ok() {
+            if self.write_pools(writer, pools).is_ok() {
                 tracing::debug!("💾 Saved {} pools for {} -> {}", pools.len(), symbol, filename);
             }
         }
     }
 
     /// Load pools for a specific symbol (today's file)
+    #[cfg(test)]
     pub fn load_symbol_pools(&self, symbol: &str) -> Vec<PoolData> {
-        let now = Utc::now();
-        let filename = format!("{}_{}-{:02}-{:02}.json", 
-            symbol, now.year(), now.month(), now.day());
-        let path = self.pools_dir.join(&filename);
+        self.load_symbol_day(symbol, Utc::now().date_naive())
+    }
 
-        if let Ok(file) = File::open(&path) {
-            let reader = BufReader::new(file);
-            if let Ok(pools) = serde_json::from_reader(reader) {
-                return pools;
-            }
+    /// One day's file, plain or gzipped
+    fn load_symbol_day(&self, symbol: &str, date: NaiveDate) -> Vec<PoolData> {
+        if !is_valid_symbol(symbol) {
+            return Vec::new();
         }
-        vec![]
+        let path = self.pools_dir.join(Self::symbol_filename(symbol, date));
+        read_pools(&path)
+            .or_else(|| read_pools(&path.with_extension("json.gz")))
+            .unwrap_or_default()
+    }
+
+    /// Pools from every dated file of `symbol` in `from..=to`, sorted by timestamp.
+    /// Missing or unreadable days are skipped.
+    pub fn load_symbol_range(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Vec<PoolData> {
+        let mut pools: Vec<PoolData> = from.iter_days()
+            .take_while(|date| *date <= to)
+            .flat_map(|date| self.load_symbol_day(symbol, date))
+            .collect();
+        pools.sort_by_key(|pool| pool.timestamp);
+        pools
     }
 
     /// Save full snapshot of all pools
     pub fn save_snapshot(&self, all_pools: &[PoolData]) {
         let now = Utc::now();
-        let filename = format!("full_{}.json", now.format("%Y-%m-%dT%H-%M"));
+        let extension = if self.compress { "json.gz" } else { "json" };
+        let filename = format!("full_{}.{}", now.format("%Y-%m-%dT%H-%M"), extension);
         let path = self.snapshots_dir.join(&filename);
 
-        if let Ok(file) = File::create(&path) {
-            let writer = BufWriter::new(file);
-            if serde_json::to_writer_pretty(writer, all_pools).is_ok() {
-                tracing::info!("📦 Snapshot saved: {} ({} pools)", filename, all_pools.len());
+        let Ok(file) = File::create(&path) else { return };
+        let saved = if self.compress {
+            let mut encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+            self.write_pools(&mut encoder, all_pools).is_ok()
+                && encoder.finish().and_then(|mut w| w.flush()).is_ok()
+        } else {
+            self.write_pools(BufWriter::new(file), all_pools).is_ok()
+        };
+        if saved {
+            tracing::info!("📦 Snapshot saved: {} ({} pools)", filename, all_pools.len());
+        }
+    }
+
+    /// Flat CSV snapshot (fixed column set, ignores the field allowlist)
+    pub fn save_snapshot_csv(&self, all_pools: &[PoolData]) {
+        let now = Utc::now();
+        let filename = format!("full_{}.csv", now.format("%Y-%m-%dT%H-%M"));
+        let path = self.snapshots_dir.join(&filename);
+
+        match File::create(&path).map_err(csv::Error::from).and_then(|file| write_csv(file, all_pools)) {
+            Ok(()) => tracing::info!("📦 CSV snapshot saved: {} ({} pools)", filename, all_pools.len()),
+            Err(e) => tracing::warn!("⚠️ Failed to save CSV snapshot: {}", e),
+        }
+    }
+
+    /// Convert a JSON snapshot (.json or .json.gz) to CSV next to it ("full_x.json" -> "full_x.csv")
+    pub fn export_csv(json_path: &Path) -> Result<(PathBuf, usize), Box<dyn std::error::Error>> {
+        let pools = read_pools(json_path)
+            .ok_or_else(|| format!("{}: not a readable pool snapshot", json_path.display()))?;
+        let name = json_path.file_name().and_then(|n| n.to_str()).unwrap_or("snapshot");
+        let stem = name.trim_end_matches(".gz").trim_end_matches(".json");
+        let csv_path = json_path.with_file_name(format!("{stem}.csv"));
+        write_csv(File::create(&csv_path)?, &pools)?;
+        Ok((csv_path, pools.len()))
+    }
+
+    /// Delete snapshots beyond the age/count limits, oldest first. Files are
+    /// grouped by their `full_%Y-%m-%dT%H-%M` timestamp (json/gz/csv of one
+    /// snapshot count once) and the newest snapshot is always kept.
+    pub fn prune_snapshots(&self) -> usize {
+        if self.max_snapshot_age_days == 0 && self.max_snapshot_files == 0 {
+            return 0;
+        }
+        let Ok(entries) = fs::read_dir(&self.snapshots_dir) else { return 0 };
+
+        let mut snapshots: Vec<(NaiveDateTime, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+                let timestamp = snapshot_timestamp(&path)?;
+                Some((timestamp, path))
+            })
+            .collect();
+        // Newest first
+        snapshots.sort_by_key(|(ts, _)| std::cmp::Reverse(*ts));
+
+        let mut timestamps: Vec<NaiveDateTime> = snapshots.iter().map(|(ts, _)| *ts).collect();
+        timestamps.dedup();
+        let Some(newest) = timestamps.first().copied() else { return 0 };
+
+        let cutoff = (self.max_snapshot_age_days > 0)
+            .then(|| Utc::now().naive_utc() - chrono::Duration::days(self.max_snapshot_age_days as i64));
+        let keep_files = if self.max_snapshot_files > 0 { self.max_snapshot_files } else { usize::MAX };
+        let expired = |ts: &NaiveDateTime| {
+            let rank = timestamps.iter().position(|t| t == ts).unwrap_or(0);
+            *ts != newest && (rank >= keep_files || cutoff.is_some_and(|c| *ts < c))
+        };
+
+        let mut pruned = 0;
+        for (timestamp, path) in &snapshots {
+            if expired(timestamp) && fs::remove_file(path).is_ok() {
+                pruned += 1;
+            }
+        }
+        if pruned > 0 {
+            tracing::info!("🧹 Pruned {} old snapshot files", pruned);
+        }
+        pruned
+    }
+
+    /// Save one cycle's alerts (per-cycle JSON file or appended daily NDJSON)
+    pub fn save_alerts(&self, alerts: &[ArbitrageAlert], format: AlertFileFormat) {
+        if alerts.is_empty() {
+            return;
+        }
+        fs::create_dir_all(&self.alerts_dir).ok();
+        let now = Utc::now();
+
+        let result = match format {
+            AlertFileFormat::Json => {
+                // Millisecond stamp: back-to-back cycles must not overwrite each other
+                let filename = format!("alerts_{}.json", now.format("%Y-%m-%dT%H-%M-%S%.3f"));
+                File::create(self.alerts_dir.join(filename))
+                    .map_err(serde_json::Error::io)
+                    .and_then(|file| serde_json::to_writer_pretty(BufWriter::new(file), alerts))
+            }
+            AlertFileFormat::Ndjson => {
+                let filename = format!("alerts_{}.ndjson", now.format("%Y-%m-%d"));
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(self.alerts_dir.join(filename))
+                    .map_err(serde_json::Error::io)
+                    .and_then(|file| {
+                        let mut writer = BufWriter::new(file);
+                        for alert in alerts {
+                            serde_json::to_writer(&mut writer, alert)?;
+                            writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+                        }
+                        writer.flush().map_err(serde_json::Error::io)
+                    })
             }
+        };
+
+        match result {
+            Ok(()) => tracing::debug!("💾 Saved {} alerts", alerts.len()),
+            Err(e) => tracing::warn!("⚠️ Failed to save alerts: {}", e),
         }
     }
 
+    /// Daily gap file name: "gaps_2024-01-31.ndjson"
+    fn gaps_filename(date: NaiveDate) -> String {
+        format!("gaps_{}.ndjson", date.format("%Y-%m-%d"))
+    }
+
+    /// Append one gap-monitor cycle to today's file, one row per gap
+    pub fn save_gaps(&self, gaps: &[Gap]) {
+        if gaps.is_empty() {
+            return;
+        }
+        fs::create_dir_all(&self.gaps_dir).ok();
+        let now = Utc::now();
+        let path = self.gaps_dir.join(Self::gaps_filename(now.date_naive()));
+
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(serde_json::Error::io)
+            .and_then(|file| {
+                let mut writer = BufWriter::new(file);
+                for gap in gaps {
+                    let row = StoredGap { timestamp: now.timestamp(), gap: gap.clone() };
+                    serde_json::to_writer(&mut writer, &row)?;
+                    writer.write_all(b"\n").map_err(serde_json::Error::io)?;
+                }
+                writer.flush().map_err(serde_json::Error::io)
+            });
+
+        match result {
+            Ok(()) => tracing::debug!("💾 Saved {} gaps -> {}", gaps.len(), path.display()),
+            Err(e) => tracing::warn!("⚠️ Failed to save gaps: {}", e),
+        }
+    }
+
+    /// Gap rows of one day, in write order (unreadable lines skipped)
+    #[cfg(test)]
+    pub fn load_gaps(&self, date: NaiveDate) -> Vec<StoredGap> {
+        fs::read_to_string(self.gaps_dir.join(Self::gaps_filename(date)))
+            .map(|text| text.lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect())
+            .unwrap_or_default()
+    }
+
     /// Save pools grouped by symbol
     pub fn save_all_by_symbol(&self, pools: &[PoolData]) {
         use std::collections::HashMap;
@@ -87,46 +391,309 @@ impl LocalStorage {
         }
     }
 
-    /// Get storage stats
-    pub fn get_stats(&self) -> StorageStats {
-        let pool_files = fs::read_dir(&self.pools_dir)
-            .map(|entries| entries.count())
-            .unwrap_or(0);
-        
-        let snapshot_files = fs::read_dir(&self.snapshots_dir)
-            .map(|entries| entries.count())
-            .unwrap_or(0);
-
-        let total_size = Self::dir_size(&self.data_dir);
-
-        StorageStats {
-            pool_files,
-            snapshot_files,
-            total_size_mb: total_size as f64 / 1024.0 / 1024.0,
-        }
-    }
-
-    fn dir_size(path: &PathBuf) -> u64 {
-        let mut size = 0;
-        if let Ok(entries) = fs::read_dir(path) {
-            for entry in entries.flatten() {
-                let metadata = entry.metadata().ok();
-                if let Some(meta) = metadata {
-                    if meta.is_file() {
-                        size += meta.len();
-                    } else if meta.is_dir() {
-                        size += Self::dir_size(&entry.path());
-                    }
-                }
-            }
+}
+
+/// "full_2024-01-31T12-00.json[.gz]" / ".csv" -> snapshot time
+fn snapshot_timestamp(path: &Path) -> Option<NaiveDateTime> {
+    let name = path.file_name()?.to_str()?;
+    let rest = name.strip_prefix("full_")?;
+    let stamp = rest.split('.').next()?;
+    NaiveDateTime::parse_from_str(stamp, "%Y-%m-%dT%H-%M").ok()
+}
+
+/// One CSV row; column order is the header order
+#[derive(serde::Serialize)]
+struct CsvRow<'a> {
+    symbol: &'a str,
+    chain: &'a str,
+    dex: &'a str,
+    pool_address: &'a str,
+    pair: &'a str,
+    price_usd: f64,
+    lp_reserve_usd: f64,
+    volume_24h: f64,
+    fee_tier: Option<f64>,
+    fee_estimated: bool,
+    base_token: Option<&'a str>,
+    quote_token: Option<&'a str>,
+    source: &'a str,
+    timestamp: i64,
+}
+
+/// CSV with header row; the csv crate handles quoting (pairs may contain commas)
+fn write_csv<W: Write>(writer: W, pools: &[PoolData]) -> csv::Result<()> {
+    let mut writer = csv::Writer::from_writer(BufWriter::new(writer));
+    for pool in pools {
+        writer.serialize(CsvRow {
+            symbol: &pool.symbol,
+            chain: &pool.chain,
+            dex: &pool.dex,
+            pool_address: &pool.pool_address,
+            pair: &pool.pair,
+            price_usd: pool.price_usd,
+            lp_reserve_usd: pool.lp_reserve_usd,
+            volume_24h: pool.volume_24h,
+            fee_tier: pool.fee_tier,
+            fee_estimated: pool.fee_estimated,
+            base_token: pool.base_token.as_deref(),
+            quote_token: pool.quote_token.as_deref(),
+            source: &pool.source,
+            timestamp: pool.timestamp,
+        })?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// One persisted gap with the cycle time (unix seconds)
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StoredGap {
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub gap: Gap,
+}
+
+impl StorageBackend for LocalStorage {
+    /// Per-symbol daily files + full snapshot (+ CSV), then retention
+    fn save_cycle(&self, pools: &[PoolData]) {
+        self.save_all_by_symbol(pools);
+        LocalStorage::save_snapshot(self, pools);
+        if self.csv_snapshots {
+            self.save_snapshot_csv(pools);
         }
-        size
+        self.prune_snapshots();
+    }
+
+    fn save_snapshot(&self, pools: &[PoolData]) {
+        LocalStorage::save_snapshot(self, pools);
+    }
+
+    fn save_alerts(&self, alerts: &[ArbitrageAlert], format: AlertFileFormat) {
+        LocalStorage::save_alerts(self, alerts, format);
+    }
+
+    fn load_symbol_range(&self, symbol: &str, from: NaiveDate, to: NaiveDate) -> Vec<PoolData> {
+        LocalStorage::load_symbol_range(self, symbol, from, to)
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct StorageStats {
-    pub pool_files: usize,
-    pub snapshot_files: usize,
-    pub total_size_mb: f64,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use crate::test_util::{pool, temp_dir};
+
+    fn storage(label: &str) -> LocalStorage {
+        LocalStorage::new(temp_dir(label).to_str().unwrap())
+    }
+
+    fn only_file(dir: &Path) -> PathBuf {
+        let files: Vec<PathBuf> = fs::read_dir(dir).unwrap().flatten().map(|e| e.path()).collect();
+        assert_eq!(files.len(), 1, "{files:?}");
+        files.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn allowlist_projects_written_pools_and_still_loads() {
+        let mut storage = storage("storage-fields");
+        storage.set_fields(&["symbol".to_string(), "price_usd".to_string(), "timestamp".to_string()]);
+        storage.save_snapshot(&[pool("ETH", "test", 3000.0, 1_700_000_000)]);
+
+        let path = only_file(&storage.snapshots_dir);
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        let mut keys: Vec<&str> = written[0].as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort();
+        assert_eq!(keys, ["price_usd", "symbol", "timestamp"]);
+
+        let loaded = read_pools(&path).unwrap();
+        assert_eq!(loaded[0], PoolData {
+            symbol: "ETH".to_string(),
+            price_usd: 3000.0,
+            timestamp: 1_700_000_000,
+            ..PoolData::default()
+        });
+    }
+
+    #[test]
+    fn empty_allowlist_writes_full_objects() {
+        let storage = storage("storage-full");
+        let pools = [pool("ETH", "test", 3000.0, 1_700_000_000)];
+        storage.save_snapshot(&pools);
+        assert_eq!(read_pools(&only_file(&storage.snapshots_dir)).unwrap(), pools);
+    }
+
+    fn alerts(symbol: &str) -> Vec<ArbitrageAlert> {
+        let low = pool(symbol, "test", 1000.0, 1_700_000_000);
+        let high = PoolData { pool_address: "0xdear".to_string(), price_usd: 1020.0, ..low.clone() };
+        vec![ArbitrageAlert::from_pools(&low, &high)]
+    }
+
+    fn alert_files(storage: &LocalStorage) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(&storage.alerts_dir).unwrap().flatten().map(|e| e.path()).collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn json_alerts_get_one_file_per_cycle() {
+        let storage = storage("storage-alerts-json");
+        storage.save_alerts(&alerts("ETH"), AlertFileFormat::Json);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        storage.save_alerts(&alerts("BTC"), AlertFileFormat::Json);
+        // Cycles without alerts write nothing
+        storage.save_alerts(&[], AlertFileFormat::Json);
+
+        let files = alert_files(&storage);
+        assert_eq!(files.len(), 2, "{files:?}");
+        let symbols: Vec<String> = files.iter()
+            .map(|path| {
+                assert!(path.to_str().unwrap().ends_with(".json"));
+                let cycle: Vec<ArbitrageAlert> = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+                cycle[0].symbol.clone()
+            })
+            .collect();
+        assert_eq!(symbols, ["ETH", "BTC"]);
+    }
+
+    #[test]
+    fn ndjson_alerts_append_one_line_per_alert() {
+        let storage = storage("storage-alerts-ndjson");
+        let first = alerts("ETH");
+        let second = alerts("BTC");
+        storage.save_alerts(&first, AlertFileFormat::Ndjson);
+        storage.save_alerts(&second, AlertFileFormat::Ndjson);
+
+        let files = alert_files(&storage);
+        assert_eq!(files.len(), 1, "{files:?}");
+        assert!(files[0].to_str().unwrap().ends_with(".ndjson"));
+        let lines: Vec<ArbitrageAlert> = fs::read_to_string(&files[0]).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, [first, second].concat());
+    }
+
+    #[test]
+    fn symbol_range_spans_dated_files() {
+        let storage = storage("storage-range");
+        let day = |d: u32| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+        let write_day = |date: NaiveDate, pools: &[PoolData]| {
+            let path = storage.pools_dir.join(LocalStorage::symbol_filename("ETH", date));
+            storage.write_pools(BufWriter::new(File::create(path).unwrap()), pools).unwrap();
+        };
+        write_day(day(1), &[pool("ETH", "test", 3000.0, 1_709_300_000)]);
+        write_day(day(2), &[pool("ETH", "test", 3100.0, 1_709_390_000), pool("ETH", "test", 3050.0, 1_709_380_000)]);
+        write_day(day(3), &[pool("ETH", "test", 3200.0, 1_709_470_000)]);
+
+        let prices: Vec<f64> = storage.load_symbol_range("ETH", day(1), day(2)).iter().map(|p| p.price_usd).collect();
+        assert_eq!(prices, [3000.0, 3050.0, 3100.0], "both days, sorted by timestamp, day 3 excluded");
+        assert_eq!(storage.load_symbol_range("ETH", day(2), day(2)).len(), 2);
+        // Missing days are skipped
+        assert_eq!(storage.load_symbol_range("ETH", day(3), day(9)).len(), 1);
+    }
+
+    #[test]
+    fn unsafe_symbols_never_reach_the_filesystem() {
+        for symbol in ["", "../ETH", "ETH/../../x", "eth", "E.TH", "ABCDEFGHIJKLMNOPQRSTU"] {
+            assert!(!is_valid_symbol(symbol), "{symbol:?}");
+        }
+        assert!(is_valid_symbol("ETH") && is_valid_symbol("1INCH"));
+
+        let storage = storage("storage-traversal");
+        // A pool file just outside pools/ that a traversal would hit
+        let data_dir = storage.pools_dir.parent().unwrap();
+        let outside = data_dir.join(LocalStorage::symbol_filename("SECRET", Utc::now().date_naive()));
+        fs::write(&outside, serde_json::to_string(&[pool("SECRET", "test", 1.0, 1)]).unwrap()).unwrap();
+        assert!(storage.load_symbol_pools("../SECRET").is_empty());
+
+        storage.save_symbol_pools("../ESCAPE", &[pool("ETH", "test", 1.0, 1)]);
+        assert_eq!(fs::read_dir(data_dir).unwrap().count(), 3, "pools/, snapshots/ and the planted file only");
+    }
+
+    #[test]
+    fn csv_export_round_trips_quoted_pairs() {
+        let storage = storage("storage-csv");
+        let tricky = PoolData { pair: "WETH, USDC (0.05%)".to_string(), ..pool("ETH", "test", 3000.5, 1_700_000_000) };
+        let pools = [tricky, pool("BTC", "test", 60_000.0, 1_700_000_060).with_fee_tier(Some(0.003))];
+        let json_path = storage.snapshots_dir.join("full_2024-01-31T12-00.json");
+        storage.write_pools(BufWriter::new(File::create(&json_path).unwrap()), &pools).unwrap();
+
+        let (csv_path, count) = LocalStorage::export_csv(&json_path).unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(csv_path.file_name().unwrap(), "full_2024-01-31T12-00.csv");
+
+        let mut reader = csv::Reader::from_path(&csv_path).unwrap();
+        let header: Vec<String> = reader.headers().unwrap().iter().map(str::to_string).collect();
+        assert_eq!(&header[..6], ["symbol", "chain", "dex", "pool_address", "pair", "price_usd"]);
+        let rows: Vec<HashMap<String, String>> = reader.deserialize().map(Result::unwrap).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["pair"], "WETH, USDC (0.05%)", "comma survives quoting");
+        assert_eq!(rows[0]["price_usd"].parse::<f64>().unwrap(), 3000.5);
+        assert_eq!(rows[0]["fee_tier"], "");
+        assert_eq!(rows[1]["symbol"], "BTC");
+        assert_eq!(rows[1]["fee_tier"].parse::<f64>().unwrap(), 0.003);
+        assert_eq!(rows[1]["timestamp"], "1700000060");
+        // Raw text: the pair is quoted, not split into an extra column
+        assert!(fs::read_to_string(&csv_path).unwrap().contains("\"WETH, USDC (0.05%)\""));
+    }
+
+    #[test]
+    fn compressed_snapshot_reads_back_identically() {
+        let mut storage = storage("storage-gzip");
+        storage.set_compress(true);
+        let pools = [pool("ETH", "test", 3000.25, 1_700_000_000), pool("BTC", "test", 60_000.0, 1_700_000_060)];
+        storage.save_snapshot(&pools);
+
+        let path = only_file(&storage.snapshots_dir);
+        assert!(path.to_str().unwrap().ends_with(".json.gz"), "{path:?}");
+        assert_eq!(&fs::read(&path).unwrap()[..2], [0x1f, 0x8b], "gzip magic");
+        assert!(is_pool_file(&path));
+        assert_eq!(read_pools(&path).unwrap(), pools);
+
+        // Per-symbol loaders fall back to the .gz variant of a day's file
+        let day_file = storage.pools_dir.join(LocalStorage::symbol_filename("ETH", Utc::now().date_naive()));
+        let gz = File::create(day_file.with_extension("json.gz")).unwrap();
+        let mut encoder = GzEncoder::new(gz, Compression::default());
+        storage.write_pools(&mut encoder, &pools[..1]).unwrap();
+        encoder.finish().unwrap();
+        assert_eq!(storage.load_symbol_pools("ETH"), pools[..1]);
+    }
+
+    fn snapshot_names(storage: &LocalStorage) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(&storage.snapshots_dir).unwrap().flatten()
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn pruning_keeps_the_newest_snapshots() {
+        let mut storage = storage("storage-prune");
+        for name in [
+            "full_2024-01-01T00-00.json",
+            "full_2024-01-01T00-01.json",
+            "full_2024-01-01T00-02.json", "full_2024-01-01T00-02.csv",
+            "full_2024-01-01T00-03.json.gz",
+            "notes.txt",
+        ] {
+            fs::write(storage.snapshots_dir.join(name), "[]").unwrap();
+        }
+
+        // No limits configured: nothing goes
+        assert_eq!(storage.prune_snapshots(), 0);
+
+        storage.set_retention(0, 2);
+        assert_eq!(storage.prune_snapshots(), 2);
+        // The json + csv of one snapshot count once; unrelated files are left alone
+        assert_eq!(snapshot_names(&storage), [
+            "full_2024-01-01T00-02.csv", "full_2024-01-01T00-02.json",
+            "full_2024-01-01T00-03.json.gz", "notes.txt",
+        ]);
+
+        // Everything is older than a day, but the newest snapshot always survives
+        storage.set_retention(1, 0);
+        assert_eq!(storage.prune_snapshots(), 2);
+        assert_eq!(snapshot_names(&storage), ["full_2024-01-01T00-03.json.gz", "notes.txt"]);
+    }
 }
@@ -1,9 +1,15 @@
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::time::Duration;
-use crate::models::PoolData;
-use super::{PoolSource, SourceError};
+use crate::models::{PoolData, canonical_chain, chain_for_id};
+use crate::models::pool::parse_fee_label;
+use super::meta_agg::{self, TokenCache};
+use super::{http, PoolSource, SourceError};
+
+/// Timeout of each DexScreener/Matcha request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 // L1 tokens that need W-prefix search for wrapped versions
 const L1_TOKENS: &[&str] = &[
@@ -36,20 +42,56 @@ pub struct DexScreenerSource {
     client: Client,
 }
 
+impl Default for DexScreenerSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl DexScreenerSource {
     pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .unwrap(),
+        Self::with_client(http::shared_client())
+    }
+
+    /// Source on an injected (shared) client
+    pub fn with_client(client: Client) -> Self {
+        Self { client }
+    }
+
+    /// Pools of one search response whose base token is `variant` (or the W-prefixed symbol)
+    fn parse_search(symbol: &str, variant: &str, data: DexScreenerResponse) -> Vec<PoolData> {
+        let mut pools = Vec::new();
+        for pair in data.into_pairs("DexScreener").into_iter().take(10) {
+            let token_symbol = pair.base_token
+                .as_ref()
+                .and_then(|t| t.symbol.clone())
+                .unwrap_or_default();
+
+            // 심볼 정확 매칭 필터
+            let upper_variant = variant.to_uppercase();
+            let upper_token = token_symbol.to_uppercase();
+            if upper_token != upper_variant && upper_token != format!("W{}", symbol.to_uppercase()) {
+                continue; // 심볼 불일치 → 스킵
+            }
+
+            if let Some(pool) = pair.into_pool(token_symbol, format!("{}/USD", variant)) {
+                pools.push(pool);
+            }
         }
+        pools
     }
 }
 
+/// Pairs stay raw until `into_pairs`, so one bad pair is skipped alone
 #[derive(Debug, Deserialize)]
 struct DexScreenerResponse {
-    pairs: Option<Vec<DexScreenerPair>>,
+    pairs: Option<Vec<serde_json::Value>>,
+}
+
+impl DexScreenerResponse {
+    fn into_pairs(self, source: &str) -> Vec<DexScreenerPair> {
+        http::lenient_entries(source, self.pairs.unwrap_or_default())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -62,16 +104,20 @@ struct DexScreenerPair {
     pair_address: Option<String>,
     #[serde(rename = "baseToken")]
     base_token: Option<DexScreenerToken>,
+    #[serde(rename = "quoteToken")]
+    quote_token: Option<DexScreenerToken>,
     #[serde(rename = "priceUsd")]
     price_usd: Option<String>,
     liquidity: Option<DexScreenerLiquidity>,
     volume: Option<DexScreenerVolume>,
+    /// Pool kind, e.g. ["v2"], ["v3"], ["CLMM"]
+    labels: Option<Vec<String>>,
 }
 
 #[derive(Debug, Deserialize)]
 struct DexScreenerToken {
+    address: Option<String>,
     symbol: Option<String>,
-    name: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +130,55 @@ struct DexScreenerVolume {
     h24: Option<f64>,
 }
 
+impl DexScreenerPair {
+    /// PoolData for this pair; None without an address or a positive price
+    fn into_pool(self, symbol: String, pair_label: String) -> Option<PoolData> {
+        let pool_addr = self.pair_address.unwrap_or_default();
+        let price = self.price_usd
+            .as_ref()
+            .and_then(|p| p.parse::<f64>().ok())
+            .unwrap_or(0.0);
+        if pool_addr.is_empty() || price <= 0.0 {
+            return None;
+        }
+
+        let chain = self.chain_id.unwrap_or_else(|| "unknown".to_string());
+        let dex = self.dex_id.unwrap_or_else(|| "unknown".to_string());
+        // "uniswap" + ["v3"] -> "uniswap_v3" (기본 수수료 판별용)
+        let dex_kind = match self.labels.as_deref().and_then(|l| l.first()) {
+            Some(label) => format!("{}_{}", dex, label.to_lowercase()),
+            None => dex.clone(),
+        };
+        let fee = self.labels.iter().flatten().find_map(|l| parse_fee_label(l));
+        let lp = self.liquidity
+            .as_ref()
+            .and_then(|l| l.usd)
+            .unwrap_or(0.0);
+        let volume = self.volume
+            .as_ref()
+            .and_then(|v| v.h24)
+            .unwrap_or(0.0);
+
+        Some(PoolData::new(
+            symbol,
+            chain,
+            dex,
+            pool_addr,
+            pair_label,
+            price,
+            lp,
+            volume,
+            "dexscreener".to_string(),
+        )
+        .with_fee_tier(fee)
+        .with_conventional_fee(&dex_kind)
+        .with_tokens(
+            self.base_token.and_then(|t| t.address),
+            self.quote_token.and_then(|t| t.address),
+        ))
+    }
+}
+
 #[async_trait]
 impl PoolSource for DexScreenerSource {
     fn name(&self) -> &'static str { "DexScreener" }
@@ -98,57 +193,10 @@ impl PoolSource for DexScreenerSource {
                 variant
             );
 
-            match self.client.get(&url).send().await {
-                Ok(resp) if resp.status().is_success() => {
-                    if let Ok(data) = resp.json::<DexScreenerResponse>().await {
-                        if let Some(pairs) = data.pairs {
-                            for pair in pairs.into_iter().take(10) {
-                                let pool_addr = pair.pair_address.unwrap_or_default();
-                                let chain = pair.chain_id.unwrap_or_else(|| "unknown".to_string());
-                                let dex = pair.dex_id.unwrap_or_else(|| "unknown".to_string());
-                                let token_symbol = pair.base_token
-                                    .as_ref()
-                                    .and_then(|t| t.symbol.clone())
-                                    .unwrap_or_default();
-                                
-                                // 심볼 정확 매칭 필터
-                                let upper_variant = variant.to_uppercase();
-                                let upper_token = token_symbol.to_uppercase();
-                                if upper_token != upper_variant && upper_token != format!("W{}", symbol.to_uppercase()) {
-                                    continue; // 심볼 불일치 → 스킵
-                                }
-                                
-                                let price = pair.price_usd
-                                    .as_ref()
-                                    .and_then(|p| p.parse::<f64>().ok())
-                                    .unwrap_or(0.0);
-                                let lp = pair.liquidity
-                                    .as_ref()
-                                    .and_then(|l| l.usd)
-                                    .unwrap_or(0.0);
-                                let volume = pair.volume
-                                    .as_ref()
-                                    .and_then(|v| v.h24)
-                                    .unwrap_or(0.0);
-
-                                if !pool_addr.is_empty() && price > 0.0 {
-                                    all_pools.push(PoolData::new(
-                                        token_symbol,
-                                        chain,
-                                        dex,
-                                        pool_addr,
-                                        format!("{}/USD", variant),
-                                        price,
-                                        lp,
-                                        volume,
-                                        "dexscreener".to_string(),
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
-                _ => {}
+            let req = self.client.get(&url)
+                .timeout(REQUEST_TIMEOUT);
+            if let Ok(data) = http::fetch_json::<DexScreenerResponse>(self.name(), req).await {
+                all_pools.extend(Self::parse_search(symbol, &variant, data));
             }
         }
 
@@ -156,19 +204,113 @@ impl PoolSource for DexScreenerSource {
     }
 }
 
+/// Addresses per `/tokens` request (DexScreener limit)
+const DEXSCREENER_TOKENS_BATCH: usize = 30;
+
+/// DexScreener by token address: exact pools of the cached contract on each
+/// chain, so generic tickers don't pick up unrelated tokens
+pub struct DexScreenerTokenSource {
+    client: Client,
+    cache: TokenCache,
+}
+
+impl DexScreenerTokenSource {
+    pub fn new(cache: TokenCache) -> Self {
+        Self::with_client(http::shared_client(), cache)
+    }
+
+    /// Source on an injected (shared) client
+    pub fn with_client(client: Client, cache: TokenCache) -> Self {
+        Self { client, cache }
+    }
+
+    /// Canonical chain -> lowercase token address, for chains with a known address
+    fn token_addresses(&self, symbol: &str) -> HashMap<&'static str, String> {
+        let cache = self.cache.read();
+        cache.get(&symbol.to_uppercase())
+            .into_iter()
+            .flatten()
+            .filter_map(|(chain_id, token)| Some((chain_for_id(*chain_id as u64)?, token.address.to_lowercase())))
+            .collect()
+    }
+
+    /// Pairs whose base token is the cached address on the pair's own chain
+    fn parse_pairs(symbol: &str, addresses: &HashMap<&'static str, String>, pairs: Vec<DexScreenerPair>) -> Vec<PoolData> {
+        pairs.into_iter()
+            .filter(|pair| {
+                let chain = canonical_chain(pair.chain_id.as_deref().unwrap_or(""));
+                let base = pair.base_token.as_ref().and_then(|t| t.address.as_deref()).unwrap_or("");
+                addresses.get(chain.as_str()).is_some_and(|address| address.eq_ignore_ascii_case(base))
+            })
+            .filter_map(|pair| {
+                let quote = pair.quote_token.as_ref()
+                    .and_then(|t| t.symbol.clone())
+                    .unwrap_or_else(|| "USD".to_string());
+                pair.into_pool(symbol.to_string(), format!("{}/{}", symbol, quote))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PoolSource for DexScreenerTokenSource {
+    fn name(&self) -> &'static str { "DexScreenerTokens" }
+
+    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        let symbol = symbol.to_uppercase();
+        let addresses = self.token_addresses(&symbol);
+        if addresses.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut unique: Vec<&str> = addresses.values().map(String::as_str).collect();
+        unique.sort_unstable();
+        unique.dedup();
+
+        let mut pools = Vec::new();
+        for batch in unique.chunks(DEXSCREENER_TOKENS_BATCH) {
+            let url = format!("https://api.dexscreener.com/latest/dex/tokens/{}", batch.join(","));
+            let req = self.client.get(&url)
+                .timeout(REQUEST_TIMEOUT);
+            let data: DexScreenerResponse = match http::fetch_json(self.name(), req).await {
+                Ok(data) => data,
+                Err(SourceError::Http(_)) => continue,
+                Err(e) => return Err(e),
+            };
+            pools.extend(Self::parse_pairs(&symbol, &addresses, data.into_pairs(self.name())));
+        }
+        Ok(pools)
+    }
+}
+
 /// Matcha (0x) Token Search - with proper headers
 pub struct MatchaSource {
     client: Client,
+    /// Receives the decimals Matcha reports for already-cached tokens
+    token_cache: Option<TokenCache>,
+}
+
+impl Default for MatchaSource {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl MatchaSource {
     pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .unwrap(),
-        }
+        Self::with_client(http::shared_client())
+    }
+
+    /// Source on an injected (shared) client
+    pub fn with_client(client: Client) -> Self {
+        Self { client, token_cache: None }
+    }
+
+    /// Record reported decimals into `cache` so the direct aggregator quotes
+    /// (OpenOcean/ParaSwap) size and scale by the real decimals
+    /// from the next cycle on
+    pub fn set_token_cache(&mut self, cache: TokenCache) {
+        self.token_cache = Some(cache);
     }
 }
 
@@ -187,68 +329,54 @@ impl PoolSource for MatchaSource {
                 chain_ids, variant
             );
 
-            let resp = self.client.get(&url)
+            let req = self.client.get(&url)
+                .timeout(REQUEST_TIMEOUT)
                 .header("accept", "*/*")
                 .header("accept-language", "ko-KR,ko;q=0.8")
                 .header("referer", "https://matcha.xyz/")
                 .header("sec-fetch-dest", "empty")
                 .header("sec-fetch-mode", "cors")
                 .header("sec-fetch-site", "same-origin")
-                .header("user-agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36")
-                .send()
-                .await;
-
-            match resp {
-                Ok(r) if r.status().is_success() => {
-                    if let Ok(data) = r.json::<serde_json::Value>().await {
-                        if let Some(tokens) = data["data"].as_array() {
-                            for token in tokens.iter().take(10) {
-                                let token_symbol = token["symbol"].as_str().unwrap_or("");
-                                let chain_id = token["chainId"].as_u64().unwrap_or(0);
-                                let address = token["address"].as_str().unwrap_or("");
-                                let name = token["name"].as_str().unwrap_or("");
-                                let decimals = token["decimals"].as_u64().unwrap_or(18);
-
-                                // Match symbol (case insensitive)
-                                if !token_symbol.eq_ignore_ascii_case(&variant) &&
-                                   !token_symbol.to_uppercase().contains(&variant.to_uppercase()) {
-                                    continue;
-                                }
-
-                                let chain_name = match chain_id {
-                                    1 => "ethereum",
-                                    56 => "bsc",
-                                    137 => "polygon",
-                                    42161 => "arbitrum",
-                                    8453 => "base",
-                                    10 => "optimism",
-                                    43114 => "avalanche",
-                                    534352 => "scroll",
-                                    59144 => "linea",
-                                    5000 => "mantle",
-                                    143 => "monad",
-                                    9745 => "sonic",
-                                    _ => "other",
-                                };
-
-                                if !address.is_empty() {
-                                    all_pools.push(PoolData::new(
-                                        token_symbol.to_string(),
-                                        chain_name.to_string(),
-                                        "matcha".to_string(),
-                                        address.to_string(),
-                                        format!("{} ({} dec)", name, decimals),
-                                        0.0,
-                                        0.0,
-                                        0.0,
-                                        "matcha".to_string(),
-                                    ));
-                                }
-                            }
+                .header("user-agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36");
+
+            if let Ok(data) = http::fetch_json::<serde_json::Value>(self.name(), req).await {
+                if let Some(tokens) = data["data"].as_array() {
+                    for token in tokens.iter().take(10) {
+                        let token_symbol = token["symbol"].as_str().unwrap_or("");
+                        let chain_id = token["chainId"].as_u64().unwrap_or(0);
+                        let address = token["address"].as_str().unwrap_or("");
+                        let name = token["name"].as_str().unwrap_or("");
+                        let reported_decimals = token["decimals"].as_u64();
+                        let decimals = reported_decimals.unwrap_or(18);
+
+                        // Match symbol (case insensitive)
+                        if !token_symbol.eq_ignore_ascii_case(&variant) &&
+                           !token_symbol.to_uppercase().contains(&variant.to_uppercase()) {
+                            continue;
+                        }
+
+                        let chain_name = chain_for_id(chain_id).unwrap_or("other");
+
+                        let valid_decimals = reported_decimals.and_then(|d| u8::try_from(d).ok());
+                        if let (Some(cache), Some(decimals)) = (&self.token_cache, valid_decimals) {
+                            meta_agg::record_decimals(cache, token_symbol, chain_id as u32, address, decimals);
+                        }
+
+                        if !address.is_empty() {
+                            all_pools.push(PoolData::new(
+                                token_symbol.to_string(),
+                                chain_name.to_string(),
+                                "matcha".to_string(),
+                                address.to_string(),
+                                format!("{} ({} dec)", name, decimals),
+                                0.0,
+                                0.0,
+                                0.0,
+                                "matcha".to_string(),
+                            ).with_tokens(Some(address.to_string()), None));
                         }
                     }
                 }
-                _ => {}
             }
         }
 
@@ -256,106 +384,113 @@ impl PoolSource for MatchaSource {
     }
 }
 
-/// 1inch Aggregator - simplified
-pub struct OneInchSource {
-    client: Client,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl OneInchSource {
-    pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(8))
-                .build()
-                .unwrap(),
-        }
+    fn dexscreener_fixture() -> DexScreenerResponse {
+        serde_json::from_str(include_str!("../../tests/fixtures/dexscreener.json")).unwrap()
     }
-}
-
-#[async_trait]
-impl PoolSource for OneInchSource {
-    fn name(&self) -> &'static str { "1inch" }
-
-    async fn fetch_pools(&self, _symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        // 1inch requires API key, skip for now
-        Ok(vec![])
-    }
-}
-
-/// ParaSwap - simplified
-pub struct ParaSwapSource {
-    client: Client,
-}
 
-impl ParaSwapSource {
-    pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(8))
-                .build()
-                .unwrap(),
-        }
+    #[test]
+    fn parses_the_dexscreener_search_fixture() {
+        let pools = DexScreenerSource::parse_search("ETH", "WETH", dexscreener_fixture());
+        // ETHFI is a different token
+        assert_eq!(pools.len(), 3, "{pools:?}");
+
+        let mainnet = &pools[0];
+        assert_eq!(mainnet.symbol, "WETH");
+        assert_eq!(mainnet.chain, "ethereum");
+        assert_eq!(mainnet.dex, "uniswap");
+        assert_eq!(mainnet.pool_address, "0x88e6A0c2dDD26FEEb64F039a2c41296FcB3f5640");
+        assert_eq!(mainnet.pair, "WETH/USD");
+        assert_eq!(mainnet.price_usd, 3012.45);
+        assert_eq!(mainnet.lp_reserve_usd, 152300411.5);
+        assert_eq!(mainnet.volume_24h, 48211904.12);
+        // v3 pools have no single conventional fee
+        assert_eq!(mainnet.fee_tier, None);
+        assert_eq!(mainnet.base_token.as_deref(), Some("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"));
+        assert_eq!(mainnet.quote_token.as_deref(), Some("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"));
+        assert_eq!(mainnet.source, "dexscreener");
+
+        assert_eq!(pools[1].chain, "base");
+        assert_eq!(pools[1].dex, "aerodrome");
+
+        // Unlabelled camelot pair without liquidity data
+        let arbitrum = &pools[2];
+        assert_eq!(arbitrum.chain, "arbitrum");
+        assert_eq!(arbitrum.price_usd, 3011.2);
+        assert_eq!(arbitrum.lp_reserve_usd, 0.0);
+        assert_eq!(arbitrum.fee_tier, Some(0.003));
+        assert!(arbitrum.fee_estimated);
+
+        // The base-symbol search keeps the wrapped pairs too
+        assert_eq!(DexScreenerSource::parse_search("ETH", "ETH", dexscreener_fixture()).len(), 3);
     }
-}
 
-#[async_trait]
-impl PoolSource for ParaSwapSource {
-    fn name(&self) -> &'static str { "ParaSwap" }
-
-    async fn fetch_pools(&self, _symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        // ParaSwap needs specific token addresses, skip for now
-        Ok(vec![])
+    fn pair(labels: &str) -> DexScreenerPair {
+        serde_json::from_str(&format!(r#"{{
+            "chainId": "ethereum", "dexId": "uniswap", "pairAddress": "0xpool",
+            "priceUsd": "3000.5", "labels": {labels}
+        }}"#)).unwrap()
     }
-}
 
-/// KyberSwap - simplified
-pub struct KyberSwapSource {
-    client: Client,
-}
+    #[test]
+    fn dexscreener_fee_comes_from_labels_then_the_dex_default() {
+        let pool = pair(r#"["v3", "0.3%"]"#).into_pool("ETH".into(), "WETH/USDC".into()).unwrap();
+        assert_eq!((pool.fee_tier, pool.fee_estimated), (Some(0.003), false));
 
-impl KyberSwapSource {
-    pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(8))
-                .build()
-                .unwrap(),
-        }
-    }
-}
+        let pool = pair(r#"["v2"]"#).into_pool("ETH".into(), "WETH/USDC".into()).unwrap();
+        assert_eq!((pool.fee_tier, pool.fee_estimated), (Some(0.003), true));
 
-#[async_trait]
-impl PoolSource for KyberSwapSource {
-    fn name(&self) -> &'static str { "KyberSwap" }
-
-    async fn fetch_pools(&self, _symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        // KyberSwap needs specific token addresses, skip for now
-        Ok(vec![])
+        let pool = pair(r#"["v3"]"#).into_pool("ETH".into(), "WETH/USDC".into()).unwrap();
+        assert_eq!(pool.fee_tier, None);
     }
-}
-
-/// OpenOcean - simplified
-pub struct OpenOceanSource {
-    client: Client,
-}
 
-impl OpenOceanSource {
-    pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(8))
-                .build()
-                .unwrap(),
-        }
+    #[test]
+    fn token_pairs_keep_only_the_cached_contract_as_base() {
+        let cache: TokenCache = std::sync::Arc::new(parking_lot::RwLock::new(HashMap::from([(
+            "LINK".to_string(),
+            HashMap::from([
+                (1, meta_agg::TokenInfo { address: "0x514910771AF9Ca656af840dff83E8264EcF986CA".to_string(), decimals: 18 }),
+                (56, meta_agg::TokenInfo { address: "0xf8a0bf9cf54bb92f17374d9e9a321e6a111a51bd".to_string(), decimals: 18 }),
+            ]),
+        )])));
+        let source = DexScreenerTokenSource::with_client(http::shared_client(), cache);
+
+        let addresses = source.token_addresses("link");
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses["ethereum"], "0x514910771af9ca656af840dff83e8264ecf986ca");
+        assert_eq!(addresses["bsc"], "0xf8a0bf9cf54bb92f17374d9e9a321e6a111a51bd");
+
+        let data: DexScreenerResponse = serde_json::from_str(include_str!("../../tests/fixtures/dexscreener_tokens.json")).unwrap();
+        let pools = DexScreenerTokenSource::parse_pairs("LINK", &addresses, data.into_pairs("DexScreenerTokens"));
+        // Dropped: the sushiswap pair has LINK as the quote, arbitrum has no cached address
+        assert_eq!(pools.len(), 2, "{pools:?}");
+
+        let mainnet = &pools[0];
+        assert_eq!((mainnet.chain.as_str(), mainnet.dex.as_str()), ("ethereum", "uniswap"));
+        assert_eq!(mainnet.pair, "LINK/WETH");
+        assert_eq!(mainnet.price_usd, 14.52);
+        assert_eq!(mainnet.lp_reserve_usd, 21480331.2);
+        assert_eq!(mainnet.fee_tier, Some(0.003));
+        assert_eq!(mainnet.base_token.as_deref(), Some("0x514910771AF9Ca656af840dff83E8264EcF986CA"));
+
+        let bsc = &pools[1];
+        assert_eq!((bsc.chain.as_str(), bsc.pair.as_str()), ("bsc", "LINK/WBNB"));
+        assert_eq!(bsc.base_token.as_deref(), Some("0xF8A0BF9cF54Bb92F17374d9e9A321E6a111a51bD"));
+        assert_eq!(bsc.quote_token.as_deref(), Some("0xbb4CdB9CBd36B01bD1cBaEBF2De08d9173bc095c"));
     }
-}
-
-#[async_trait]
-impl PoolSource for OpenOceanSource {
-    fn name(&self) -> &'static str { "OpenOcean" }
 
-    async fn fetch_pools(&self, _symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        // OpenOcean needs specific token addresses, skip for now
-        Ok(vec![])
+    #[test]
+    fn a_malformed_pair_is_skipped_alone() {
+        let data: DexScreenerResponse = serde_json::from_str(r#"{ "pairs": [
+            { "chainId": "ethereum", "dexId": "uniswap", "pairAddress": "0xgood", "priceUsd": "3000.5",
+              "baseToken": { "symbol": "WETH" } },
+            { "chainId": "ethereum", "pairAddress": "0xbad", "priceUsd": { "value": 1 } }
+        ] }"#).unwrap();
+        let pools = DexScreenerSource::parse_search("ETH", "WETH", data);
+        assert_eq!(pools.len(), 1);
+        assert_eq!((pools[0].pool_address.as_str(), pools[0].price_usd), ("0xgood", 3000.5));
     }
 }
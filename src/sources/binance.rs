@@ -0,0 +1,185 @@
+use reqwest::Client;
+use serde::Deserialize;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use futures::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use crate::config::BinanceConfig;
+use super::{CexSource, SourceError, http};
+use super::upbit::CexPrice;
+
+/// Quote asset; USDT is treated as USD
+const QUOTE: &str = "USDT";
+
+/// Binance spot prices for USDT-quoted pairs
+pub struct BinanceClient {
+    prices: Arc<DashMap<String, CexPrice>>,
+    client: Client,
+    timeout: Duration,
+    rest_url: String,
+    ws_url: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceTicker {
+    symbol: String,
+    price: String,
+}
+
+/// `<symbol>@miniTicker` payload inside a combined stream message
+#[derive(Debug, Deserialize)]
+struct MiniTicker {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "c")]
+    close: String,
+    #[serde(rename = "E")]
+    event_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    data: MiniTicker,
+}
+
+/// "BTCUSDT" -> "BTC" (None for other quote assets)
+fn base_asset(pair: &str) -> Option<&str> {
+    pair.strip_suffix(QUOTE).filter(|base| !base.is_empty())
+}
+
+fn to_price(pair: &str, price: &str, timestamp: i64) -> Option<CexPrice> {
+    let base = base_asset(pair)?;
+    let price_usd: f64 = price.parse().ok()?;
+    if !price_usd.is_finite() || price_usd <= 0.0 {
+        return None;
+    }
+    Some(CexPrice {
+        symbol: base.to_string(),
+        price_krw: 0.0,
+        price_usd,
+        timestamp,
+    })
+}
+
+impl BinanceClient {
+    pub fn from_config(config: &BinanceConfig) -> Self {
+        Self::with_client(http::shared_client(), config)
+    }
+
+    /// Client on an injected (shared) HTTP client
+    pub fn with_client(client: Client, config: &BinanceConfig) -> Self {
+        Self {
+            prices: Arc::new(DashMap::new()),
+            client,
+            timeout: Duration::from_secs(config.timeout_secs.max(1)),
+            rest_url: config.rest_url.clone(),
+            ws_url: config.ws_url.clone(),
+        }
+    }
+
+    pub fn get_all_prices(&self) -> Vec<CexPrice> {
+        self.prices.iter().map(|p| p.value().clone()).collect()
+    }
+
+    /// Update prices from a REST snapshot of every USDT pair; returns the pair count
+    pub async fn refresh(&self) -> Result<usize, SourceError> {
+        let req = self.client.get(format!("{}/api/v3/ticker/price", self.rest_url))
+            .timeout(self.timeout);
+        let tickers: Vec<BinanceTicker> = http::fetch_json("Binance", req).await?;
+
+        let now = chrono::Utc::now().timestamp_millis();
+        let mut count = 0;
+        for ticker in tickers {
+            if let Some(price) = to_price(&ticker.symbol, &ticker.price, now) {
+                self.prices.insert(price.symbol.clone(), price);
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Live updates via `<symbol>usdt@miniTicker` streams. Only symbols Binance
+    /// actually lists should be passed; unknown streams just stay silent.
+    pub async fn start_websocket(&self, symbols: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        if symbols.is_empty() {
+            return Ok(());
+        }
+
+        let streams: Vec<String> = symbols.iter()
+            .map(|s| format!("{}{}@miniTicker", s.to_lowercase(), QUOTE.to_lowercase()))
+            .collect();
+        let url = format!("{}/stream?streams={}", self.ws_url, streams.join("/"));
+        let (ws_stream, _) = connect_async(url).await?;
+        let (_write, mut read) = ws_stream.split();
+
+        let prices = self.prices.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = read.next().await {
+                if let Ok(Message::Text(text)) = msg {
+                    if let Ok(msg) = serde_json::from_str::<StreamMessage>(&text) {
+                        let ticker = msg.data;
+                        if let Some(price) = to_price(&ticker.symbol, &ticker.close, ticker.event_time) {
+                            prices.insert(price.symbol.clone(), price);
+                        }
+                    }
+                }
+            }
+            tracing::warn!("⚠️ Binance WebSocket closed");
+        });
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CexSource for BinanceClient {
+    fn name(&self) -> &'static str {
+        "Binance"
+    }
+
+    /// Base assets of every USDT pair (refreshes the price snapshot)
+    async fn fetch_symbols(&self) -> Result<Vec<String>, SourceError> {
+        self.refresh().await?;
+        let mut symbols: Vec<String> = self.prices.iter().map(|p| p.key().clone()).collect();
+        symbols.sort();
+        Ok(symbols)
+    }
+
+    fn get_all_prices(&self) -> Vec<CexPrice> {
+        BinanceClient::get_all_prices(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::serve;
+
+    #[tokio::test]
+    async fn refresh_parses_the_ticker_fixture() {
+        let router = axum::Router::new().route("/api/v3/ticker/price", axum::routing::get(|| async {
+            ([("content-type", "application/json")], include_str!("../../tests/fixtures/binance.json"))
+        }));
+        let client = BinanceClient::from_config(&BinanceConfig { rest_url: serve(router).await, ..BinanceConfig::default() });
+
+        // Non-USDT quotes, zero prices and the bare quote asset are skipped
+        assert_eq!(client.refresh().await.unwrap(), 3);
+        assert_eq!(client.fetch_symbols().await.unwrap(), ["BNB", "BTC", "ETH"]);
+        let eth = client.get_all_prices().into_iter().find(|p| p.symbol == "ETH").unwrap();
+        assert_eq!(eth.price_usd, 3012.45);
+        assert_eq!(eth.price_krw, 0.0);
+        assert!(eth.timestamp > 0);
+    }
+
+    #[test]
+    fn parses_combined_stream_mini_tickers() {
+        let text = r#"{"stream":"btcusdt@miniTicker","data":{"e":"24hrMiniTicker","E":1700000000123,"s":"BTCUSDT","c":"65100.50","o":"64000.00","h":"65500.00","l":"63900.00","v":"1200.5","q":"78000000.0"}}"#;
+        let ticker = serde_json::from_str::<StreamMessage>(text).unwrap().data;
+        let price = to_price(&ticker.symbol, &ticker.close, ticker.event_time).unwrap();
+        assert_eq!(price.symbol, "BTC");
+        assert_eq!(price.price_usd, 65100.5);
+        assert_eq!(price.timestamp, 1_700_000_000_123);
+    }
+}
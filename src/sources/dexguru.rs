@@ -1,12 +1,45 @@
 use async_trait::async_trait;
+use futures::future::join_all;
+use std::time::Duration;
 use reqwest::Client;
 use serde::Deserialize;
+use crate::config::{ApiKeysConfig, DexGuruConfig};
 use crate::models::PoolData;
-use super::{PoolSource, SourceError};
+use super::{http, quote, PoolSource, SourceError};
+use super::meta_agg::{lookup_token, TokenCache, TokenInfo};
+
+/// Timeout of each aggregator quote
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Aggregators queried by `DexGuruSource`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregator {
+    ZeroX,
+    OneInch,
+}
+
+impl Aggregator {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "0x" | "zerox" => Some(Aggregator::ZeroX),
+            "1inch" | "oneinch" => Some(Aggregator::OneInch),
+            _ => None,
+        }
+    }
+}
 
 /// dex-guru 스타일 멀티 어그리게이터
 pub struct DexGuruSource {
     client: Client,
+    chains: Vec<u32>,
+    /// Only aggregators with a configured key
+    aggregators: Vec<Aggregator>,
+    oneinch_key: String,
+    zerox_key: String,
+    /// Per-chain contract and decimals of each symbol
+    cache: TokenCache,
+    /// Replaces both APIs (tests): 1inch at `{base}/1inch`, 0x at `{base}/0x/{chain_id}`
+    base_url: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -17,93 +50,158 @@ struct OneInchQuote {
     from_amount: String,
 }
 
+/// Chain name for the chains both aggregators support
+fn chain_name(chain_id: u32) -> Option<&'static str> {
+    match chain_id {
+        1 => Some("ethereum"),
+        56 => Some("bsc"),
+        137 => Some("polygon"),
+        _ => None,
+    }
+}
+
 impl DexGuruSource {
-    pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(10))
-                .build()
-                .unwrap(),
+    /// Source on an injected (shared) client. Aggregators without an API key
+    /// are dropped; None when none are left, so no doomed requests are made.
+    /// Token addresses come from `cache`; symbols it doesn't know are not quoted.
+    pub fn with_client(client: Client, config: &DexGuruConfig, keys: &ApiKeysConfig, cache: TokenCache) -> Option<Self> {
+        let aggregators: Vec<Aggregator> = config.aggregators.iter()
+            .filter_map(|name| {
+                let agg = Aggregator::parse(name);
+                if agg.is_none() {
+                    tracing::warn!("⚠️ Unknown DexGuru aggregator: {}", name);
+                }
+                agg
+            })
+            .filter(|agg| {
+                let has_key = match agg {
+                    Aggregator::ZeroX => keys.zerox().is_some(),
+                    Aggregator::OneInch => keys.oneinch().is_some(),
+                };
+                if !has_key {
+                    tracing::warn!("⚠️ DexGuru: no API key for {:?}, skipped", agg);
+                }
+                has_key
+            })
+            .collect();
+
+        if aggregators.is_empty() {
+            return None;
         }
+        Some(Self {
+            client,
+            chains: config.chains.clone(),
+            aggregators,
+            oneinch_key: keys.oneinch().unwrap_or_default().to_string(),
+            zerox_key: keys.zerox().unwrap_or_default().to_string(),
+            cache,
+            base_url: None,
+        })
+    }
+
+    #[cfg(test)]
+    fn with_base_url(mut self, base: &str) -> Self {
+        self.base_url = Some(base.trim_end_matches('/').to_string());
+        self
     }
 
-    async fn fetch_1inch(&self, chain_id: u32, token: &str) -> Result<Option<f64>, SourceError> {
-        // 1inch API - USDC 기준 가격 조회
-        let usdc = match chain_id {
-            1 => "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", // ETH
-            56 => "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d", // BSC
-            137 => "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174", // Polygon
-            _ => return Ok(None),
+    async fn fetch_1inch(&self, chain_id: u32, token: &TokenInfo, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        // 1inch API - 스테이블코인 기준 가격 조회
+        let Some(chain) = chain_name(chain_id) else {
+            return Ok(vec![]);
+        };
+        let Some(quote) = quote::quote_token(chain_id) else {
+            return Ok(vec![]);
         };
 
+        let base = self.base_url.as_ref().map(|b| format!("{}/1inch", b))
+            .unwrap_or_else(|| "https://api.1inch.dev".to_string());
         let url = format!(
-            "https://api.1inch.dev/swap/v6.0/{}/quote?src={}&dst={}&amount=1000000000000000000",
-            chain_id, token, usdc
+            "{}/swap/v6.0/{}/quote?src={}&dst={}&amount={}",
+            base, chain_id, token.address, quote.address, token.one_token()
         );
 
-        let resp = self.client.get(&url)
-            .header("Authorization", "Bearer YOUR_API_KEY") // 필요시 설정
-            .send()
-            .await
-            .map_err(|e| SourceError::Network(e.to_string()))?;
+        let req = self.client.get(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .bearer_auth(&self.oneinch_key);
 
-        if !resp.status().is_success() {
-            return Ok(None);
-        }
+        let response: OneInchQuote = match http::fetch_json(self.name(), req).await {
+            Ok(response) => response,
+            Err(SourceError::Http(_)) => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
 
-        let quote: OneInchQuote = resp.json()
-            .await
-            .map_err(|e| SourceError::Parse(e.to_string()))?;
+        // Both amounts are raw: the input in the token's decimals, the output in the quote's
+        let from_amount: f64 = response.from_amount.parse().unwrap_or(0.0);
+        let to_amount: f64 = response.to_amount.parse().unwrap_or(0.0);
+        let price = quote.to_units(to_amount) / (from_amount / 10f64.powi(token.decimals as i32));
 
-        let from_amount: f64 = quote.from_amount.parse().unwrap_or(1.0);
-        let to_amount: f64 = quote.to_amount.parse().unwrap_or(0.0);
-        let price = to_amount / from_amount * 1e12; // USDC 6 decimals
+        if price <= 0.0 {
+            return Ok(vec![]);
+        }
 
-        Ok(Some(price))
+        Ok(vec![PoolData::new(
+            symbol.to_string(),
+            chain.to_string(),
+            "1inch".to_string(),
+            "aggregated".to_string(),
+            format!("{}/{}", symbol, quote.symbol),
+            price,
+            0.0, // 1inch doesn't return LP
+            0.0,
+            "1inch".to_string(),
+        ).with_tokens(Some(token.address.clone()), Some(quote.address.to_string()))])
     }
 
-    async fn fetch_0x(&self, chain_id: u32, token: &str, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        // 0x API
-        let chain = match chain_id {
-            1 => "ethereum",
-            56 => "bsc",
-            137 => "polygon",
+    async fn fetch_0x(&self, chain_id: u32, token: &TokenInfo, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        // 0x API - 체인별 호스트
+        let (chain, host) = match chain_id {
+            1 => ("ethereum", "api.0x.org"),
+            56 => ("bsc", "bsc.api.0x.org"),
+            137 => ("polygon", "polygon.api.0x.org"),
             _ => return Ok(vec![]),
         };
 
+        let base = self.base_url.as_ref().map(|b| format!("{}/0x/{}", b, chain_id))
+            .unwrap_or_else(|| format!("https://{}", host));
         let url = format!(
-            "https://api.0x.org/swap/v1/price?sellToken={}&buyToken=USDC&sellAmount=1000000000000000000",
-            token
+            "{}/swap/v1/price?sellToken={}&buyToken=USDC&sellAmount={}",
+            base, token.address, token.one_token()
         );
 
-        let resp = self.client.get(&url)
-            .header("0x-api-key", "YOUR_API_KEY")
-            .send()
-            .await;
-
-        match resp {
-            Ok(r) if r.status().is_success() => {
-                if let Ok(data) = r.json::<serde_json::Value>().await {
-                    if let Some(price) = data["price"].as_str().and_then(|s| s.parse::<f64>().ok()) {
-                        return Ok(vec![PoolData::new(
-                            symbol.to_string(),
-                            chain.to_string(),
-                            "0x".to_string(),
-                            "aggregated".to_string(),
-                            format!("{}/USDC", symbol),
-                            price,
-                            0.0, // 0x doesn't return LP
-                            0.0,
-                            "0x".to_string(),
-                        )]);
-                    }
-                }
+        let req = self.client.get(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .header("0x-api-key", &self.zerox_key);
+
+        if let Ok(data) = http::fetch_json::<serde_json::Value>(self.name(), req).await {
+            if let Some(price) = data["price"].as_str().and_then(|s| s.parse::<f64>().ok()) {
+                return Ok(vec![PoolData::new(
+                    symbol.to_string(),
+                    chain.to_string(),
+                    "0x".to_string(),
+                    "aggregated".to_string(),
+                    format!("{}/USDC", symbol),
+                    price,
+                    0.0, // 0x doesn't return LP
+                    0.0,
+                    "0x".to_string(),
+                ).with_tokens(Some(token.address.clone()), data["buyTokenAddress"].as_str().map(str::to_string))]);
             }
-            _ => {}
         }
 
         Ok(vec![])
     }
+
+    async fn fetch_one(&self, aggregator: Aggregator, chain_id: u32, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        // Both APIs need the contract address; chains without one are skipped
+        let Some(token) = lookup_token(&self.cache, symbol, chain_id) else {
+            return Ok(vec![]);
+        };
+        match aggregator {
+            Aggregator::ZeroX => self.fetch_0x(chain_id, &token, symbol).await,
+            Aggregator::OneInch => self.fetch_1inch(chain_id, &token, symbol).await,
+        }
+    }
 }
 
 #[async_trait]
@@ -113,15 +211,173 @@ impl PoolSource for DexGuruSource {
     }
 
     async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        let mut pools = Vec::new();
+        // 체인 × 어그리게이터 조합을 동시에 조회
+        let requests = self.chains.iter()
+            .filter(|chain_id| chain_name(**chain_id).is_some())
+            .flat_map(|chain_id| {
+                self.aggregators.iter().map(move |agg| self.fetch_one(*agg, *chain_id, symbol))
+            });
 
-        // 여러 체인에서 0x 가격 조회
-        for (chain_id, _chain_name) in [(1u32, "ethereum"), (56, "bsc"), (137, "polygon")] {
-            if let Ok(chain_pools) = self.fetch_0x(chain_id, symbol, symbol).await {
-                pools.extend(chain_pools);
-            }
-        }
+        let pools = join_all(requests).await
+            .into_iter()
+            .filter_map(|result| result.ok())
+            .flatten()
+            .collect();
 
         Ok(pools)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use axum::extract::{Path as UrlPath, Query};
+    use axum::routing::get;
+    use parking_lot::{Mutex, RwLock};
+    use crate::test_util::serve;
+
+    /// Each mock call stays open this long, so concurrent calls overlap
+    const MOCK_DELAY: Duration = Duration::from_millis(200);
+
+    const WBTC_ETHEREUM: &str = "0x2260fac5e5542a773aa44fbcfedf7c193bc2c599";
+    const BTCB_BSC: &str = "0x7130d2a12b9bcbfae4f2634d864a1ee1ce3ead9c";
+
+    /// What the mock aggregators saw
+    #[derive(Default)]
+    struct Requests {
+        /// (aggregator, chain, token param, amount param)
+        seen: Mutex<Vec<(&'static str, u32, String, String)>>,
+        in_flight: AtomicUsize,
+        peak_in_flight: AtomicUsize,
+    }
+
+    impl Requests {
+        /// Record a request and hold it open for `MOCK_DELAY`
+        async fn handle(&self, aggregator: &'static str, chain: u32, token: &str, amount: &str) {
+            self.seen.lock().push((aggregator, chain, token.to_string(), amount.to_string()));
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak_in_flight.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(MOCK_DELAY).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        }
+
+        fn sorted(&self) -> Vec<(&'static str, u32, String, String)> {
+            let mut seen = self.seen.lock().clone();
+            seen.sort();
+            seen
+        }
+    }
+
+    /// 1 token -> 60,000 USDC on both APIs; the 1inch input echoes the requested amount
+    async fn mock_aggregators(requests: Arc<Requests>) -> String {
+        let oneinch_requests = requests.clone();
+        let router = axum::Router::new()
+            .route("/1inch/swap/v6.0/:chain/quote", get(
+                move |UrlPath(chain): UrlPath<u32>, Query(q): Query<HashMap<String, String>>| async move {
+                    oneinch_requests.handle("1inch", chain, &q["src"], &q["amount"]).await;
+                    // BSC USDC has 18 decimals, the others 6
+                    let to_amount = if chain == 56 { "60000000000000000000000" } else { "60000000000" };
+                    axum::Json(serde_json::json!({ "fromAmount": q["amount"], "toAmount": to_amount }))
+                },
+            ))
+            .route("/0x/:chain/swap/v1/price", get(
+                move |UrlPath(chain): UrlPath<u32>, Query(q): Query<HashMap<String, String>>| async move {
+                    requests.handle("0x", chain, &q["sellToken"], &q["sellAmount"]).await;
+                    axum::Json(serde_json::json!({ "price": format!("{}", 59_990 + chain % 10), "buyTokenAddress": "0xusdc" }))
+                },
+            ));
+        serve(router).await
+    }
+
+    /// WBTC known on Ethereum (8 decimals) and BSC (18), not on Polygon
+    fn token_cache() -> TokenCache {
+        let chains = HashMap::from([
+            (1, TokenInfo { address: WBTC_ETHEREUM.to_string(), decimals: 8 }),
+            (56, TokenInfo { address: BTCB_BSC.to_string(), decimals: 18 }),
+        ]);
+        Arc::new(RwLock::new(HashMap::from([("WBTC".to_string(), chains)])))
+    }
+
+    fn source(base: &str, aggregators: &[&str]) -> DexGuruSource {
+        let config = DexGuruConfig {
+            enabled: true,
+            chains: vec![1, 56, 137],
+            aggregators: aggregators.iter().map(|a| a.to_string()).collect(),
+        };
+        let keys = ApiKeysConfig {
+            oneinch: Some("k1".to_string()),
+            zerox: Some("k2".to_string()),
+            thegraph: None,
+        };
+        DexGuruSource::with_client(http::shared_client(), &config, &keys, token_cache())
+            .expect("both aggregators have keys")
+            .with_base_url(base)
+    }
+
+    #[tokio::test]
+    async fn collects_both_aggregators_on_every_chain_concurrently() {
+        let requests = Arc::new(Requests::default());
+        let base = mock_aggregators(requests.clone()).await;
+        let mut pools = source(&base, &["0x", "1inch"]).fetch_pools("wbtc").await.unwrap();
+
+        // 2 cached chains x 2 aggregators, all open at once rather than back to back
+        assert_eq!(pools.len(), 4);
+        assert_eq!(requests.peak_in_flight.load(Ordering::SeqCst), 4);
+
+        pools.sort_by(|a, b| (&a.dex, &a.chain).cmp(&(&b.dex, &b.chain)));
+        let got: Vec<(&str, &str, f64, Option<&str>)> = pools.iter()
+            .map(|p| (p.dex.as_str(), p.chain.as_str(), p.price_usd, p.base_token.as_deref()))
+            .collect();
+        assert_eq!(got, [
+            ("0x", "bsc", 59_996.0, Some(BTCB_BSC)),
+            ("0x", "ethereum", 59_991.0, Some(WBTC_ETHEREUM)),
+            ("1inch", "bsc", 60_000.0, Some(BTCB_BSC)),
+            ("1inch", "ethereum", 60_000.0, Some(WBTC_ETHEREUM)),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn requests_carry_the_cached_address_and_one_whole_token() {
+        let requests = Arc::new(Requests::default());
+        let base = mock_aggregators(requests.clone()).await;
+        source(&base, &["0x", "1inch"]).fetch_pools("WBTC").await.unwrap();
+
+        let one_btcb = "1000000000000000000".to_string();
+        assert_eq!(requests.sorted(), [
+            ("0x", 1, WBTC_ETHEREUM.to_string(), "100000000".to_string()),
+            ("0x", 56, BTCB_BSC.to_string(), one_btcb.clone()),
+            ("1inch", 1, WBTC_ETHEREUM.to_string(), "100000000".to_string()),
+            ("1inch", 56, BTCB_BSC.to_string(), one_btcb),
+        ], "Polygon has no cached address and is skipped");
+    }
+
+    #[tokio::test]
+    async fn uncached_symbols_are_never_requested() {
+        let requests = Arc::new(Requests::default());
+        let base = mock_aggregators(requests.clone()).await;
+        assert!(source(&base, &["0x", "1inch"]).fetch_pools("WETH").await.unwrap().is_empty());
+        assert!(requests.sorted().is_empty());
+    }
+
+    #[tokio::test]
+    async fn only_configured_aggregators_are_queried() {
+        let base = mock_aggregators(Arc::default()).await;
+        let pools = source(&base, &["1inch"]).fetch_pools("WBTC").await.unwrap();
+        assert_eq!(pools.len(), 2);
+        assert!(pools.iter().all(|p| p.dex == "1inch"));
+    }
+
+    #[test]
+    fn aggregator_without_key_is_dropped() {
+        let config = DexGuruConfig::default();
+        let keys = ApiKeysConfig { oneinch: None, zerox: Some("k".to_string()), thegraph: None };
+        let source = DexGuruSource::with_client(http::shared_client(), &config, &keys, token_cache()).unwrap();
+        assert_eq!(source.aggregators, [Aggregator::ZeroX]);
+
+        let none = ApiKeysConfig { oneinch: Some("  ".to_string()), zerox: None, thegraph: None };
+        assert!(DexGuruSource::with_client(http::shared_client(), &config, &none, token_cache()).is_none());
+    }
+}
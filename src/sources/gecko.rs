@@ -1,27 +1,35 @@
 use async_trait::async_trait;
+use std::time::Duration;
 use reqwest::Client;
 use serde::Deserialize;
-use crate::models::PoolData;
-use super::{PoolSource, SourceError};
+use crate::models::pool::{parse_fee_label, PoolData};
+use super::{http, PoolSource, SourceError};
+
+/// Timeout of each GeckoTerminal request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
 
 pub struct GeckoTerminal {
     client: Client,
 }
 
+/// Pools stay raw until `http::lenient_entries`, so one bad pool is skipped alone
 #[derive(Debug, Deserialize)]
 struct GeckoResponse {
-    data: Vec<GeckoPool>,
+    #[serde(default)]
+    data: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
 struct GeckoPool {
-    #[allow(dead_code)]
+    /// "{network}_{address}", e.g. "eth_0x..."
     id: String,
     attributes: GeckoPoolAttributes,
     relationships: Option<GeckoRelationships>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Missing fields default; pools without an address are dropped afterwards
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
 struct GeckoPoolAttributes {
     name: String,
     address: String,
@@ -37,69 +45,82 @@ struct GeckoVolume {
 
 #[derive(Debug, Deserialize)]
 struct GeckoRelationships {
-    dex: Option<GeckoDex>,
+    dex: Option<GeckoRelation>,
+    network: Option<GeckoRelation>,
+    base_token: Option<GeckoRelation>,
+    quote_token: Option<GeckoRelation>,
 }
 
+/// `{ "data": { "id": ... } }` relationship (dex, network)
 #[derive(Debug, Deserialize)]
-struct GeckoDex {
-    data: Option<GeckoDexData>,
+struct GeckoRelation {
+    data: Option<GeckoRelationData>,
 }
 
 #[derive(Debug, Deserialize)]
-struct GeckoDexData {
+struct GeckoRelationData {
     id: String,
 }
 
-impl GeckoTerminal {
-    pub fn new() -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(5))
-                .build()
-                .unwrap(),
-        }
+impl GeckoRelationships {
+    fn dex_id(&self) -> Option<&str> {
+        self.dex.as_ref()?.data.as_ref().map(|d| d.id.as_str())
     }
-}
 
-#[async_trait]
-impl PoolSource for GeckoTerminal {
-    fn name(&self) -> &'static str {
-        "GeckoTerminal"
+    fn network_id(&self) -> Option<&str> {
+        self.network.as_ref()?.data.as_ref().map(|d| d.id.as_str())
     }
 
-    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        let url = format!(
-            "https://api.geckoterminal.com/api/v2/search/pools?query={}",
-            symbol
-        );
+    /// Token ids are "{network}_{address}"; returns the address part
+    fn token_address(relation: &Option<GeckoRelation>) -> Option<String> {
+        let id = &relation.as_ref()?.data.as_ref()?.id;
+        Some(id.rsplit_once('_').map_or(id.as_str(), |(_, address)| address).to_string())
+    }
+}
 
-        let resp = self.client.get(&url)
-            .header("Accept", "application/json")
-            .send()
-            .await
-            .map_err(|e| SourceError::Network(e.to_string()))?;
+/// GeckoTerminal network id ("eth", "polygon_pos", ...): the network
+/// relationship, else the `{network}_{address}` pool id prefix, else "unknown".
+/// The canonical name is applied by `PoolData::new`.
+fn network_of(pool: &GeckoPool) -> String {
+    pool.relationships.as_ref()
+        .and_then(|r| r.network_id())
+        .or_else(|| pool.id.rsplit_once('_').map(|(network, _)| network))
+        .filter(|network| !network.is_empty())
+        .unwrap_or("unknown")
+        .to_string()
+}
 
-        if resp.status() == 429 {
-            return Err(SourceError::RateLimit);
-        }
+impl Default for GeckoTerminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        if !resp.status().is_success() {
-            return Ok(vec![]);
-        }
+impl GeckoTerminal {
+    pub fn new() -> Self {
+        Self::with_client(http::shared_client())
+    }
 
-        let data: GeckoResponse = resp.json()
-            .await
-            .map_err(|e| SourceError::Parse(e.to_string()))?;
+    /// Source on an injected (shared) client
+    pub fn with_client(client: Client) -> Self {
+        Self { client }
+    }
 
+    /// Pools of a search response whose pair name contains the symbol (or its W-prefixed form)
+    fn parse_pools(symbol: &str, data: GeckoResponse) -> Vec<PoolData> {
         let upper_symbol = symbol.to_uppercase();
         
-        let pools: Vec<PoolData> = data.data.into_iter()
+        let entries: Vec<GeckoPool> = http::lenient_entries("GeckoTerminal", data.data);
+        entries.into_iter()
+            .filter(|p| !p.attributes.address.is_empty())
             .filter_map(|p| {
-                // 심볼 정확 매칭 필터: pair name에서 심볼 확인
+                // 심볼 정확 매칭 필터: pair name의 base 쪽에서 심볼 확인
                 let pair_name = p.attributes.name.to_uppercase();
-                
-                // 정확한 심볼 매칭 (예: "BTC" → "BTC / USDC" OK, "BITCOIN" NO)
-                let symbol_match = pair_name.split(|c: char| !c.is_alphanumeric())
+                let base_name = pair_name.split('/').next().unwrap_or("");
+
+                // 정확한 심볼 매칭 (예: "BTC" → "BTC / USDC" OK, "BITCOIN" NO, "ETHFI / WETH" NO:
+                // 가격은 base 토큰 기준)
+                let symbol_match = base_name.split(|c: char| !c.is_alphanumeric())
                     .any(|part| part == upper_symbol || part == format!("W{}", upper_symbol));
                 
                 if !symbol_match {
@@ -121,26 +142,148 @@ impl PoolSource for GeckoTerminal {
                     .and_then(|s| s.parse::<f64>().ok())
                     .unwrap_or(0.0);
 
-                let dex_name = p.relationships
-                    .and_then(|r| r.dex)
-                    .and_then(|d| d.data)
-                    .map(|d| d.id)
-                    .unwrap_or_else(|| "unknown".to_string());
+                let dex_name = p.relationships.as_ref()
+                    .and_then(|r| r.dex_id())
+                    .unwrap_or("unknown")
+                    .to_string();
+
+                // V3 풀 이름에 fee tier 포함 (예: "WETH / USDC 0.05%")
+                let fee = parse_fee_label(&p.attributes.name);
+                let network = network_of(&p);
+                let (base_token, quote_token) = p.relationships.as_ref()
+                    .map(|r| (
+                        GeckoRelationships::token_address(&r.base_token),
+                        GeckoRelationships::token_address(&r.quote_token),
+                    ))
+                    .unwrap_or_default();
 
                 Some(PoolData::new(
                     symbol.to_string(),
-                    "multi".to_string(),
-                    dex_name,
+                    network,
+                    dex_name.clone(),
                     p.attributes.address,
                     p.attributes.name,
                     price,
                     lp,
                     volume,
                     "geckoterminal".to_string(),
-                ))
+                )
+                .with_fee_tier(fee)
+                .with_conventional_fee(&dex_name)
+                .with_tokens(base_token, quote_token))
             })
-            .collect();
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PoolSource for GeckoTerminal {
+    fn name(&self) -> &'static str {
+        "GeckoTerminal"
+    }
+
+    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        let url = format!(
+            "https://api.geckoterminal.com/api/v2/search/pools?query={}",
+            symbol
+        );
+
+        let req = self.client.get(&url)
+            .timeout(REQUEST_TIMEOUT)
+            .header("Accept", "application/json");
+
+        let data: GeckoResponse = match http::fetch_json(self.name(), req).await {
+            Ok(data) => data,
+            Err(SourceError::Http(_)) => return Ok(vec![]),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self::parse_pools(symbol, data))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture() -> GeckoResponse {
+        serde_json::from_str(include_str!("../../tests/fixtures/geckoterminal.json")).unwrap()
+    }
+
+    #[test]
+    fn parses_the_search_fixture() {
+        let pools = GeckoTerminal::parse_pools("ETH", fixture());
+        // ETHFI / WETH prices ETHFI, and the null-price pool has nothing to report
+        assert_eq!(pools.len(), 2, "{pools:?}");
+
+        let mainnet = &pools[0];
+        assert_eq!(mainnet.symbol, "ETH");
+        assert_eq!(mainnet.chain, "ethereum");
+        assert_eq!(mainnet.dex, "uniswap_v3");
+        assert_eq!(mainnet.pool_address, "0x88e6a0c2ddd26feeb64f039a2c41296fcb3f5640");
+        assert_eq!(mainnet.pair, "WETH / USDC 0.05%");
+        assert_eq!(mainnet.price_usd, 3012.4507813);
+        assert_eq!(mainnet.lp_reserve_usd, 152298113.4021);
+        assert_eq!(mainnet.volume_24h, 48211904.1204);
+        assert_eq!(mainnet.fee_tier, Some(0.0005));
+        assert!(!mainnet.fee_estimated);
+        assert_eq!(mainnet.base_token.as_deref(), Some("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"));
+        assert_eq!(mainnet.quote_token.as_deref(), Some("0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48"));
+        assert_eq!(mainnet.source, "geckoterminal");
+
+        // No fee in the name: the DEX's conventional fee
+        let polygon = &pools[1];
+        assert_eq!(polygon.chain, "polygon");
+        assert_eq!(polygon.dex, "quickswap");
+        assert_eq!(polygon.fee_tier, Some(0.003));
+        assert!(polygon.fee_estimated);
+        assert_eq!(polygon.base_token.as_deref(), Some("0x7ceb23fd6bc0add59e62ac25578270cff1b9f619"));
+    }
+
+    /// One-pool search response; `relationships` is inserted verbatim
+    fn response(id: &str, relationships: &str) -> GeckoResponse {
+        serde_json::from_str(&format!(r#"{{ "data": [{{
+            "id": "{id}",
+            "attributes": {{ "name": "WETH / USDC", "address": "0xpool", "base_token_price_usd": "3000" }}
+            {relationships}
+        }}] }}"#)).unwrap()
+    }
+
+    fn chain_of(data: GeckoResponse) -> String {
+        let pools = GeckoTerminal::parse_pools("ETH", data);
+        assert_eq!(pools.len(), 1);
+        pools[0].chain.clone()
+    }
+
+    #[test]
+    fn network_relationship_wins_over_the_id_prefix() {
+        let network = r#", "relationships": { "network": { "data": { "id": "arbitrum", "type": "network" } } }"#;
+        assert_eq!(chain_of(response("eth_0xpool", network)), "arbitrum");
+    }
+
+    #[test]
+    fn pool_id_prefix_is_the_fallback() {
+        assert_eq!(chain_of(response("bsc_0xpool", "")), "bsc");
+        assert_eq!(chain_of(response("polygon_pos_0xpool", "")), "polygon");
+        // Relationships present but without a network
+        let dex_only = r#", "relationships": { "dex": { "data": { "id": "uniswap_v3" } }, "network": { "data": null } }"#;
+        assert_eq!(chain_of(response("eth_0xpool", dex_only)), "ethereum");
+    }
+
+    #[test]
+    fn undeterminable_network_is_unknown_not_multi() {
+        assert_eq!(chain_of(response("0xpool", "")), "unknown");
+        assert_eq!(chain_of(response("_0xpool", "")), "unknown");
+    }
 
-        Ok(pools)
+    #[test]
+    fn a_malformed_pool_is_skipped_alone() {
+        let data: GeckoResponse = serde_json::from_str(r#"{ "data": [
+            { "id": "eth_0xgood", "attributes": { "name": "WETH / USDC", "address": "0xgood", "base_token_price_usd": "3000" } },
+            { "id": 42, "attributes": "not an object" }
+        ] }"#).unwrap();
+        let pools = GeckoTerminal::parse_pools("ETH", data);
+        assert_eq!(pools.len(), 1);
+        assert_eq!((pools[0].pool_address.as_str(), pools[0].price_usd), ("0xgood", 3000.0));
     }
 }
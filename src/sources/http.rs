@@ -0,0 +1,324 @@
+use reqwest::{Client, RequestBuilder};
+use std::time::Duration;
+use serde::de::DeserializeOwned;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use super::{retry_policy, retry_with_backoff, SourceError};
+
+static SHARED_CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Process-wide HTTP client: one connection pool and TLS setup for every source.
+/// Cloning is cheap and shares the pool, so sources hold a clone instead of building
+/// their own. Because the client is shared it carries no timeout; each caller sets
+/// one per request (`RequestBuilder::timeout`) to suit its API.
+pub fn shared_client() -> Client {
+    SHARED_CLIENT.get_or_init(|| {
+        Client::builder()
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .unwrap()
+    }).clone()
+}
+
+/// Deadletter sink for unparseable source responses (opt-in)
+struct DeadLetter {
+    dir: PathBuf,
+    max_files: usize,
+    seq: AtomicU64,
+}
+
+static DEADLETTER: OnceLock<DeadLetter> = OnceLock::new();
+
+/// Enable deadletter capture into `dir`, keeping at most `max_files` files
+pub fn init_deadletter(dir: &str, max_files: usize) {
+    let dir = PathBuf::from(dir);
+    if let Err(e) = fs::create_dir_all(&dir) {
+        tracing::warn!("⚠️ Deadletter dir {} unavailable: {}", dir.display(), e);
+        return;
+    }
+    let _ = DEADLETTER.set(DeadLetter {
+        dir,
+        max_files: max_files.max(1),
+        seq: AtomicU64::new(0),
+    });
+}
+
+/// Fixture sink: first successful raw response per source (opt-in)
+struct FixtureCapture {
+    dir: PathBuf,
+    captured: Mutex<HashSet<String>>,
+}
+
+static CAPTURE: OnceLock<FixtureCapture> = OnceLock::new();
+
+/// Enable fixture capture into `dir` as `<source>.json`
+pub fn init_capture(dir: &str) -> std::io::Result<()> {
+    let dir = PathBuf::from(dir);
+    fs::create_dir_all(&dir)?;
+    let _ = CAPTURE.set(FixtureCapture {
+        dir,
+        captured: Mutex::new(HashSet::new()),
+    });
+    Ok(())
+}
+
+/// Fixture path for a source name (lowercase)
+pub fn fixture_path(dir: &Path, source: &str) -> PathBuf {
+    dir.join(format!("{}.json", source.to_lowercase()))
+}
+
+/// Sources captured so far
+pub fn captured_sources() -> Vec<String> {
+    let mut sources: Vec<String> = CAPTURE.get()
+        .map(|c| c.captured.lock().iter().cloned().collect())
+        .unwrap_or_default();
+    sources.sort();
+    sources
+}
+
+impl FixtureCapture {
+    fn record(&self, source: &str, body: &str) {
+        // Only valid JSON makes a usable fixture
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(body) else {
+            return;
+        };
+        if !self.captured.lock().insert(source.to_string()) {
+            return;
+        }
+
+        let path = fixture_path(&self.dir, source);
+        let pretty = serde_json::to_string_pretty(&value).unwrap_or_else(|_| body.to_string());
+        match fs::write(&path, pretty) {
+            Ok(_) => tracing::info!("📸 Fixture saved: {}", path.display()),
+            Err(e) => {
+                tracing::warn!("⚠️ Fixture write failed for {}: {}", source, e);
+                self.captured.lock().remove(source);
+            }
+        }
+    }
+}
+
+impl DeadLetter {
+    fn record(&self, source: &str, url: &str, body: &str, error: &str) {
+        let now = chrono::Utc::now();
+        let seq = self.seq.fetch_add(1, Ordering::Relaxed);
+        let filename = format!("{}_{:06}_{}.json", now.timestamp_millis(), seq, source);
+        let entry = serde_json::json!({
+            "source": source,
+            "url": url,
+            "timestamp": now.timestamp(),
+            "error": error,
+            "body": body,
+        });
+
+        match fs::write(self.dir.join(&filename), entry.to_string()) {
+            Ok(_) => tracing::debug!("🪦 Deadletter saved: {}", filename),
+            Err(e) => tracing::warn!("⚠️ Deadletter write failed: {}", e),
+        }
+
+        self.enforce_limit();
+    }
+
+    /// Drop the oldest files beyond `max_files` (names sort by timestamp)
+    fn enforce_limit(&self) {
+        let mut files: Vec<PathBuf> = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.extension().map(|e| e == "json").unwrap_or(false))
+                .collect(),
+            Err(_) => return,
+        };
+
+        if files.len() <= self.max_files {
+            return;
+        }
+
+        files.sort();
+        let excess = files.len() - self.max_files;
+        for path in files.into_iter().take(excess) {
+            fs::remove_file(path).ok();
+        }
+    }
+}
+
+/// Entries of a response array that deserialize as `T`. Malformed entries are
+/// skipped (and counted in the log) so one bad pool doesn't fail the symbol.
+pub fn lenient_entries<T: DeserializeOwned>(source: &str, entries: Vec<serde_json::Value>) -> Vec<T> {
+    let total = entries.len();
+    let parsed: Vec<T> = entries.into_iter()
+        .filter_map(|entry| serde_json::from_value(entry).ok())
+        .collect();
+    if parsed.len() < total {
+        tracing::warn!("⚠️ {}: skipped {}/{} malformed entries", source, total - parsed.len(), total);
+    }
+    parsed
+}
+
+/// Send a request and parse the JSON body, capturing unparseable bodies
+/// into the deadletter dir (and the first body per source as a fixture) when enabled.
+/// Network errors and rate limits are retried per the global `RetryPolicy`.
+pub async fn fetch_json<T: DeserializeOwned>(source: &str, req: RequestBuilder) -> Result<T, SourceError> {
+    // Requests with streaming bodies can't be cloned; send those once
+    if req.try_clone().is_none() {
+        return fetch_json_once(source, req).await;
+    }
+    retry_with_backoff(retry_policy(), || fetch_json_once(source, req.try_clone().unwrap())).await
+}
+
+/// `fetch_json` without the retries, for callers running their own retry loop
+pub async fn fetch_json_once<T: DeserializeOwned>(source: &str, req: RequestBuilder) -> Result<T, SourceError> {
+    super::ratelimit::acquire(source).await;
+    let resp = req.send()
+        .await
+        .map_err(|e| SourceError::Network(e.to_string()))?;
+
+    let status = resp.status();
+    if status == 429 {
+        let retry_after = resp.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<u64>().ok())
+            .map(Duration::from_secs);
+        return Err(SourceError::RateLimit(retry_after));
+    }
+    if !status.is_success() {
+        return Err(SourceError::Http(status.as_u16()));
+    }
+
+    let url = resp.url().to_string();
+    let body = resp.text()
+        .await
+        .map_err(|e| SourceError::Network(e.to_string()))?;
+
+    if let Some(capture) = CAPTURE.get() {
+        capture.record(source, &body);
+    }
+
+    serde_json::from_str(&body).map_err(|e| {
+        if let Some(deadletter) = DEADLETTER.get() {
+            deadletter.record(source, &url, &body, &e.to_string());
+        }
+        SourceError::Parse(e.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{serve, temp_dir};
+
+    fn json_files(dir: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir).unwrap()
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|e| e == "json"))
+            .collect();
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn deadletter_records_body_and_error() {
+        let dir = temp_dir("deadletter-record");
+        let deadletter = DeadLetter { dir: dir.clone(), max_files: 10, seq: AtomicU64::new(0) };
+        deadletter.record("Gecko", "https://example.com/pools", "<html>oops</html>", "expected value at line 1");
+
+        let files = json_files(&dir);
+        assert_eq!(files.len(), 1);
+        assert!(files[0].file_name().unwrap().to_string_lossy().ends_with("_Gecko.json"));
+        let entry: serde_json::Value = serde_json::from_str(&fs::read_to_string(&files[0]).unwrap()).unwrap();
+        assert_eq!(entry["source"], "Gecko");
+        assert_eq!(entry["url"], "https://example.com/pools");
+        assert_eq!(entry["body"], "<html>oops</html>");
+        assert_eq!(entry["error"], "expected value at line 1");
+        assert!(entry["timestamp"].as_i64().unwrap() > 0);
+    }
+
+    #[test]
+    fn deadletter_keeps_at_most_max_files() {
+        let dir = temp_dir("deadletter-limit");
+        let deadletter = DeadLetter { dir: dir.clone(), max_files: 3, seq: AtomicU64::new(0) };
+        for i in 0..7 {
+            deadletter.record("Src", "http://x", &format!("body {i}"), "bad");
+        }
+
+        let files = json_files(&dir);
+        assert_eq!(files.len(), 3);
+        // The newest entries survive
+        let bodies: Vec<String> = files.iter()
+            .map(|f| serde_json::from_str::<serde_json::Value>(&fs::read_to_string(f).unwrap()).unwrap()["body"]
+                .as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(bodies, ["body 4", "body 5", "body 6"]);
+    }
+
+    #[tokio::test]
+    async fn parse_failure_is_deadlettered() {
+        let dir = temp_dir("deadletter-fetch");
+        init_deadletter(dir.to_str().unwrap(), 5);
+        let base = serve(axum::Router::new().route("/pools", axum::routing::get(|| async { "not json" }))).await;
+
+        let req = shared_client().get(format!("{base}/pools"));
+        let result = fetch_json::<serde_json::Value>("Broken", req).await;
+        assert!(matches!(result, Err(SourceError::Parse(_))));
+
+        // The deadletter is process-wide; other tests' parse failures may land here too
+        let files: Vec<PathBuf> = json_files(&dir).into_iter()
+            .filter(|f| f.to_string_lossy().ends_with("_Broken.json"))
+            .collect();
+        assert_eq!(files.len(), 1);
+        let entry: serde_json::Value = serde_json::from_str(&fs::read_to_string(&files[0]).unwrap()).unwrap();
+        assert_eq!(entry["source"], "Broken");
+        assert_eq!(entry["body"], "not json");
+        assert_eq!(entry["url"], format!("{base}/pools"));
+    }
+
+    #[test]
+    fn capture_writes_one_valid_json_file_per_source() {
+        let dir = temp_dir("fixture-capture");
+        let capture = FixtureCapture { dir: dir.clone(), captured: Mutex::new(HashSet::new()) };
+        capture.record("DexScreener", r#"{"pairs":[{"pairAddress":"0x1"}]}"#);
+        capture.record("GeckoTerminal", "<html>maintenance</html>");
+        capture.record("GeckoTerminal", r#"{"data":[]}"#);
+        // Only the first response per source is kept
+        capture.record("DexScreener", r#"{"pairs":[]}"#);
+
+        let files = json_files(&dir);
+        assert_eq!(files, [fixture_path(&dir, "DexScreener"), fixture_path(&dir, "GeckoTerminal")]);
+        let bodies: Vec<serde_json::Value> = files.iter()
+            .map(|f| serde_json::from_str(&fs::read_to_string(f).unwrap()).unwrap())
+            .collect();
+        assert_eq!(bodies[0]["pairs"][0]["pairAddress"], "0x1");
+        assert_eq!(bodies[1], serde_json::json!({ "data": [] }));
+        let mut captured: Vec<String> = capture.captured.lock().iter().cloned().collect();
+        captured.sort();
+        assert_eq!(captured, ["DexScreener", "GeckoTerminal"]);
+    }
+
+    #[test]
+    fn lenient_entries_keep_the_good_entries() {
+        #[derive(Debug, PartialEq, serde::Deserialize)]
+        struct Entry {
+            address: String,
+            #[serde(default)]
+            price: f64,
+        }
+
+        let entries = vec![
+            serde_json::json!({ "address": "0xgood", "price": 1.5 }),
+            serde_json::json!({ "address": 7 }),
+            serde_json::json!("garbage"),
+            serde_json::json!({ "address": "0xdefaults" }),
+        ];
+        let parsed: Vec<Entry> = lenient_entries("test", entries);
+        assert_eq!(parsed, [
+            Entry { address: "0xgood".to_string(), price: 1.5 },
+            Entry { address: "0xdefaults".to_string(), price: 0.0 },
+        ]);
+    }
+}
@@ -0,0 +1,210 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+use parking_lot::RwLock;
+use reqwest::Client;
+use serde::Deserialize;
+use crate::models::PoolData;
+use super::{http, PoolSource, SourceError};
+
+/// Timeout of each Jupiter price request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+const JUPITER_API: &str = "https://lite-api.jup.ag";
+
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// Well-known mints, checked before the token search
+const KNOWN_MINTS: &[(&str, &str)] = &[
+    ("SOL", "So11111111111111111111111111111111111111112"),
+    ("USDC", USDC_MINT),
+    ("JUP", "JUPyiwrYJFskUPiHa7hkeR8VUtAeFoSYbKedZNsDvCN"),
+    ("BONK", "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263"),
+    ("RAY", "4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R"),
+    ("PYTH", "HZ1JovNiVvGrGNiiYvEozEVgZ58xaU3RKwX8eACQBCt3"),
+    ("WIF", "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm"),
+    ("JTO", "jtojtomepa8beP8AuQc6eXt5FriJwfFMwQx2v2f9mCL"),
+    ("ORCA", "orcaEKTdK7LKz57vaAYr9QeNsVEPfiu6QeMU1kektZE"),
+    ("RENDER", "rndrizKT3MK1iimdxRdWabcF7Zg7AR5T4nud4EkHBof"),
+    ("W", "85VBFQZC9TZkfaptBWjvUw7YbZjy52A6mjtPGjstQAmQ"),
+    ("HNT", "hntyVP6YFm1Hg25TN9WGLqM12b8TQmcknKrdu1oxWux"),
+    ("PENGU", "2zMMhcVQEXDtdE6vsFS7S7D5oUodfJHE8vd1gnBouauv"),
+    ("TRUMP", "6p6xgHyF7AeE6TZkSmFsko444wqoP15icUSqi2jfGiPN"),
+];
+
+/// `/tokens/v2/search` entry
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterToken {
+    /// Mint address
+    id: String,
+    symbol: String,
+    #[serde(default)]
+    is_verified: Option<bool>,
+    #[serde(default)]
+    liquidity: Option<f64>,
+}
+
+/// `/price/v3` value, keyed by mint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JupiterPrice {
+    usd_price: f64,
+}
+
+/// Jupiter USD prices for Solana tokens. Mints come from `KNOWN_MINTS`, then
+/// Jupiter's verified token search; results (misses included) are cached for
+/// the process lifetime, so non-Solana symbols cost one search.
+///
+/// Pools are emitted with chain "solana", which `canonical_chain` keeps as is
+/// ("sol" normalizes to it too), so they group with other sources' Solana pools.
+pub struct JupiterSource {
+    client: Client,
+    /// Jupiter API root
+    base_url: String,
+    /// Uppercased symbol -> mint (None = not on Solana)
+    mints: RwLock<HashMap<String, Option<String>>>,
+}
+
+impl Default for JupiterSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JupiterSource {
+    pub fn new() -> Self {
+        Self::with_client(http::shared_client())
+    }
+
+    /// Source on an injected (shared) client
+    pub fn with_client(client: Client) -> Self {
+        let mints = KNOWN_MINTS.iter()
+            .map(|(symbol, mint)| (symbol.to_string(), Some(mint.to_string())))
+            .collect();
+        Self { client, base_url: JUPITER_API.to_string(), mints: RwLock::new(mints) }
+    }
+
+    /// Point the token search and price calls at another host (proxy, mock server)
+    pub fn set_base_url(&mut self, base_url: &str) {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+    }
+
+    /// Verified token with exactly this symbol and the deepest liquidity
+    fn pick_mint(symbol: &str, tokens: Vec<JupiterToken>) -> Option<String> {
+        tokens.into_iter()
+            .filter(|t| t.is_verified == Some(true) && t.symbol.eq_ignore_ascii_case(symbol))
+            .max_by(|a, b| a.liquidity.unwrap_or(0.0).total_cmp(&b.liquidity.unwrap_or(0.0)))
+            .map(|t| t.id)
+    }
+
+    /// One USDC-quoted pseudo-pool for `mint`; None without a usable price
+    fn parse_price(symbol: &str, mint: String, prices: &HashMap<String, JupiterPrice>) -> Option<PoolData> {
+        let price = prices.get(&mint).map(|p| p.usd_price).filter(|p| *p > 0.0 && p.is_finite())?;
+        Some(PoolData::new(
+            symbol.to_string(),
+            "solana".to_string(),
+            "jupiter".to_string(),
+            format!("jupiter:{}", mint),
+            format!("{}/USDC", symbol),
+            price,
+            0.0, 0.0,
+            "jupiter".to_string(),
+        ).with_tokens(Some(mint), Some(USDC_MINT.to_string())))
+    }
+
+    async fn resolve_mint(&self, symbol: &str) -> Result<Option<String>, SourceError> {
+        if let Some(cached) = self.mints.read().get(symbol) {
+            return Ok(cached.clone());
+        }
+
+        let url = format!("{}/tokens/v2/search?query={}", self.base_url, symbol);
+        let req = self.client.get(&url)
+            .timeout(REQUEST_TIMEOUT);
+        let tokens: Vec<JupiterToken> = http::fetch_json(self.name(), req).await?;
+
+        let mint = Self::pick_mint(symbol, tokens);
+        self.mints.write().insert(symbol.to_string(), mint.clone());
+        Ok(mint)
+    }
+}
+
+#[async_trait]
+impl PoolSource for JupiterSource {
+    fn name(&self) -> &'static str { "Jupiter" }
+
+    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        let symbol = symbol.to_uppercase();
+        let Some(mint) = self.resolve_mint(&symbol).await? else {
+            return Ok(vec![]);
+        };
+
+        let url = format!("{}/price/v3?ids={}", self.base_url, mint);
+        let req = self.client.get(&url)
+            .timeout(REQUEST_TIMEOUT);
+        let prices: HashMap<String, JupiterPrice> = http::fetch_json(self.name(), req).await?;
+        Ok(Self::parse_price(&symbol, mint, &prices).into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use axum::routing::get;
+    use crate::models::canonical_chain;
+    use crate::test_util::serve;
+
+    const PYTH: &str = "HZ1JovNiVvGrGNiiYvEozEVgZ58xaU3RKwX8eACQBCt3";
+
+    #[test]
+    fn search_picks_the_deepest_verified_exact_symbol() {
+        let tokens: Vec<JupiterToken> = serde_json::from_str(include_str!("../../tests/fixtures/jupiter_search.json")).unwrap();
+        // The deeper unverified clone and PYTHUP are skipped
+        assert_eq!(JupiterSource::pick_mint("pyth", tokens).as_deref(), Some(PYTH));
+        assert_eq!(JupiterSource::pick_mint("PYTH", Vec::new()), None);
+    }
+
+    #[test]
+    fn price_payload_becomes_a_solana_usdc_pool() {
+        let prices: HashMap<String, JupiterPrice> = serde_json::from_str(include_str!("../../tests/fixtures/jupiter_price.json")).unwrap();
+        let pool = JupiterSource::parse_price("PYTH", PYTH.to_string(), &prices).unwrap();
+
+        assert_eq!(pool.chain, "solana");
+        assert_eq!(canonical_chain(&pool.chain), "solana");
+        assert_eq!((pool.dex.as_str(), pool.source.as_str()), ("jupiter", "jupiter"));
+        assert_eq!(pool.pool_address, format!("jupiter:{PYTH}"));
+        assert_eq!(pool.pair, "PYTH/USDC");
+        assert_eq!(pool.price_usd, 0.14123);
+        assert_eq!(pool.base_token.as_deref(), Some(PYTH));
+        assert_eq!(pool.quote_token.as_deref(), Some(USDC_MINT));
+
+        // A mint missing from the payload yields nothing
+        assert!(JupiterSource::parse_price("PYTH", "other".to_string(), &prices).is_none());
+    }
+
+    #[tokio::test]
+    async fn symbols_not_on_solana_are_empty_and_searched_once() {
+        let searches = Arc::new(AtomicUsize::new(0));
+        let counter = searches.clone();
+        let router = axum::Router::new()
+            .route("/tokens/v2/search", get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { axum::Json(serde_json::json!([])) }
+            }))
+            .route("/price/v3", get(|| async {
+                axum::Json(serde_json::json!({ "So11111111111111111111111111111111111111112": { "usdPrice": 151.2 } }))
+            }));
+        let mut source = JupiterSource::with_client(http::shared_client());
+        source.set_base_url(&serve(router).await);
+
+        assert!(source.fetch_pools("XRP").await.unwrap().is_empty());
+        assert!(source.fetch_pools("xrp").await.unwrap().is_empty());
+        assert_eq!(searches.load(Ordering::SeqCst), 1);
+
+        // Known mints skip the search
+        let sol = source.fetch_pools("SOL").await.unwrap();
+        assert_eq!((sol.len(), sol[0].price_usd), (1, 151.2));
+        assert_eq!(searches.load(Ordering::SeqCst), 1);
+    }
+}
@@ -1,223 +1,93 @@
 use async_trait::async_trait;
 use reqwest::Client;
-use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use parking_lot::RwLock;
 use crate::models::PoolData;
-use super::{PoolSource, SourceError};
-
-/// Shared token address cache (symbol -> chain_id -> address)
-pub type TokenCache = Arc<RwLock<HashMap<String, HashMap<u32, String>>>>;
-
-/// Get USDC address for chain (16 chains supported)
-fn get_usdc_address(chain_id: u32) -> &'static str {
-    match chain_id {
-        1 => "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",       // Ethereum
-        8453 => "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913",     // Base
-        56 => "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d",       // BSC
-        137 => "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174",      // Polygon
-        42161 => "0xaf88d065e77c8cC2239327C5EDb3A432268e5831",    // Arbitrum
-        43114 => "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E",    // Avalanche
-        10 => "0x7F5c764cBc14f9669B88837ca1490cCa17c31607",       // Optimism
-        81457 => "0x4300000000000000000000000000000000000003",    // Blast
-        59144 => "0x176211869cA2b568f2A7D4EE941E073a821EE1ff",    // Linea
-        5000 => "0x09Bc4E0D10E52467089689024a2c50bf19f29E13",     // Mantle
-        34443 => "0xd988097fb8612cc24eeC14542bC03424c656005f",    // Mode
-        534352 => "0x06eFdBFf2a14a7c8E15944D1F4A48F9F95F663A4",   // Scroll
-        130 => "0x078D782b760474a361dDA0AF3839290b0EF57AD6",      // Unichain
-        _ => "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
-    }
-}
+use super::{http, quote, PoolSource, SourceError};
+use super::quote::QuoteToken;
 
-/// Load consolidated token data from JSON file
-fn load_token_data() -> HashMap<String, HashMap<u32, String>> {
-    let json_str = include_str!("../../matcha_tokens_consolidated.json");
-    
-    // Parse JSON: {"SYMBOL": {"chainId": "address", ...}, ...}
-    let raw: HashMap<String, HashMap<String, String>> = 
-        serde_json::from_str(json_str).unwrap_or_default();
-    
-    // Convert string chain IDs to u32
-    raw.into_iter()
-        .map(|(symbol, chains)| {
-            let converted: HashMap<u32, String> = chains.into_iter()
-                .filter_map(|(chain_str, addr)| {
-                    chain_str.parse::<u32>().ok().map(|id| (id, addr))
-                })
-                .collect();
-            (symbol, converted)
-        })
-        .collect()
-}
+/// Timeout of each OpenOcean/ParaSwap quote
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
-/// Create a new shared token cache pre-loaded with Matcha data
-pub fn new_token_cache() -> TokenCache {
-    Arc::new(RwLock::new(load_token_data()))
-}
+/// Assumed when a token's decimals are unknown
+const DEFAULT_DECIMALS: u8 = 18;
 
-/// Matcha Token Resolver - uses pre-loaded data from consolidated JSON
-pub struct MatchaTokenResolver {
-    cache: TokenCache,
+/// Token contract on one chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    pub address: String,
+    /// 18 when the source data doesn't say
+    pub decimals: u8,
 }
 
-impl MatchaTokenResolver {
-    pub fn new(cache: TokenCache) -> Self {
-        Self { cache }
-    }
-
-    pub fn resolve(&self, symbol: &str) -> HashMap<u32, String> {
-        let cache = self.cache.read();
-        cache.get(&symbol.to_uppercase())
-            .cloned()
-            .unwrap_or_default()
+impl TokenInfo {
+    /// Raw amount of exactly one token, for quote requests
+    pub fn one_token(&self) -> String {
+        10u128.pow(self.decimals.min(38) as u32).to_string()
     }
 }
 
-#[async_trait]
-impl PoolSource for MatchaTokenResolver {
-    fn name(&self) -> &'static str { "Matcha" }
+/// Shared token cache (symbol -> chain_id -> token)
+pub type TokenCache = Arc<RwLock<HashMap<String, HashMap<u32, TokenInfo>>>>;
 
-    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        let addresses = self.resolve(symbol);
-        
-        let pools: Vec<PoolData> = addresses.iter()
-            .map(|(chain_id, address)| {
-                let chain_name = match chain_id {
-                    1 => "ethereum",
-                    8453 => "base",
-                    56 => "bsc",
-                    137 => "polygon",
-                    42161 => "arbitrum",
-                    43114 => "avalanche",
-                    10 => "optimism",
-                    81457 => "blast",
-                    59144 => "linea",
-                    5000 => "mantle",
-                    34443 => "mode",
-                    534352 => "scroll",
-                    130 => "unichain",
-                    _ => "other",
-                };
-                
-                PoolData::new(
-                    symbol.to_string(),
-                    chain_name.to_string(),
-                    "matcha-data".to_string(),
-                    address.clone(),
-                    format!("{} token", symbol),
-                    0.0, 0.0, 0.0,
-                    "matcha".to_string(),
-                )
-            })
-            .collect();
-        
-        Ok(pools)
-    }
+/// Cached contract of `symbol` on `chain_id` (symbol is case-insensitive)
+pub fn lookup_token(cache: &TokenCache, symbol: &str, chain_id: u32) -> Option<TokenInfo> {
+    cache.read()
+        .get(&symbol.to_uppercase())
+        .and_then(|chains| chains.get(&chain_id))
+        .cloned()
 }
 
-/// KyberSwap with static token data
-pub struct KyberSwapDirectSource {
-    client: Client,
-    cache: TokenCache,
-}
-
-impl KyberSwapDirectSource {
-    pub fn new(cache: TokenCache) -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .unwrap(),
-            cache,
+/// Record decimals reported for a cached token (same address only; unknown
+/// tokens are not added)
+pub fn record_decimals(cache: &TokenCache, symbol: &str, chain_id: u32, address: &str, decimals: u8) {
+    let mut cache = cache.write();
+    if let Some(token) = cache.get_mut(&symbol.to_uppercase()).and_then(|chains| chains.get_mut(&chain_id)) {
+        if token.address.eq_ignore_ascii_case(address) {
+            token.decimals = decimals;
         }
     }
-
-    fn get_token_address(&self, symbol: &str, chain_id: u32) -> Option<String> {
-        let cache = self.cache.read();
-        cache.get(&symbol.to_uppercase())
-            .and_then(|chains| chains.get(&chain_id))
-            .cloned()
-    }
 }
 
-#[async_trait]
-impl PoolSource for KyberSwapDirectSource {
-    fn name(&self) -> &'static str { "KyberSwap" }
+/// Load consolidated token data from JSON file
+fn load_token_data() -> HashMap<String, HashMap<u32, TokenInfo>> {
+    let json_str = include_str!("../../matcha_tokens_consolidated.json");
+    parse_token_data(json_str).expect("bundled matcha_tokens_consolidated.json parses")
+}
 
-    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
-        let mut pools = Vec::new();
-        
-        for (chain_name, chain_id) in [
-            ("ethereum", 1u32), ("bsc", 56), ("polygon", 137), ("arbitrum", 42161),
-            ("avalanche", 43114), ("optimism", 10), ("base", 8453), ("linea", 59144), ("scroll", 534352)
-        ] {
-            let token_addr = match self.get_token_address(symbol, chain_id) {
-                Some(addr) => addr,
-                None => continue,
-            };
-            
-            let usdc = get_usdc_address(chain_id);
-            let url = format!(
-                "https://aggregator-api.kyberswap.com/{}/api/v1/routes?tokenIn={}&tokenOut={}&amountIn=1000000000000000000",
-                chain_name, token_addr, usdc
-            );
+/// `{"SYMBOL": {"chainId": "address" | {"address": ..., "decimals": 8}, ...}, ...}`;
+/// the old string-only entries load with the default decimals
+fn parse_token_data(json_str: &str) -> Result<HashMap<String, HashMap<u32, TokenInfo>>, serde_json::Error> {
+    let raw: HashMap<String, HashMap<String, serde_json::Value>> = serde_json::from_str(json_str)?;
+    
+    // Convert string chain IDs to u32
+    Ok(raw.into_iter()
+        .map(|(symbol, chains)| {
+            let converted: HashMap<u32, TokenInfo> = chains.into_iter()
+                .filter_map(|(chain_str, entry)| {
+                    let id = chain_str.parse::<u32>().ok()?;
+                    let token = match entry {
+                        serde_json::Value::String(address) => TokenInfo { address, decimals: DEFAULT_DECIMALS },
+                        entry => TokenInfo {
+                            address: entry["address"].as_str()?.to_string(),
+                            decimals: entry["decimals"].as_u64()
+                                .and_then(|d| u8::try_from(d).ok())
+                                .unwrap_or(DEFAULT_DECIMALS),
+                        },
+                    };
+                    Some((id, token))
+                })
+                .collect();
+            (symbol, converted)
+        })
+        .collect())
+}
 
-            if let Ok(resp) = self.client.get(&url).send().await {
-                if resp.status().is_success() {
-                    if let Ok(data) = resp.json::<serde_json::Value>().await {
-                        if let Some(route_summary) = data["data"]["routeSummary"].as_object() {
-                            let amount_out = route_summary.get("amountOut")
-                                .and_then(|v| v.as_str())
-                                .and_then(|s| s.parse::<f64>().ok())
-                                .map(|v| v / 1e6)
-                                .unwrap_or(0.0);
-                            
-                            if let Some(route) = data["data"]["routeSummary"]["route"].as_array() {
-                                for (i, leg) in route.iter().enumerate() {
-                                    if let Some(swaps) = leg.as_array() {
-                                        for swap in swaps {
-                                            let pool = swap["pool"].as_str().unwrap_or("");
-                                            let exchange = swap["exchange"].as_str().unwrap_or("kyberswap");
-                                            
-                                            if !pool.is_empty() {
-                                                pools.push(PoolData::new(
-                                                    symbol.to_string(),
-                                                    chain_name.to_string(),
-                                                    exchange.to_string(),
-                                                    pool.to_string(),
-                                                    format!("{}/USDC (hop {})", symbol, i + 1),
-                                                    amount_out,
-                                                    0.0, 0.0,
-                                                    "kyberswap".to_string(),
-                                                ));
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                            
-                            if pools.is_empty() && amount_out > 0.0 {
-                                pools.push(PoolData::new(
-                                    symbol.to_string(),
-                                    chain_name.to_string(),
-                                    "kyberswap".to_string(),
-                                    format!("kyber:{}:{}", chain_id, symbol),
-                                    format!("{}/USDC", symbol),
-                                    amount_out,
-                                    0.0, 0.0,
-                                    "kyberswap".to_string(),
-                                ));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        Ok(pools)
-    }
+/// Create a new shared token cache pre-loaded with Matcha data
+pub fn new_token_cache() -> TokenCache {
+    Arc::new(RwLock::new(load_token_data()))
 }
 
 /// OpenOcean with static token data
@@ -227,21 +97,13 @@ pub struct OpenOceanDirectSource {
 }
 
 impl OpenOceanDirectSource {
-    pub fn new(cache: TokenCache) -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .unwrap(),
-            cache,
-        }
+    /// Source on an injected (shared) client
+    pub fn with_client(client: Client, cache: TokenCache) -> Self {
+        Self { client, cache }
     }
 
-    fn get_token_address(&self, symbol: &str, chain_id: u32) -> Option<String> {
-        let cache = self.cache.read();
-        cache.get(&symbol.to_uppercase())
-            .and_then(|chains| chains.get(&chain_id))
-            .cloned()
+    fn get_token(&self, symbol: &str, chain_id: u32) -> Option<TokenInfo> {
+        lookup_token(&self.cache, symbol, chain_id)
     }
 }
 
@@ -264,40 +126,21 @@ impl PoolSource for OpenOceanDirectSource {
             ("scroll", 534352, "scroll"),
             ("mantle", 5000, "mantle"),
         ] {
-            let token_addr = match self.get_token_address(symbol, chain_id) {
-                Some(addr) => addr,
-                None => continue,
+            let Some(token) = self.get_token(symbol, chain_id) else {
+                continue;
+            };
+            let Some(quote) = quote::quote_token(chain_id) else {
+                continue;
             };
-            
-            let usdc = get_usdc_address(chain_id);
             let url = format!(
-                "https://open-api.openocean.finance/v3/{}/quote?inTokenAddress={}&outTokenAddress={}&amount=1000000000000000000&gasPrice=5",
-                chain, token_addr, usdc
+                "https://open-api.openocean.finance/v3/{}/quote?inTokenAddress={}&outTokenAddress={}&amount={}&gasPrice=5",
+                chain, token.address, quote.address, token.one_token()
             );
 
-            if let Ok(resp) = self.client.get(&url).send().await {
-                if resp.status().is_success() {
-                    if let Ok(data) = resp.json::<serde_json::Value>().await {
-                        if let Some(out_amount) = data["data"]["outAmount"].as_str() {
-                            if let Ok(price) = out_amount.parse::<f64>() {
-                                let price = price / 1e6;
-                                
-                                if price > 0.0 {
-                                    pools.push(PoolData::new(
-                                        symbol.to_string(),
-                                        chain_name.to_string(),
-                                        "openocean".to_string(),
-                                        format!("openocean:{}:{}", chain_id, symbol),
-                                        format!("{}/USDC", symbol),
-                                        price,
-                                        0.0, 0.0,
-                                        "openocean".to_string(),
-                                    ));
-                                }
-                            }
-                        }
-                    }
-                }
+            let req = self.client.get(&url)
+                .timeout(REQUEST_TIMEOUT);
+            if let Ok(data) = http::fetch_json::<serde_json::Value>(self.name(), req).await {
+                pools.extend(openocean_pool(symbol, chain_name, chain_id, &token, &quote, &data));
             }
         }
         
@@ -305,6 +148,28 @@ impl PoolSource for OpenOceanDirectSource {
     }
 }
 
+/// The aggregated pool of one chain's quote for exactly one token in
+fn openocean_pool(
+    symbol: &str,
+    chain_name: &str,
+    chain_id: u32,
+    token: &TokenInfo,
+    quote: &QuoteToken,
+    data: &serde_json::Value,
+) -> Option<PoolData> {
+    let price = quote.to_units(data["data"]["outAmount"].as_str()?.parse::<f64>().ok()?);
+    (price > 0.0).then(|| PoolData::new(
+        symbol.to_string(),
+        chain_name.to_string(),
+        "openocean".to_string(),
+        format!("openocean:{}:{}", chain_id, symbol),
+        format!("{}/{}", symbol, quote.symbol),
+        price,
+        0.0, 0.0,
+        "openocean".to_string(),
+    ).with_tokens(Some(token.address.clone()), Some(quote.address.to_string())))
+}
+
 /// ParaSwap with static token data
 pub struct ParaSwapDirectSource {
     client: Client,
@@ -312,21 +177,13 @@ pub struct ParaSwapDirectSource {
 }
 
 impl ParaSwapDirectSource {
-    pub fn new(cache: TokenCache) -> Self {
-        Self {
-            client: Client::builder()
-                .timeout(Duration::from_secs(10))
-                .build()
-                .unwrap(),
-            cache,
-        }
+    /// Source on an injected (shared) client
+    pub fn with_client(client: Client, cache: TokenCache) -> Self {
+        Self { client, cache }
     }
 
-    fn get_token_address(&self, symbol: &str, chain_id: u32) -> Option<String> {
-        let cache = self.cache.read();
-        cache.get(&symbol.to_uppercase())
-            .and_then(|chains| chains.get(&chain_id))
-            .cloned()
+    fn get_token(&self, symbol: &str, chain_id: u32) -> Option<TokenInfo> {
+        lookup_token(&self.cache, symbol, chain_id)
     }
 }
 
@@ -341,73 +198,193 @@ impl PoolSource for ParaSwapDirectSource {
             (1u32, "ethereum"), (56, "bsc"), (137, "polygon"), (42161, "arbitrum"),
             (43114, "avalanche"), (10, "optimism"), (8453, "base")
         ] {
-            let token_addr = match self.get_token_address(symbol, chain_id) {
-                Some(addr) => addr,
-                None => continue,
+            let Some(token) = self.get_token(symbol, chain_id) else {
+                continue;
+            };
+            let Some(quote) = quote::quote_token(chain_id) else {
+                continue;
             };
-            
-            let usdc = get_usdc_address(chain_id);
             let url = format!(
-                "https://apiv5.paraswap.io/prices?srcToken={}&destToken={}&amount=1000000000000000000&srcDecimals=18&destDecimals=6&network={}",
-                token_addr, usdc, chain_id
+                "https://apiv5.paraswap.io/prices?srcToken={}&destToken={}&amount={}&srcDecimals={}&destDecimals={}&network={}",
+                token.address, quote.address, token.one_token(), token.decimals, quote.decimals, chain_id
             );
 
-            if let Ok(resp) = self.client.get(&url).send().await {
-                if resp.status().is_success() {
-                    if let Ok(data) = resp.json::<serde_json::Value>().await {
-                        if let Some(dest_amount) = data["priceRoute"]["destAmount"].as_str() {
-                            if let Ok(price) = dest_amount.parse::<f64>() {
-                                let price = price / 1e6;
-                                
-                                if let Some(best_route) = data["priceRoute"]["bestRoute"].as_array() {
-                                    for step in best_route {
-                                        if let Some(swaps) = step["swaps"].as_array() {
-                                            for swap in swaps {
-                                                if let Some(exchanges) = swap["swapExchanges"].as_array() {
-                                                    for exchange in exchanges {
-                                                        let dex = exchange["exchange"].as_str().unwrap_or("paraswap");
-                                                        if let Some(addrs) = exchange["poolAddresses"].as_array() {
-                                                            for addr in addrs {
-                                                                if let Some(a) = addr.as_str() {
-                                                                    pools.push(PoolData::new(
-                                                                        symbol.to_string(),
-                                                                        chain_name.to_string(),
-                                                                        dex.to_string(),
-                                                                        a.to_string(),
-                                                                        format!("{}/USDC", symbol),
-                                                                        price,
-                                                                        0.0, 0.0,
-                                                                        "paraswap".to_string(),
-                                                                    ));
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                                
-                                if pools.is_empty() && price > 0.0 {
-                                    pools.push(PoolData::new(
-                                        symbol.to_string(),
-                                        chain_name.to_string(),
-                                        "paraswap".to_string(),
-                                        format!("paraswap:{}:{}", chain_id, symbol),
-                                        format!("{}/USDC", symbol),
-                                        price,
-                                        0.0, 0.0,
-                                        "paraswap".to_string(),
-                                    ));
-                                }
+            let req = self.client.get(&url)
+                .timeout(REQUEST_TIMEOUT);
+            if let Ok(data) = http::fetch_json::<serde_json::Value>(self.name(), req).await {
+                pools.extend(paraswap_pools(symbol, chain_name, chain_id, &token, &quote, &data));
+            }
+        }
+        
+        Ok(pools)
+    }
+}
+
+/// Pools of one chain's best route for exactly one token in; a single
+/// aggregated pool when the route lists none
+fn paraswap_pools(
+    symbol: &str,
+    chain_name: &str,
+    chain_id: u32,
+    token: &TokenInfo,
+    quote: &QuoteToken,
+    data: &serde_json::Value,
+) -> Vec<PoolData> {
+    let mut pools = Vec::new();
+    let Some(price) = data["priceRoute"]["destAmount"].as_str().and_then(|s| s.parse::<f64>().ok()) else {
+        return pools;
+    };
+    let price = quote.to_units(price);
+    let pool = |dex: &str, address: String| PoolData::new(
+        symbol.to_string(),
+        chain_name.to_string(),
+        dex.to_string(),
+        address,
+        format!("{}/{}", symbol, quote.symbol),
+        price,
+        0.0, 0.0,
+        "paraswap".to_string(),
+    ).with_tokens(Some(token.address.clone()), Some(quote.address.to_string()));
+    
+    if let Some(best_route) = data["priceRoute"]["bestRoute"].as_array() {
+        for step in best_route {
+            if let Some(swaps) = step["swaps"].as_array() {
+                for swap in swaps {
+                    if let Some(exchanges) = swap["swapExchanges"].as_array() {
+                        for exchange in exchanges {
+                            let dex = exchange["exchange"].as_str().unwrap_or("paraswap");
+                            if let Some(addrs) = exchange["poolAddresses"].as_array() {
+                                pools.extend(addrs.iter()
+                                    .filter_map(|addr| addr.as_str())
+                                    .map(|a| pool(dex, a.to_string())));
                             }
                         }
                     }
                 }
             }
         }
-        
-        Ok(pools)
+    }
+    
+    if pools.is_empty() && price > 0.0 {
+        pools.push(pool("paraswap", format!("paraswap:{}:{}", chain_id, symbol)));
+    }
+    pools
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(address: &str, decimals: u8) -> TokenInfo {
+        TokenInfo { address: address.to_string(), decimals }
+    }
+
+    #[test]
+    fn one_token_follows_the_token_decimals() {
+        assert_eq!(token("0xwbtc", 8).one_token(), "100000000");
+        assert_eq!(token("0xusdt", 6).one_token(), "1000000");
+        assert_eq!(token("0xweth", 18).one_token(), "1000000000000000000");
+    }
+
+    #[test]
+    fn an_8_decimal_token_is_priced_per_whole_token() {
+        let wbtc = token("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599", 8);
+        let usdc = quote::QuotePreference::default().quote_token(1).unwrap();
+        // amount = 1e8 (1 WBTC) -> 60,000 USDC at 6 decimals
+        let data = serde_json::json!({ "priceRoute": {
+            "destAmount": "60000000000",
+            "bestRoute": [{ "swaps": [{ "swapExchanges": [
+                { "exchange": "UniswapV3", "poolAddresses": ["0xpool1"] },
+                { "exchange": "Curve", "poolAddresses": ["0xpool2"] },
+            ] }] }],
+        } });
+        let pools = paraswap_pools("WBTC", "ethereum", 1, &wbtc, &usdc, &data);
+        let got: Vec<(&str, f64)> = pools.iter().map(|p| (p.pool_address.as_str(), p.price_usd)).collect();
+        assert_eq!(got, [("0xpool1", 60_000.0), ("0xpool2", 60_000.0)]);
+        assert_eq!(pools[0].base_token.as_deref(), Some(wbtc.address.as_str()));
+
+        // Same on BSC, where the 18-decimal quote drives the divisor
+        let bsc_usdc = quote::QuotePreference::default().quote_token(56).unwrap();
+        let data = serde_json::json!({ "priceRoute": { "destAmount": "60000000000000000000000", "bestRoute": [] } });
+        let pools = paraswap_pools("WBTC", "bsc", 56, &wbtc, &bsc_usdc, &data);
+        assert_eq!(pools.len(), 1);
+        assert_eq!((pools[0].pool_address.as_str(), pools[0].price_usd), ("paraswap:56:WBTC", 60_000.0));
+    }
+
+    #[test]
+    fn a_6_decimal_token_is_priced_per_whole_token() {
+        let usdt = token("0xdac17f958d2ee523a2206206994597c13d831ec7", 6);
+        let usdc = quote::QuotePreference::default().quote_token(1).unwrap();
+        // amountIn = 1e6 (1 USDT) -> 0.9998 USDC
+        let data = serde_json::json!({ "data": { "outAmount": "999800" } });
+        let pool = openocean_pool("USDT", "ethereum", 1, &usdt, &usdc, &data).unwrap();
+        assert_eq!((pool.pool_address.as_str(), pool.price_usd), ("openocean:1:USDT", 0.9998));
+
+        let data = serde_json::json!({ "priceRoute": {
+            "destAmount": "999800",
+            "bestRoute": [{ "swaps": [{ "swapExchanges": [
+                { "exchange": "UniswapV3", "poolAddresses": ["0xpool"] },
+            ] }] }],
+        } });
+        let pools = paraswap_pools("USDT", "ethereum", 1, &usdt, &usdc, &data);
+        assert_eq!(pools.len(), 1);
+        assert_eq!((pools[0].dex.as_str(), pools[0].price_usd), ("UniswapV3", 0.9998));
+
+        assert!(openocean_pool("USDT", "ethereum", 1, &usdt, &usdc, &serde_json::json!({ "data": { "outAmount": "0" } })).is_none());
+    }
+
+    #[test]
+    fn each_chain_without_route_pools_gets_its_own_aggregated_pool() {
+        let wbtc = token("0xwbtc", 8);
+        let usdc = quote::QuotePreference::default().quote_token(1).unwrap();
+        let routed = serde_json::json!({ "priceRoute": {
+            "destAmount": "60000000000",
+            "bestRoute": [{ "swaps": [{ "swapExchanges": [
+                { "exchange": "UniswapV3", "poolAddresses": ["0xpool"] },
+            ] }] }],
+        } });
+        let bare = serde_json::json!({ "priceRoute": { "destAmount": "60100000000", "bestRoute": [] } });
+
+        // A chain that listed route pools doesn't suppress the next chain's fallback
+        let mut pools = paraswap_pools("WBTC", "ethereum", 1, &wbtc, &usdc, &routed);
+        pools.extend(paraswap_pools("WBTC", "arbitrum", 42161, &wbtc, &usdc, &bare));
+        let got: Vec<(&str, &str, f64)> = pools.iter()
+            .map(|p| (p.chain.as_str(), p.pool_address.as_str(), p.price_usd))
+            .collect();
+        assert_eq!(got, [("ethereum", "0xpool", 60_000.0), ("arbitrum", "paraswap:42161:WBTC", 60_100.0)]);
+
+        assert!(paraswap_pools("WBTC", "ethereum", 1, &wbtc, &usdc, &serde_json::json!({ "error": "x" })).is_empty());
+    }
+
+    #[test]
+    fn token_data_accepts_both_entry_formats() {
+        let tokens = parse_token_data(r#"{
+            "WBTC": {
+                "1": { "address": "0x2260fac5e5542a773aa44fbcfedf7c193bc2c599", "decimals": 8 },
+                "56": "0x7130d2a12b9bcbfae4f2634d864a1ee1ce3ead9c",
+                "137": { "address": "0x1bfd67037b42cf73acf2047067bd4f2c47d9bfd6" },
+                "not-a-chain": "0xignored",
+                "10": { "decimals": 8 },
+                "42161": { "address": "0x2f2a2543b76a4166549f7aab2e75bef0aefc5b0f", "decimals": 300 }
+            },
+            "USDT": { "1": { "address": "0xdac17f958d2ee523a2206206994597c13d831ec7", "decimals": 6 } }
+        }"#).unwrap();
+
+        let wbtc = &tokens["WBTC"];
+        assert_eq!(wbtc[&1], token("0x2260fac5e5542a773aa44fbcfedf7c193bc2c599", 8));
+        // Old string-only and decimals-less entries get the default
+        assert_eq!(wbtc[&56], token("0x7130d2a12b9bcbfae4f2634d864a1ee1ce3ead9c", DEFAULT_DECIMALS));
+        assert_eq!(wbtc[&137].decimals, DEFAULT_DECIMALS);
+        assert_eq!(wbtc[&42161].decimals, DEFAULT_DECIMALS, "out-of-range decimals");
+        // Bad chain ids and entries without an address are dropped
+        assert_eq!(wbtc.len(), 4);
+        assert_eq!(tokens["USDT"][&1].decimals, 6);
+    }
+
+    #[test]
+    fn malformed_token_data_is_an_error() {
+        assert!(parse_token_data("{ not json").is_err());
+        assert!(parse_token_data(r#"{ "WBTC": ["0xwbtc"] }"#).is_err());
+        assert!(!load_token_data().is_empty(), "the bundled file parses");
     }
 }
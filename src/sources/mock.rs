@@ -0,0 +1,123 @@
+//! Scripted `PoolSource` / `CexSource` for tests: fixed pools and prices, no network.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use async_trait::async_trait;
+use crate::models::PoolData;
+use super::{CexSource, PoolSource, SourceError};
+use super::upbit::CexPrice;
+
+pub struct MockSource {
+    name: &'static str,
+    /// Uppercased symbol -> pools returned for it (unknown symbols return none)
+    pools: HashMap<String, Vec<PoolData>>,
+    /// Returned from every call instead of pools
+    error: Option<SourceError>,
+    /// Sleep before answering
+    delay: Duration,
+    /// Never answer (for timeouts)
+    hang: bool,
+    /// `fetch_pools` calls so far
+    calls: AtomicUsize,
+}
+
+impl MockSource {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            pools: HashMap::new(),
+            error: None,
+            delay: Duration::ZERO,
+            hang: false,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    /// Fail every call with `error`
+    pub fn failing(name: &'static str, error: SourceError) -> Self {
+        Self { error: Some(error), ..Self::new(name) }
+    }
+
+    /// Never answer any call; only the caller's timeout ends it
+    pub fn hanging(name: &'static str) -> Self {
+        Self { hang: true, ..Self::new(name) }
+    }
+
+    /// `fetch_pools` calls made so far (answered or not)
+    pub fn calls(&self) -> usize {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Return `pools` for `symbol`; each pool's `source` is set to this source's name
+    pub fn with_pools(mut self, symbol: &str, pools: Vec<PoolData>) -> Self {
+        let pools = pools.into_iter()
+            .map(|pool| PoolData { source: self.name.to_string(), ..pool })
+            .collect();
+        self.pools.insert(symbol.to_uppercase(), pools);
+        self
+    }
+}
+
+#[async_trait]
+impl PoolSource for MockSource {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        if self.hang {
+            std::future::pending::<()>().await;
+        }
+        if !self.delay.is_zero() {
+            tokio::time::sleep(self.delay).await;
+        }
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+        Ok(self.pools.get(&symbol.to_uppercase()).cloned().unwrap_or_default())
+    }
+}
+
+/// Exchange with a fixed USD price list
+pub struct MockCex {
+    name: &'static str,
+    prices: Vec<CexPrice>,
+}
+
+impl MockCex {
+    /// `prices`: (symbol, USD price)
+    pub fn new(name: &'static str, prices: &[(&str, f64)]) -> Self {
+        let timestamp = chrono::Utc::now().timestamp();
+        let prices = prices.iter()
+            .map(|(symbol, price_usd)| CexPrice {
+                symbol: symbol.to_string(),
+                price_krw: 0.0,
+                price_usd: *price_usd,
+                timestamp,
+            })
+            .collect();
+        Self { name, prices }
+    }
+}
+
+#[async_trait]
+impl CexSource for MockCex {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<String>, SourceError> {
+        Ok(self.prices.iter().map(|p| p.symbol.clone()).collect())
+    }
+
+    fn get_all_prices(&self) -> Vec<CexPrice> {
+        self.prices.clone()
+    }
+}
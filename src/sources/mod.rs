@@ -1,11 +1,23 @@
 pub mod gecko;
 pub mod upbit;
+pub mod binance;
 pub mod dexguru;
 pub mod aggregators;
 pub mod meta_agg;
+pub mod uniswap_v3;
+pub mod jupiter;
+pub mod http;
+pub mod ratelimit;
+pub mod quote;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
 
+use std::future::Future;
+use std::sync::OnceLock;
+use std::time::Duration;
 use async_trait::async_trait;
 use crate::models::PoolData;
+use upbit::CexPrice;
 
 #[async_trait]
 pub trait PoolSource: Send + Sync {
@@ -13,11 +25,24 @@ pub trait PoolSource: Send + Sync {
     async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError>;
 }
 
-#[derive(Debug)]
+/// Centralized exchange providing spot prices for the DEX-CEX path
+#[async_trait]
+pub trait CexSource: Send + Sync {
+    /// Exchange name shown in alerts and trade guidance ("Upbit")
+    fn name(&self) -> &'static str;
+    /// Symbols listed on the exchange
+    async fn fetch_symbols(&self) -> Result<Vec<String>, SourceError>;
+    /// Latest known prices (USD-converted)
+    fn get_all_prices(&self) -> Vec<CexPrice>;
+}
+
+#[derive(Debug, Clone)]
 pub enum SourceError {
     Network(String),
     Parse(String),
-    RateLimit,
+    /// 429, with the server's `Retry-After` delay when given
+    RateLimit(Option<Duration>),
+    Http(u16),
     #[allow(dead_code)]
     NotFound,
 }
@@ -27,8 +52,146 @@ impl std::fmt::Display for SourceError {
         match self {
             SourceError::Network(e) => write!(f, "Network error: {}", e),
             SourceError::Parse(e) => write!(f, "Parse error: {}", e),
-            SourceError::RateLimit => write!(f, "Rate limited"),
+            SourceError::RateLimit(_) => write!(f, "Rate limited"),
+            SourceError::Http(status) => write!(f, "HTTP {}", status),
             SourceError::NotFound => write!(f, "Not found"),
         }
     }
 }
+
+/// Attempts and delays for transient source errors
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+        }
+    }
+}
+
+static RETRY_POLICY: OnceLock<RetryPolicy> = OnceLock::new();
+
+/// Set the process-wide retry policy (first call wins)
+pub fn set_retry_policy(policy: RetryPolicy) {
+    let _ = RETRY_POLICY.set(policy);
+}
+
+pub fn retry_policy() -> RetryPolicy {
+    RETRY_POLICY.get().copied().unwrap_or_default()
+}
+
+/// Run `op`, retrying `Network`/`RateLimit` errors with exponential backoff
+/// (a rate limit's `Retry-After` is honored, capped at `max_delay`). Other errors return immediately.
+pub async fn retry_with_backoff<T, F, Fut>(policy: RetryPolicy, mut op: F) -> Result<T, SourceError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SourceError>>,
+{
+    let mut delay = policy.base_delay;
+    let mut attempt = 1;
+    loop {
+        let wait = match op().await {
+            Err(SourceError::Network(_)) if attempt < policy.max_attempts => delay,
+            Err(SourceError::RateLimit(retry_after)) if attempt < policy.max_attempts => {
+                retry_after.unwrap_or(delay)
+            }
+            result => return result,
+        };
+        tokio::time::sleep(wait.min(policy.max_delay)).await;
+        delay = (delay * 2).min(policy.max_delay);
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(1),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn recovers_after_two_transient_failures() {
+        let calls = AtomicU32::new(0);
+        let result = retry_with_backoff(policy(3), || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(SourceError::Network("connection reset".into())),
+                _ => Ok(42),
+            }
+        })
+        .await;
+
+        assert!(matches!(result, Ok(42)));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_max_attempts() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_with_backoff(policy(3), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(SourceError::Network("down".into()))
+        })
+        .await;
+
+        assert!(matches!(result, Err(SourceError::Network(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn parse_errors_are_not_retried() {
+        let calls = AtomicU32::new(0);
+        let result: Result<(), _> = retry_with_backoff(policy(3), || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(SourceError::Parse("bad json".into()))
+        })
+        .await;
+
+        assert!(matches!(result, Err(SourceError::Parse(_))));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limit_waits_for_retry_after() {
+        let calls = AtomicU32::new(0);
+        let start = tokio::time::Instant::now();
+        let result = retry_with_backoff(policy(2), || async {
+            match calls.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(SourceError::RateLimit(Some(Duration::from_millis(700)))),
+                _ => Ok(()),
+            }
+        })
+        .await;
+
+        assert!(result.is_ok());
+        // Retry-After (700ms) wins over the 100ms base delay
+        assert!(start.elapsed() >= Duration::from_millis(700));
+        assert!(start.elapsed() < Duration::from_millis(800));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backoff_doubles_and_is_capped() {
+        let start = tokio::time::Instant::now();
+        let _: Result<(), _> = retry_with_backoff(policy(6), || async {
+            Err(SourceError::Network("down".into()))
+        })
+        .await;
+
+        // 100 + 200 + 400 + 800, then 1600ms capped at max_delay (1s)
+        assert_eq!(start.elapsed().as_millis(), 2500);
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use serde::Deserialize;
+use crate::models::{canonical_chain, chain_for_id};
+
+/// Stablecoin the aggregator quotes are priced against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum QuoteAsset {
+    #[default]
+    Usdc,
+    Usdt,
+}
+
+/// Quote token on one chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuoteToken {
+    pub symbol: &'static str,
+    pub address: &'static str,
+    pub decimals: u32,
+}
+
+impl QuoteToken {
+    /// Raw on-chain amount -> token units
+    pub fn to_units(&self, raw: f64) -> f64 {
+        raw / 10f64.powi(self.decimals as i32)
+    }
+}
+
+const fn token(symbol: &'static str, address: &'static str, decimals: u32) -> QuoteToken {
+    QuoteToken { symbol, address, decimals }
+}
+
+/// USDC (or the chain's native USD stable where USDC isn't the norm)
+fn usdc(chain_id: u32) -> Option<QuoteToken> {
+    Some(match chain_id {
+        1 => token("USDC", "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48", 6),       // Ethereum
+        8453 => token("USDC", "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", 6),    // Base
+        56 => token("USDC", "0x8AC76a51cc950d9822D68b83fE1Ad97B32Cd580d", 18),     // BSC (Binance-Peg, 18 dec)
+        137 => token("USDC", "0x2791Bca1f2de4661ED88A30C99A7a9449Aa84174", 6),     // Polygon
+        42161 => token("USDC", "0xaf88d065e77c8cC2239327C5EDb3A432268e5831", 6),   // Arbitrum
+        43114 => token("USDC", "0xB97EF9Ef8734C71904D8002F8b6Bc66Dd9c48a6E", 6),   // Avalanche
+        10 => token("USDC", "0x7F5c764cBc14f9669B88837ca1490cCa17c31607", 6),      // Optimism
+        81457 => token("USDB", "0x4300000000000000000000000000000000000003", 18),  // Blast
+        59144 => token("USDC", "0x176211869cA2b568f2A7D4EE941E073a821EE1ff", 6),   // Linea
+        5000 => token("USDC", "0x09Bc4E0D10E52467089689024a2c50bf19f29E13", 6),    // Mantle
+        34443 => token("USDC", "0xd988097fb8612cc24eeC14542bC03424c656005f", 6),   // Mode
+        534352 => token("USDC", "0x06eFdBFf2a14a7c8E15944D1F4A48F9F95F663A4", 6),  // Scroll
+        130 => token("USDC", "0x078D782b760474a361dDA0AF3839290b0EF57AD6", 6),     // Unichain
+        _ => return None,
+    })
+}
+
+fn usdt(chain_id: u32) -> Option<QuoteToken> {
+    Some(match chain_id {
+        1 => token("USDT", "0xdAC17F958D2ee523a2206206994597C13D831ec7", 6),
+        8453 => token("USDT", "0xfde4C96c8593536E31F229EA8f37b2ADa2699bb2", 6),
+        56 => token("USDT", "0x55d398326f99059fF775485246999027B3197955", 18),
+        137 => token("USDT", "0xc2132D05D31c914a87C6611C10748AEb04B58e8F", 6),
+        42161 => token("USDT", "0xFd086bC7CD5C481DCC9C85ebE478A1C0b69FCbb9", 6),
+        43114 => token("USDT", "0x9702230A8Ea53601f5cD2dc00fDBc13d4dF4A8c7", 6),
+        10 => token("USDT", "0x94b008aA00579c1307B0EF2c499aD98a8ce58e58", 6),
+        59144 => token("USDT", "0xA219439258ca9da29E9Cc4cE5596924745e12B93", 6),
+        5000 => token("USDT", "0x201EBa5CC46D216Ce6DC03F6a759e8E766e956aE", 6),
+        534352 => token("USDT", "0xf55BEC9cafDbE8730f096Aa55dad6D22d44099Df", 6),
+        _ => return None,
+    })
+}
+
+/// Preferred quote asset per chain (`[quote]`)
+#[derive(Debug, Clone, Default)]
+pub struct QuotePreference {
+    pub default: QuoteAsset,
+    /// chain id -> asset overriding `default`
+    pub per_chain: HashMap<u32, QuoteAsset>,
+}
+
+impl QuotePreference {
+    /// `per_chain` keys are chain names ("bsc") or ids ("56")
+    pub fn new(default: QuoteAsset, per_chain: &HashMap<String, QuoteAsset>) -> Self {
+        let per_chain = per_chain.iter()
+            .filter_map(|(chain, asset)| {
+                let id = chain_id_for(&canonical_chain(chain));
+                if id.is_none() {
+                    tracing::warn!("⚠️ [quote.per_chain] unknown chain {}, ignored", chain);
+                }
+                id.map(|id| (id, *asset))
+            })
+            .collect();
+        Self { default, per_chain }
+    }
+
+    /// Preferred token on `chain_id`, falling back to the other stablecoin;
+    /// None on chains without a known quote token (no other chain's address is
+    /// ever substituted)
+    pub fn quote_token(&self, chain_id: u32) -> Option<QuoteToken> {
+        let preferred = self.per_chain.get(&chain_id).copied().unwrap_or(self.default);
+        let (first, second) = match preferred {
+            QuoteAsset::Usdc => (usdc(chain_id), usdt(chain_id)),
+            QuoteAsset::Usdt => (usdt(chain_id), usdc(chain_id)),
+        };
+        let token = first.or(second);
+        if token.is_none() {
+            tracing::debug!("quote token unknown for chain {}", chain_id);
+        }
+        token
+    }
+}
+
+/// Chain id of a canonical chain name, among the chains with a quote token
+fn chain_id_for(chain: &str) -> Option<u32> {
+    [1u32, 8453, 56, 137, 42161, 43114, 10, 81457, 59144, 5000, 34443, 534352, 130]
+        .into_iter()
+        .find(|id| chain_for_id(*id as u64) == Some(chain))
+}
+
+static PREFERENCE: OnceLock<QuotePreference> = OnceLock::new();
+
+/// Set the process-wide quote preference; call once, before the first request
+pub fn init(preference: QuotePreference) {
+    let _ = PREFERENCE.set(preference);
+}
+
+/// Quote token for `chain_id` under the process-wide preference (USDC by default)
+pub fn quote_token(chain_id: u32) -> Option<QuoteToken> {
+    PREFERENCE.get_or_init(QuotePreference::default).quote_token(chain_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bsc_quotes_scale_by_18_decimals() {
+        let usdc = QuotePreference::default().quote_token(56).unwrap();
+        assert_eq!((usdc.symbol, usdc.decimals), ("USDC", 18));
+        assert_eq!(usdc.to_units(3000e18), 3000.0);
+
+        let usdt = QuotePreference::new(QuoteAsset::Usdt, &HashMap::new()).quote_token(56).unwrap();
+        assert_eq!((usdt.symbol, usdt.decimals), ("USDT", 18));
+        assert_eq!(usdt.to_units(1.5e18), 1.5);
+
+        // The same raw amount is a trillion times larger at 6 decimals
+        assert_eq!(QuotePreference::default().quote_token(1).unwrap().to_units(3000e18), 3000e12);
+    }
+
+    #[test]
+    fn per_chain_preference_falls_back_to_the_other_stablecoin() {
+        let per_chain = HashMap::from([("bsc".to_string(), QuoteAsset::Usdt), ("blast".to_string(), QuoteAsset::Usdt)]);
+        let preference = QuotePreference::new(QuoteAsset::Usdc, &per_chain);
+        assert_eq!(preference.quote_token(56).unwrap().symbol, "USDT");
+        assert_eq!(preference.quote_token(1).unwrap().symbol, "USDC");
+        // Blast has no USDT
+        assert_eq!(preference.quote_token(81457).unwrap().symbol, "USDB");
+    }
+
+    #[test]
+    fn unknown_chains_have_no_quote_token() {
+        assert_eq!(QuotePreference::default().quote_token(250), None);
+        assert_eq!(QuotePreference::new(QuoteAsset::Usdt, &HashMap::new()).quote_token(999_999), None);
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+use parking_lot::Mutex;
+use tokio::time::Instant;
+
+/// Slowest pace a limiter accepts (one request every ~17 minutes); far smaller
+/// rates overflow the slot interval
+pub const MIN_RPS: f64 = 0.001;
+
+/// A configured rate: 0 (unlimited) or a finite value of at least `MIN_RPS`
+pub fn check_rps(rps: f64) -> Result<(), String> {
+    if rps == 0.0 || (rps.is_finite() && rps >= MIN_RPS) {
+        Ok(())
+    } else {
+        Err(format!("{rps} must be 0 (unlimited) or a finite rate >= {MIN_RPS}"))
+    }
+}
+
+/// Evenly spaces requests to one source at `rps` per second
+pub struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// `rps` below `MIN_RPS` (or NaN) is raised to it; callers validate with `check_rps`
+    pub fn new(rps: f64) -> Self {
+        Self {
+            interval: Duration::from_secs_f64(1.0 / rps.max(MIN_RPS)),
+            next_slot: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Wait for this request's slot. Slots are handed out under the lock and
+    /// awaited outside it, so concurrent callers queue up in order.
+    pub async fn acquire(&self) {
+        let slot = {
+            let mut next = self.next_slot.lock();
+            let slot = (*next).max(Instant::now());
+            *next = slot + self.interval;
+            slot
+        };
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+/// lowercase source name -> limiter
+static LIMITERS: OnceLock<HashMap<String, RateLimiter>> = OnceLock::new();
+
+/// Install per-source limits (requests/second; non-positive = unlimited). First call wins.
+pub fn init(limits: &HashMap<String, f64>) {
+    let limiters = limits.iter()
+        .filter(|(_, rps)| rps.is_finite() && **rps > 0.0)
+        .map(|(source, rps)| (source.to_lowercase(), RateLimiter::new(*rps)))
+        .collect();
+    let _ = LIMITERS.set(limiters);
+}
+
+/// Wait until `source` may issue another request (no-op for unlimited sources)
+pub async fn acquire(source: &str) {
+    if let Some(limiter) = LIMITERS.get().and_then(|l| l.get(&source.to_lowercase())) {
+        limiter.acquire().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[tokio::test(start_paused = true)]
+    async fn requests_are_spaced_by_the_interval() {
+        let limiter = RateLimiter::new(2.0);
+        let start = Instant::now();
+        let mut offsets = Vec::new();
+        for _ in 0..4 {
+            limiter.acquire().await;
+            offsets.push(start.elapsed().as_millis());
+        }
+        assert_eq!(offsets, vec![0, 500, 1000, 1500]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn concurrent_callers_queue_in_slots() {
+        let limiter = Arc::new(RateLimiter::new(10.0));
+        let start = Instant::now();
+        let tasks: Vec<_> = (0..5)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    limiter.acquire().await;
+                    start.elapsed().as_millis()
+                })
+            })
+            .collect();
+
+        let mut offsets = Vec::new();
+        for task in tasks {
+            offsets.push(task.await.unwrap());
+        }
+        offsets.sort();
+        assert_eq!(offsets, vec![0, 100, 200, 300, 400]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn idle_time_is_not_banked() {
+        let limiter = RateLimiter::new(1.0);
+        limiter.acquire().await;
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        // After a long pause the next two calls are still a full interval apart
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed().as_millis(), 1000);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn limited_requests_still_overlap() {
+        // 10 calls of 300ms each at 10 rps: starts are 100ms apart, but a call
+        // doesn't wait for the previous one to finish
+        let limiter = Arc::new(RateLimiter::new(10.0));
+        let start = Instant::now();
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let limiter = limiter.clone();
+                tokio::spawn(async move {
+                    limiter.acquire().await;
+                    let started = start.elapsed().as_millis();
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                    started
+                })
+            })
+            .collect();
+
+        let mut starts = Vec::new();
+        for task in tasks {
+            starts.push(task.await.unwrap());
+        }
+        starts.sort();
+        assert_eq!(starts, (0..10).map(|i| i * 100).collect::<Vec<u128>>());
+        // Serialized this would be 10 x 300ms
+        assert_eq!(start.elapsed().as_millis(), 1200);
+    }
+
+    #[test]
+    fn unusable_rates_are_rejected_and_never_panic() {
+        for ok in [0.0, MIN_RPS, 0.5, 5.0, 1e6] {
+            assert!(check_rps(ok).is_ok(), "{ok}");
+        }
+        for bad in [-1.0, 1e-300, f64::MIN_POSITIVE, MIN_RPS / 2.0, f64::NAN, f64::INFINITY] {
+            assert!(check_rps(bad).is_err(), "{bad}");
+            // `Duration::from_secs_f64(1.0 / 1e-300)` would panic
+            RateLimiter::new(bad);
+        }
+    }
+}
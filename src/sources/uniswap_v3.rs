@@ -0,0 +1,290 @@
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::time::Duration;
+use reqwest::Client;
+use serde::Deserialize;
+use crate::config::{ApiKeysConfig, UniswapV3Config};
+use crate::models::{canonical_chain, PoolData};
+use super::aggregators::get_search_variants;
+use super::{http, PoolSource, SourceError};
+
+/// Timeout of each subgraph query
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Deepest pools returned per chain
+const POOLS_PER_CHAIN: usize = 10;
+
+/// Uniswap V3 subgraph ids on The Graph's decentralized network
+const SUBGRAPH_IDS: &[(&str, &str)] = &[
+    ("ethereum", "5zvR82QoaXYFyDEKLZ9t6v9adgnptxYpKpSbxtgVENFV"),
+    ("arbitrum", "FbCGRftH4a3yZugY7TnbYgPJVEv2LvMT6oF1fxPe9aJM"),
+    ("base", "43Hwfi3dJSoGpyas9VwNoDAv55yjgGrPpNSmbQZArzMG"),
+    ("polygon", "3hCPRGf4z88VC5rsBKU5AA9FBBq5nF3jbKJG7VZCbhjm"),
+];
+
+/// Pools where either side is one of `$symbols`, deepest first. Volume comes
+/// from the latest `poolDayData` (the current UTC day, so partial).
+const POOLS_QUERY: &str = r#"
+query Pools($symbols: [String!]!, $first: Int!) {
+  bundle(id: "1") { ethPriceUSD }
+  pools(
+    first: $first
+    orderBy: totalValueLockedUSD
+    orderDirection: desc
+    where: { or: [{ token0_: { symbol_in: $symbols } }, { token1_: { symbol_in: $symbols } }] }
+  ) {
+    id
+    feeTier
+    totalValueLockedUSD
+    token0 { id symbol derivedETH }
+    token1 { id symbol derivedETH }
+    poolDayData(first: 1, orderBy: date, orderDirection: desc) { volumeUSD }
+  }
+}
+"#;
+
+/// `{ "data": ..., "errors": [...] }`
+#[derive(Debug, Deserialize)]
+struct GraphResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GraphError {
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoolsData {
+    bundle: Option<Bundle>,
+    pools: Vec<SubgraphPool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Bundle {
+    #[serde(rename = "ethPriceUSD")]
+    eth_price_usd: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubgraphPool {
+    id: String,
+    /// Hundredths of a bip: "3000" = 0.3%
+    fee_tier: String,
+    #[serde(rename = "totalValueLockedUSD")]
+    total_value_locked_usd: String,
+    token0: SubgraphToken,
+    token1: SubgraphToken,
+    #[serde(default)]
+    pool_day_data: Vec<PoolDayData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubgraphToken {
+    id: String,
+    symbol: String,
+    #[serde(rename = "derivedETH")]
+    derived_eth: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PoolDayData {
+    #[serde(rename = "volumeUSD")]
+    volume_usd: String,
+}
+
+fn decimal(s: &str) -> f64 {
+    s.parse().unwrap_or(0.0)
+}
+
+/// Uniswap V3 pools straight from the protocol subgraph: real pool addresses,
+/// TVL, fee tier and token addresses
+pub struct UniswapV3Source {
+    client: Client,
+    /// (canonical chain, subgraph URL)
+    endpoints: Vec<(&'static str, String)>,
+}
+
+impl UniswapV3Source {
+    /// Source on an injected (shared) client. Chains without a URL override
+    /// need `[api_keys] thegraph`; None when no chain is left.
+    pub fn with_client(client: Client, config: &UniswapV3Config, keys: &ApiKeysConfig) -> Option<Self> {
+        let endpoints: Vec<(&'static str, String)> = config.chains.iter()
+            .filter_map(|chain| {
+                let canonical = canonical_chain(chain);
+                let Some(&(name, id)) = SUBGRAPH_IDS.iter().find(|(name, _)| *name == canonical) else {
+                    tracing::warn!("⚠️ Uniswap V3: unsupported chain {}", chain);
+                    return None;
+                };
+                let url = config.subgraph_urls.iter()
+                    .find(|(key, _)| canonical_chain(key) == name)
+                    .map(|(_, url)| url.clone())
+                    .or_else(|| keys.thegraph().map(|key| {
+                        format!("https://gateway.thegraph.com/api/{}/subgraphs/id/{}", key, id)
+                    }));
+                if url.is_none() {
+                    tracing::warn!("⚠️ Uniswap V3: no [api_keys] thegraph or subgraph_urls entry for {}, skipped", name);
+                }
+                url.map(|url| (name, url))
+            })
+            .collect();
+
+        if endpoints.is_empty() {
+            return None;
+        }
+        Some(Self { client, endpoints })
+    }
+
+    pub fn chains(&self) -> Vec<&'static str> {
+        self.endpoints.iter().map(|(chain, _)| *chain).collect()
+    }
+
+    async fn fetch_chain(&self, chain: &'static str, url: &str, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        let variants = get_search_variants(symbol);
+        let req = self.client.post(url)
+            .timeout(REQUEST_TIMEOUT)
+            .json(&serde_json::json!({
+                "query": POOLS_QUERY,
+                "variables": { "symbols": variants, "first": POOLS_PER_CHAIN },
+            }));
+
+        let response: GraphResponse<PoolsData> = http::fetch_json(self.name(), req).await?;
+        let data = match (response.data, response.errors.first()) {
+            (Some(data), _) => data,
+            (None, Some(error)) => return Err(SourceError::Parse(format!("subgraph: {}", error.message))),
+            (None, None) => return Err(SourceError::Parse("subgraph: empty response".to_string())),
+        };
+        Ok(Self::parse_pools(chain, symbol, &variants, data))
+    }
+
+    fn parse_pools(chain: &str, symbol: &str, variants: &[String], data: PoolsData) -> Vec<PoolData> {
+        let eth_usd = data.bundle.map(|b| decimal(&b.eth_price_usd)).unwrap_or(0.0);
+
+        data.pools.into_iter()
+            .filter_map(|pool| {
+                let is_ours = |t: &SubgraphToken| variants.iter().any(|v| v.eq_ignore_ascii_case(&t.symbol));
+                let (base, quote) = if is_ours(&pool.token0) {
+                    (pool.token0, pool.token1)
+                } else if is_ours(&pool.token1) {
+                    (pool.token1, pool.token0)
+                } else {
+                    return None;
+                };
+
+                let price = decimal(&base.derived_eth) * eth_usd;
+                if !(price > 0.0 && price.is_finite()) {
+                    return None;
+                }
+                let fee = pool.fee_tier.parse::<f64>().ok().map(|tier| tier / 1_000_000.0);
+                let volume = pool.pool_day_data.first().map(|d| decimal(&d.volume_usd)).unwrap_or(0.0);
+
+                Some(PoolData::new(
+                    symbol.to_uppercase(),
+                    chain.to_string(),
+                    "uniswap_v3".to_string(),
+                    pool.id,
+                    format!("{}/{}", base.symbol, quote.symbol),
+                    price,
+                    decimal(&pool.total_value_locked_usd),
+                    volume,
+                    "uniswap_v3".to_string(),
+                )
+                .with_fee_tier(fee)
+                .with_tokens(Some(base.id), Some(quote.id)))
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl PoolSource for UniswapV3Source {
+    fn name(&self) -> &'static str { "UniswapV3" }
+
+    /// Chains are queried concurrently; a failing chain only fails the whole
+    /// fetch when every chain failed
+    async fn fetch_pools(&self, symbol: &str) -> Result<Vec<PoolData>, SourceError> {
+        let results = join_all(self.endpoints.iter()
+            .map(|(chain, url)| self.fetch_chain(chain, url, symbol))).await;
+
+        let mut pools = Vec::new();
+        let mut errors = Vec::new();
+        for result in results {
+            match result {
+                Ok(chain_pools) => pools.extend(chain_pools),
+                Err(e) => errors.push(e),
+            }
+        }
+        if errors.len() == self.endpoints.len() {
+            if let Some(e) = errors.pop() {
+                return Err(e);
+            }
+        }
+        Ok(pools)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use axum::routing::post;
+    use crate::test_util::serve;
+
+    #[test]
+    fn parses_the_subgraph_fixture() {
+        let response: GraphResponse<PoolsData> =
+            serde_json::from_str(include_str!("../../tests/fixtures/uniswap_v3.json")).unwrap();
+        let variants = get_search_variants("link");
+        let pools = UniswapV3Source::parse_pools("ethereum", "link", &variants, response.data.unwrap());
+        // WETH/USDT isn't LINK; the zero-priced LINK/DAI pool is dropped
+        assert_eq!(pools.len(), 2, "{pools:?}");
+
+        let weth = &pools[0];
+        assert_eq!((weth.symbol.as_str(), weth.chain.as_str(), weth.dex.as_str()), ("LINK", "ethereum", "uniswap_v3"));
+        assert_eq!(weth.pool_address, "0xa6cc3c2531fdaa6ae1a3ca84c2855806728693e8");
+        assert_eq!(weth.pair, "LINK/WETH");
+        assert!((weth.price_usd - 15.0).abs() < 1e-9);
+        assert_eq!(weth.lp_reserve_usd, 21480331.25);
+        assert_eq!(weth.volume_24h, 1204331.5);
+        assert_eq!(weth.fee_tier, Some(0.003));
+        assert_eq!(weth.base_token.as_deref(), Some("0x514910771af9ca656af840dff83e8264ecf986ca"));
+        assert_eq!(weth.quote_token.as_deref(), Some("0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2"));
+
+        // LINK as token1 is still the base; no day data means no volume
+        let usdc = &pools[1];
+        assert_eq!(usdc.pair, "LINK/USDC");
+        assert!((usdc.price_usd - 14.97).abs() < 1e-9);
+        assert_eq!((usdc.volume_24h, usdc.fee_tier), (0.0, Some(0.0005)));
+    }
+
+    /// Source over one mock subgraph answering `body`
+    async fn source(body: serde_json::Value) -> UniswapV3Source {
+        let router = axum::Router::new().route("/subgraph", post(move || async move { axum::Json(body) }));
+        let base = serve(router).await;
+        let config = UniswapV3Config {
+            enabled: true,
+            chains: vec!["ethereum".to_string()],
+            subgraph_urls: HashMap::from([("ethereum".to_string(), format!("{base}/subgraph"))]),
+        };
+        UniswapV3Source::with_client(http::shared_client(), &config, &ApiKeysConfig::default()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn graphql_errors_without_data_fail_the_fetch() {
+        let failing = source(serde_json::json!({ "errors": [{ "message": "indexing_error" }] })).await;
+        match failing.fetch_pools("LINK").await {
+            Err(SourceError::Parse(message)) => assert_eq!(message, "subgraph: indexing_error"),
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+
+        let empty = source(serde_json::json!({})).await;
+        assert!(matches!(empty.fetch_pools("LINK").await, Err(SourceError::Parse(_))));
+
+        // Partial errors next to data still yield the data
+        let mut partial: serde_json::Value = serde_json::from_str(include_str!("../../tests/fixtures/uniswap_v3.json")).unwrap();
+        partial["errors"] = serde_json::json!([{ "message": "slow shard" }]);
+        assert_eq!(source(partial).await.fetch_pools("LINK").await.unwrap().len(), 2);
+    }
+}
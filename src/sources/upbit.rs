@@ -1,9 +1,24 @@
 use reqwest::Client;
-use serde::Deserialize;
-use tokio_tungstenite::{connect_async, tungstenite::Message};
-use futures::{StreamExt, SinkExt};
+use serde::{Deserialize, Serialize};
+use tokio_tungstenite::{connect_async, tungstenite::{Error as WsError, Message}};
+use futures::{SinkExt, Stream, StreamExt};
+use std::future::Future;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use dashmap::DashMap;
+use parking_lot::RwLock;
+use tokio::sync::Notify;
+use tokio::net::TcpStream;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use futures::stream::SplitStream;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use async_trait::async_trait;
+use crate::config::UpbitConfig;
+use super::{http, retry_with_backoff, CexSource, RetryPolicy, SourceError};
+
+/// Upbit's USDT market; its KRW price is the won-dollar rate traders actually face
+const USDT_MARKET: &str = "KRW-USDT";
 
 #[derive(Debug, Clone)]
 pub struct CexPrice {
@@ -13,9 +28,163 @@ pub struct CexPrice {
     pub timestamp: i64,
 }
 
+const WS_URL: &str = "wss://api.upbit.com/websocket/v1";
+const REST_URL: &str = "https://api.upbit.com";
+
+type TickerStream = SplitStream<WebSocketStream<MaybeTlsStream<TcpStream>>>;
+
+/// State shared between the client and its websocket task
+struct TickerFeed {
+    prices: DashMap<String, CexPrice>,
+    /// Rate derived from the KRW-USDT ticker (when enabled and seen)
+    usdt_rate: RwLock<Option<f64>>,
+    /// Static default (or externally refreshed) KRW/USD rate
+    fallback_rate: RwLock<f64>,
+    use_usdt_rate: bool,
+    /// Signalled when the first ticker arrives
+    ready: Notify,
+    connected: AtomicBool,
+    /// Unix millis of the last ticker (0 = none yet)
+    last_message_ms: AtomicI64,
+}
+
+impl TickerFeed {
+    fn rate(&self) -> f64 {
+        self.usdt_rate.read().unwrap_or_else(|| *self.fallback_rate.read())
+    }
+
+    fn on_message(&self, data: &[u8]) {
+        let Ok(ticker) = serde_json::from_slice::<UpbitTicker>(data) else {
+            return;
+        };
+        if self.use_usdt_rate && ticker.code == USDT_MARKET && ticker.trade_price > 0.0 {
+            *self.usdt_rate.write() = Some(ticker.trade_price);
+        }
+
+        let symbol = ticker.code.replace("KRW-", "");
+        let price = CexPrice {
+            symbol: symbol.clone(),
+            price_krw: ticker.trade_price,
+            price_usd: ticker.trade_price / self.rate(),
+            timestamp: ticker.timestamp,
+        };
+        self.prices.insert(symbol, price);
+
+        let first = self.last_message_ms.swap(chrono::Utc::now().timestamp_millis(), Ordering::Relaxed) == 0;
+        if first {
+            self.ready.notify_waiters();
+        }
+    }
+}
+
+/// Reconnect delay: starts at the first value, doubles up to the second
+const RECONNECT_BACKOFF: (Duration, Duration) = (Duration::from_secs(1), Duration::from_secs(30));
+
+impl TickerFeed {
+    /// Read tickers from `read`; on close, error or `stale_after` of silence,
+    /// reconnect through `connect` with backoff and keep reading, forever
+    async fn run<S, C, Fut>(self: Arc<Self>, mut read: S, stale_after: Duration, backoff: (Duration, Duration), mut connect: C)
+    where
+        S: Stream<Item = Result<Message, WsError>> + Unpin,
+        C: FnMut() -> Fut,
+        Fut: Future<Output = Result<S, WsError>>,
+    {
+        loop {
+            loop {
+                match tokio::time::timeout(stale_after, read.next()).await {
+                    Ok(Some(Ok(Message::Binary(data)))) => self.on_message(&data),
+                    Ok(Some(Ok(_))) => {}
+                    Ok(Some(Err(e))) => {
+                        tracing::warn!("⚠️ Upbit WebSocket error: {}", e);
+                        break;
+                    }
+                    Ok(None) => {
+                        tracing::warn!("⚠️ Upbit WebSocket closed");
+                        break;
+                    }
+                    Err(_) => {
+                        tracing::warn!("⚠️ No Upbit ticker for {}s, reconnecting", stale_after.as_secs());
+                        break;
+                    }
+                }
+            }
+            self.connected.store(false, Ordering::Relaxed);
+
+            let (mut delay, max_delay) = backoff;
+            let mut attempt = 1;
+            read = loop {
+                tokio::time::sleep(delay).await;
+                tracing::info!("🔄 Upbit WebSocket reconnect attempt {}", attempt);
+                match connect().await {
+                    Ok(read) => break read,
+                    Err(e) => {
+                        tracing::warn!("⚠️ Upbit reconnect failed: {}", e);
+                        delay = (delay * 2).min(max_delay);
+                        attempt += 1;
+                    }
+                }
+            };
+            self.connected.store(true, Ordering::Relaxed);
+            tracing::info!("✓ Upbit WebSocket reconnected");
+        }
+    }
+}
+
+/// Connect and send the ticker subscription
+async fn subscribe(codes: &[String]) -> Result<TickerStream, WsError> {
+    let (ws_stream, _) = connect_async(WS_URL).await?;
+    let (mut write, read) = ws_stream.split();
+
+    let subscribe = serde_json::json!([
+        {"ticket": "dex-gatherer"},
+        {"type": "ticker", "codes": codes}
+    ]);
+    write.send(Message::Text(subscribe.to_string())).await?;
+    Ok(read)
+}
+
 pub struct UpbitClient {
-    prices: Arc<DashMap<String, CexPrice>>,
-    krw_usd_rate: f64,
+    feed: Arc<TickerFeed>,
+    /// HTTP client for the REST and FX calls
+    client: Client,
+    /// Silence after which the websocket is considered stale and reconnected
+    stale_after: Duration,
+    market_timeout: Duration,
+    /// REST API base (`/v1/market/all`)
+    rest_url: String,
+    /// External USD-base FX endpoint refreshing the fallback rate
+    fx_url: Option<String>,
+    fx_refresh: Duration,
+    /// On-disk copy of the KRW market list
+    market_cache: Option<PathBuf>,
+    market_cache_ttl: i64,
+    market_retries: u32,
+}
+
+/// Cached `/v1/market/all` result
+#[derive(Serialize, Deserialize)]
+struct MarketCache {
+    fetched_at: i64,
+    coins: Vec<String>,
+}
+
+impl MarketCache {
+    fn load(path: &Path) -> Option<Self> {
+        let data = std::fs::read_to_string(path).ok()?;
+        serde_json::from_str(&data).ok()
+    }
+
+    fn save(&self, path: &Path) {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+        let result = serde_json::to_string(self)
+            .map_err(std::io::Error::other)
+            .and_then(|json| std::fs::write(path, json));
+        if let Err(e) = result {
+            tracing::warn!("⚠️ Failed to cache Upbit market list: {}", e);
+        }
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -25,79 +194,493 @@ struct UpbitTicker {
     timestamp: i64,
 }
 
+impl Default for UpbitClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl UpbitClient {
     pub fn new() -> Self {
+        Self::from_config(&UpbitConfig::default())
+    }
+
+    pub fn from_config(config: &UpbitConfig) -> Self {
+        Self::with_client(http::shared_client(), config)
+    }
+
+    /// Client on an injected (shared) HTTP client
+    pub fn with_client(client: Client, config: &UpbitConfig) -> Self {
         Self {
-            prices: Arc::new(DashMap::new()),
-            krw_usd_rate: 1400.0, // 기본 환율
+            client,
+            feed: Arc::new(TickerFeed {
+                prices: DashMap::new(),
+                usdt_rate: RwLock::new(None),
+                fallback_rate: RwLock::new(config.krw_usd_rate),
+                use_usdt_rate: config.use_usdt_rate,
+                ready: Notify::new(),
+                connected: AtomicBool::new(false),
+                last_message_ms: AtomicI64::new(0),
+            }),
+            stale_after: Duration::from_secs(config.stale_secs.max(5)),
+            market_timeout: Duration::from_secs(config.market_timeout_secs.max(1)),
+            rest_url: REST_URL.to_string(),
+            fx_url: config.fx_url.clone().filter(|url| !url.is_empty()),
+            fx_refresh: Duration::from_secs(config.fx_refresh_secs.max(60)),
+            market_cache: config.market_cache_path.as_ref()
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+            market_cache_ttl: config.market_cache_ttl_secs as i64,
+            market_retries: config.market_retries.max(1),
+        }
+    }
+
+    /// Point the REST calls at another server (tests)
+    #[cfg(test)]
+    fn with_rest_url(mut self, rest_url: String) -> Self {
+        self.rest_url = rest_url;
+        self
+    }
+
+    /// Wait until the first ticker message arrives; false on timeout
+    pub async fn wait_ready(&self, timeout: Duration) -> bool {
+        // Created before the check so a ticker arriving in between still wakes us
+        let notified = self.feed.ready.notified();
+        if !self.feed.prices.is_empty() {
+            return true;
         }
+        tokio::time::timeout(timeout, notified).await.is_ok() || !self.feed.prices.is_empty()
+    }
+
+    /// KRW per USD: KRW-USDT market price when available, else the fallback rate
+    pub fn rate(&self) -> f64 {
+        self.feed.rate()
+    }
+
+    /// Whether the rate currently comes from the KRW-USDT market
+    pub fn rate_from_market(&self) -> bool {
+        self.feed.usdt_rate.read().is_some()
+    }
+
+    /// Replace the fallback rate (static default or external FX feed)
+    pub fn set_rate(&self, rate: f64) {
+        if rate.is_finite() && rate > 0.0 {
+            *self.feed.fallback_rate.write() = rate;
+        }
+    }
+
+    /// USD price is derived at read time so FX updates apply to every ticker
+    fn with_current_rate(&self, mut price: CexPrice) -> CexPrice {
+        price.price_usd = price.price_krw / self.rate();
+        price
     }
 
     pub fn get_price(&self, symbol: &str) -> Option<CexPrice> {
-        self.prices.get(symbol).map(|p| p.clone())
+        self.feed.prices.get(symbol).map(|p| self.with_current_rate(p.clone()))
     }
 
     pub fn get_all_prices(&self) -> Vec<CexPrice> {
-        self.prices.iter().map(|p| p.value().clone()).collect()
+        self.feed.prices.iter().map(|p| self.with_current_rate(p.value().clone())).collect()
     }
 
-    pub async fn start_websocket(&self, symbols: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
-        let url = "wss://api.upbit.com/websocket/v1";
-        let (ws_stream, _) = connect_async(url).await?;
-        let (mut write, mut read) = ws_stream.split();
+    /// Whether the ticker websocket is currently connected
+    pub fn is_connected(&self) -> bool {
+        self.feed.connected.load(Ordering::Relaxed)
+    }
 
-        // 구독 메시지
-        let codes: Vec<String> = symbols.iter()
+    /// Time since the last ticker message (None before the first one)
+    pub fn last_message_age(&self) -> Option<Duration> {
+        let last = self.feed.last_message_ms.load(Ordering::Relaxed);
+        if last == 0 {
+            return None;
+        }
+        let age = chrono::Utc::now().timestamp_millis() - last;
+        Some(Duration::from_millis(age.max(0) as u64))
+    }
+
+    /// Connect and subscribe, then keep the feed alive in the background: on
+    /// close, error or `stale_after` of silence it reconnects with backoff (1s → 30s)
+    pub async fn start_websocket(&self, symbols: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut codes: Vec<String> = symbols.iter()
             .map(|s| format!("KRW-{}", s.to_uppercase()))
             .collect();
-        
-        let subscribe = serde_json::json!([
-            {"ticket": "dex-gatherer"},
-            {"type": "ticker", "codes": codes}
-        ]);
+        if self.feed.use_usdt_rate && !codes.iter().any(|c| c == USDT_MARKET) {
+            codes.push(USDT_MARKET.to_string());
+        }
+
+        let read = subscribe(&codes).await?;
+        self.feed.connected.store(true, Ordering::Relaxed);
+
+        tokio::spawn(self.feed.clone().run(read, self.stale_after, RECONNECT_BACKOFF, move || {
+            let codes = codes.clone();
+            async move { subscribe(&codes).await }
+        }));
+
+        Ok(())
+    }
 
-        write.send(Message::Text(subscribe.to_string())).await?;
+    /// Fetch KRW per USD from the FX endpoint (`{"rates": {"KRW": ...}}`) into the fallback rate
+    pub async fn refresh_fx(&self) -> Result<f64, SourceError> {
+        #[derive(Deserialize)]
+        struct FxResponse {
+            rates: std::collections::HashMap<String, f64>,
+        }
+
+        let Some(url) = self.fx_url.as_deref() else {
+            return Ok(*self.feed.fallback_rate.read());
+        };
+        let req = self.client.get(url).timeout(self.market_timeout);
+        let data: FxResponse = http::fetch_json("UpbitFx", req).await?;
+        let rate = data.rates.get("KRW")
+            .copied()
+            .filter(|r| r.is_finite() && *r > 0.0)
+            .ok_or_else(|| SourceError::Parse("missing KRW rate".to_string()))?;
+        self.set_rate(rate);
+        Ok(rate)
+    }
 
-        let prices = self.prices.clone();
-        let rate = self.krw_usd_rate;
+    /// Refresh the FX rate now and then periodically; failures keep the previous rate
+    pub async fn start_fx_refresh(self: &Arc<Self>) {
+        if self.fx_url.is_none() {
+            return;
+        }
+        match self.refresh_fx().await {
+            Ok(rate) => tracing::info!("✓ KRW/USD rate: {:.2}", rate),
+            Err(e) => tracing::warn!("⚠️ FX fetch failed, using {:.2}: {}", self.rate(), e),
+        }
 
+        let client = self.clone();
         tokio::spawn(async move {
-            while let Some(msg) = read.next().await {
-                if let Ok(Message::Binary(data)) = msg {
-                    if let Ok(ticker) = serde_json::from_slice::<UpbitTicker>(&data) {
-                        let symbol = ticker.code.replace("KRW-", "");
-                        let price = CexPrice {
-                            symbol: symbol.clone(),
-                            price_krw: ticker.trade_price,
-                            price_usd: ticker.trade_price / rate,
-                            timestamp: ticker.timestamp,
-                        };
-                        prices.insert(symbol, price);
-                    }
+            let mut ticker = tokio::time::interval(client.fx_refresh);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(e) = client.refresh_fx().await {
+                    tracing::warn!("⚠️ FX refresh failed: {}", e);
                 }
             }
         });
-
-        Ok(())
     }
 
+    /// KRW market coins: disk cache within its TTL, else `/v1/market/all` with
+    /// backoff on 429 (honoring `Retry-After`)/network errors, falling back to a stale cache if every attempt fails
     pub async fn fetch_krw_coins(&self) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        let client = Client::new();
-        let resp = client.get("https://api.upbit.com/v1/market/all")
-            .send()
-            .await?;
+        let now = chrono::Utc::now().timestamp();
+        let cached = self.market_cache.as_deref().and_then(MarketCache::load);
+        if let Some(cache) = cached.as_ref().filter(|c| now - c.fetched_at < self.market_cache_ttl) {
+            tracing::info!("✓ Using cached Upbit market list ({} coins)", cache.coins.len());
+            return Ok(cache.coins.clone());
+        }
+
+        // The only retry loop for this request: attempts are sent with `fetch_json_once`
+        let policy = RetryPolicy {
+            max_attempts: self.market_retries,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+        };
+        let mut attempt = 0;
+        let result = retry_with_backoff(policy, || {
+            attempt += 1;
+            let attempt = attempt;
+            async move {
+                self.request_krw_coins().await.inspect_err(|e| {
+                    tracing::warn!("⚠️ Upbit market list attempt {}/{} failed: {}", attempt, self.market_retries, e);
+                })
+            }
+        }).await;
+
+        let last_error = match result {
+            Ok(coins) => {
+                if let Some(path) = self.market_cache.as_deref() {
+                    MarketCache { fetched_at: now, coins: coins.clone() }.save(path);
+                }
+                return Ok(coins);
+            }
+            Err(e) => e,
+        };
+
+        match cached {
+            Some(cache) => {
+                tracing::warn!("⚠️ Upbit market list unavailable ({}), using stale cache", last_error);
+                Ok(cache.coins)
+            }
+            None => Err(last_error.to_string().into()),
+        }
+    }
 
+    async fn request_krw_coins(&self) -> Result<Vec<String>, SourceError> {
         #[derive(Deserialize)]
         struct Market {
             market: String,
         }
 
-        let markets: Vec<Market> = resp.json().await?;
-        let krw_coins: Vec<String> = markets.into_iter()
+        let req = self.client
+            .get(format!("{}/v1/market/all", self.rest_url))
+            .timeout(self.market_timeout);
+        let markets: Vec<Market> = http::fetch_json_once("Upbit", req).await?;
+        Ok(markets.into_iter()
             .filter(|m| m.market.starts_with("KRW-"))
             .map(|m| m.market.replace("KRW-", ""))
-            .collect();
+            .collect())
+    }
+}
+
+#[async_trait]
+impl CexSource for UpbitClient {
+    fn name(&self) -> &'static str {
+        "Upbit"
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<String>, SourceError> {
+        self.fetch_krw_coins().await.map_err(|e| SourceError::Network(e.to_string()))
+    }
+
+    fn get_all_prices(&self) -> Vec<CexPrice> {
+        UpbitClient::get_all_prices(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(use_usdt_rate: bool) -> UpbitClient {
+        UpbitClient::from_config(&UpbitConfig { use_usdt_rate, krw_usd_rate: 1300.0, ..UpbitConfig::default() })
+    }
+
+    fn ticker(code: &str, trade_price: f64) -> Vec<u8> {
+        serde_json::json!({ "type": "ticker", "code": code, "trade_price": trade_price, "timestamp": 1_700_000_000_000i64 })
+            .to_string()
+            .into_bytes()
+    }
+
+    #[test]
+    fn usdt_ticker_sets_the_conversion_rate() {
+        let upbit = client(true);
+        upbit.feed.on_message(&ticker("KRW-BTC", 91_000_000.0));
+        assert_eq!(upbit.get_price("BTC").unwrap().price_usd, 70_000.0);
+        assert!(!upbit.rate_from_market());
+
+        upbit.feed.on_message(&ticker(USDT_MARKET, 1400.0));
+        assert!(upbit.rate_from_market());
+        assert_eq!(upbit.rate(), 1400.0);
+        // Earlier tickers are re-priced at read time
+        assert_eq!(upbit.get_price("BTC").unwrap().price_usd, 65_000.0);
+        assert_eq!(upbit.get_price("USDT").unwrap().price_usd, 1.0);
+
+        // An FX refresh only moves the fallback, the market rate keeps winning
+        upbit.set_rate(1000.0);
+        assert_eq!(upbit.rate(), 1400.0);
+    }
+
+    #[test]
+    fn usdt_rate_ignored_when_disabled() {
+        let upbit = client(false);
+        upbit.feed.on_message(&ticker(USDT_MARKET, 1400.0));
+        upbit.feed.on_message(&ticker("KRW-ETH", 3_900_000.0));
+        assert!(!upbit.rate_from_market());
+        assert_eq!(upbit.get_price("ETH").unwrap().price_usd, 3000.0);
+    }
+
+    #[test]
+    fn malformed_or_zero_usdt_ticker_keeps_fallback() {
+        let upbit = client(true);
+        upbit.feed.on_message(b"not json");
+        upbit.feed.on_message(&ticker(USDT_MARKET, 0.0));
+        assert!(!upbit.rate_from_market());
+        assert_eq!(upbit.rate(), 1300.0);
+    }
+
+    /// Deliver a ticker to the feed after `delay`, as the websocket task would
+    fn deliver_after(upbit: &UpbitClient, delay: Duration) {
+        let feed = upbit.feed.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            feed.on_message(&ticker("KRW-ETH", 3_900_000.0));
+        });
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn startup_proceeds_as_soon_as_the_first_ticker_arrives() {
+        let upbit = client(false);
+        deliver_after(&upbit, Duration::from_millis(20));
+        let started = tokio::time::Instant::now();
+        assert!(upbit.wait_ready(Duration::from_secs(5)).await);
+        assert_eq!(started.elapsed(), Duration::from_millis(20));
+
+        // Already ready: no wait at all
+        let started = tokio::time::Instant::now();
+        assert!(upbit.wait_ready(Duration::from_secs(5)).await);
+        assert_eq!(started.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn slow_first_ticker_gives_up_at_the_timeout() {
+        let upbit = client(false);
+        deliver_after(&upbit, Duration::from_millis(500));
+        let started = tokio::time::Instant::now();
+        assert!(!upbit.wait_ready(Duration::from_millis(100)).await);
+        assert_eq!(started.elapsed(), Duration::from_millis(100));
+        assert!(upbit.get_all_prices().is_empty());
+    }
+
+    #[test]
+    fn changing_the_rate_reprices_usd() {
+        let upbit = client(false);
+        upbit.feed.on_message(&ticker("KRW-ETH", 3_900_000.0));
+        assert_eq!(upbit.get_price("ETH").unwrap().price_usd, 3000.0);
+
+        upbit.set_rate(1500.0);
+        assert_eq!(upbit.rate(), 1500.0);
+        assert_eq!(upbit.get_price("ETH").unwrap().price_usd, 2600.0);
+        assert_eq!(upbit.get_all_prices()[0].price_usd, 2600.0);
+
+        // Nonsense rates are ignored
+        for bad in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            upbit.set_rate(bad);
+        }
+        assert_eq!(upbit.rate(), 1500.0);
+    }
+
+    #[tokio::test]
+    async fn fx_refresh_updates_the_fallback_and_keeps_it_on_failure() {
+        let router = axum::Router::new()
+            .route("/fx", axum::routing::get(|| async { axum::Json(serde_json::json!({ "base": "USD", "rates": { "KRW": 1385.5, "JPY": 151.2 } })) }))
+            .route("/no-krw", axum::routing::get(|| async { axum::Json(serde_json::json!({ "rates": { "JPY": 151.2 } })) }))
+            .route("/down", axum::routing::get(|| async { axum::http::StatusCode::INTERNAL_SERVER_ERROR }));
+        let base = crate::test_util::serve(router).await;
+        let upbit_at = |path: &str| UpbitClient::from_config(&UpbitConfig {
+            fx_url: Some(format!("{base}{path}")),
+            krw_usd_rate: 1400.0,
+            ..UpbitConfig::default()
+        });
+
+        let upbit = upbit_at("/fx");
+        upbit.feed.on_message(&ticker("KRW-BTC", 138_550_000.0));
+        assert_eq!(upbit.refresh_fx().await.unwrap(), 1385.5);
+        assert_eq!(upbit.get_price("BTC").unwrap().price_usd, 100_000.0);
+
+        for path in ["/no-krw", "/down"] {
+            let upbit = upbit_at(path);
+            assert!(upbit.refresh_fx().await.is_err(), "{path}");
+            assert_eq!(upbit.rate(), 1400.0, "{path}");
+        }
+    }
+
+    /// `/v1/market/all` answering 429 (`Retry-After: 0`) for the first `limited` requests
+    async fn market_server(limited: usize) -> (String, Arc<std::sync::atomic::AtomicUsize>) {
+        use axum::http::{header, StatusCode};
+        use axum::response::IntoResponse;
+
+        let requests = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = requests.clone();
+        let router = axum::Router::new().route("/v1/market/all", axum::routing::get(move || {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if n < limited {
+                    return (StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, "0")]).into_response();
+                }
+                axum::Json(serde_json::json!([
+                    { "market": "KRW-BTC" }, { "market": "BTC-ETH" }, { "market": "KRW-ETH" },
+                ])).into_response()
+            }
+        }));
+        (crate::test_util::serve(router).await, requests)
+    }
+
+    fn market_client(rest_url: String, cache: &Path, retries: u32) -> UpbitClient {
+        UpbitClient::from_config(&UpbitConfig {
+            market_cache_path: Some(cache.to_str().unwrap().to_string()),
+            market_retries: retries,
+            ..UpbitConfig::default()
+        })
+        .with_rest_url(rest_url)
+    }
+
+    #[tokio::test]
+    async fn market_list_survives_a_rate_limit_then_comes_from_the_cache() {
+        let (base, requests) = market_server(1).await;
+        let cache = crate::test_util::temp_dir("upbit-markets").join("markets.json");
+        let upbit = market_client(base, &cache, 4);
+
+        assert_eq!(upbit.fetch_krw_coins().await.unwrap(), ["BTC", "ETH"]);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        // Within the TTL the disk copy answers without a request
+        assert_eq!(upbit.fetch_krw_coins().await.unwrap(), ["BTC", "ETH"]);
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn market_retries_bound_the_total_requests() {
+        let (base, requests) = market_server(usize::MAX).await;
+        let cache = crate::test_util::temp_dir("upbit-markets-limited").join("markets.json");
+        let upbit = market_client(base.clone(), &cache, 3);
+
+        assert!(upbit.fetch_krw_coins().await.is_err());
+        // One attempt per configured retry, not multiplied by the shared fetch retries
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        // An expired cache is still better than nothing
+        MarketCache { fetched_at: 0, coins: vec!["XRP".to_string()] }.save(&cache);
+        assert_eq!(market_client(base, &cache, 1).fetch_krw_coins().await.unwrap(), ["XRP"]);
+    }
+
+    type Frames = futures::stream::BoxStream<'static, Result<Message, WsError>>;
+
+    /// Stream yielding `frames`, then staying open without messages
+    fn frames(frames: Vec<Result<Message, WsError>>) -> Frames {
+        futures::stream::iter(frames).chain(futures::stream::pending()).boxed()
+    }
+
+    async fn wait_for_price(upbit: &UpbitClient, symbol: &str) {
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while upbit.get_price(symbol).is_none() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        }).await.unwrap_or_else(|_| panic!("no {symbol} ticker"));
+    }
+
+    #[tokio::test]
+    async fn feed_reconnects_after_an_error_and_resubscribes() {
+        let upbit = client(false);
+        let first = futures::stream::iter(vec![
+            Ok(Message::Binary(ticker("KRW-BTC", 91_000_000.0))),
+            Err(WsError::ConnectionClosed),
+        ]).boxed();
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let connects = attempts.clone();
+        tokio::spawn(upbit.feed.clone().run(first, Duration::from_secs(5), (Duration::from_millis(1), Duration::from_millis(4)), move || {
+            let attempt = connects.fetch_add(1, Ordering::SeqCst);
+            async move {
+                // The first reconnect fails, the second delivers
+                if attempt == 0 {
+                    Err(WsError::ConnectionClosed)
+                } else {
+                    Ok(frames(vec![Ok(Message::Binary(ticker("KRW-ETH", 3_900_000.0)))]))
+                }
+            }
+        }));
+
+        wait_for_price(&upbit, "ETH").await;
+        assert_eq!(upbit.get_price("BTC").unwrap().price_krw, 91_000_000.0);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert!(upbit.is_connected());
+        assert!(upbit.last_message_age().unwrap() < Duration::from_secs(2));
+    }
+
+    #[tokio::test]
+    async fn silent_feed_is_reconnected() {
+        let upbit = client(false);
+        let attempts = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let connects = attempts.clone();
+        tokio::spawn(upbit.feed.clone().run(frames(Vec::new()), Duration::from_millis(20), (Duration::from_millis(1), Duration::from_millis(1)), move || {
+            connects.fetch_add(1, Ordering::SeqCst);
+            async { Ok(frames(vec![Ok(Message::Binary(ticker("KRW-XRP", 700.0)))])) }
+        }));
 
-        Ok(krw_coins)
+        wait_for_price(&upbit, "XRP").await;
+        assert!(attempts.load(Ordering::SeqCst) >= 1);
+        assert!(upbit.is_connected());
     }
 }
@@ -0,0 +1,120 @@
+//! Shared helpers for the unit tests.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::models::PoolData;
+
+/// Fresh empty directory under the system temp dir, unique per call
+pub fn temp_dir(label: &str) -> PathBuf {
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "dex-gatherer-{}-{}-{}",
+        label,
+        std::process::id(),
+        SEQ.fetch_add(1, Ordering::Relaxed),
+    ));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Serve `router` on an ephemeral local port; returns its base URL
+pub async fn serve(router: axum::Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+/// Pool priced at `price_usd`, seen by `source` at `timestamp`; the rest is plausible
+/// filler, overridable with the builder methods below
+pub fn pool(symbol: &str, source: &str, price_usd: f64, timestamp: i64) -> PoolData {
+    PoolData {
+        symbol: symbol.to_string(),
+        chain: "ethereum".to_string(),
+        dex: "uniswap".to_string(),
+        pool_address: format!("0x{}{}", symbol.to_lowercase(), source.to_lowercase()),
+        pair: format!("{}/USDC", symbol),
+        price_usd,
+        lp_reserve_usd: 1_000_000.0,
+        volume_24h: 500_000.0,
+        source: source.to_string(),
+        timestamp,
+        ..Default::default()
+    }
+}
+
+/// Test-only overrides for [`pool`]
+impl PoolData {
+    pub fn on_chain(mut self, chain: &str) -> Self {
+        self.chain = chain.to_string();
+        self
+    }
+
+    pub fn at_address(mut self, address: &str) -> Self {
+        self.pool_address = address.to_string();
+        self
+    }
+
+    pub fn with_lp(mut self, lp_reserve_usd: f64) -> Self {
+        self.lp_reserve_usd = lp_reserve_usd;
+        self
+    }
+
+    pub fn with_volume(mut self, volume_24h: f64) -> Self {
+        self.volume_24h = volume_24h;
+        self
+    }
+
+    pub fn shared(self) -> Arc<PoolData> {
+        Arc::new(self)
+    }
+}
+
+/// Owned symbol list
+pub fn symbols(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+/// Tiny deterministic xorshift generator for property-style tests
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Any finite f64 (NaN/inf excluded), magnitudes spread over the whole range
+    pub fn finite_f64(&mut self) -> f64 {
+        loop {
+            let value = f64::from_bits(self.next_u64());
+            if value.is_finite() {
+                return value;
+            }
+        }
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    /// Short string, including quotes, unicode and separators that need escaping
+    pub fn string(&mut self) -> String {
+        const PARTS: [&str; 8] = ["ETH", "0xAbC", "\"q\"", "한글", "a,b", "/", "\\n", ""];
+        (0..self.next_u64() % 4)
+            .map(|_| PARTS[(self.next_u64() % PARTS.len() as u64) as usize])
+            .collect()
+    }
+
+    pub fn option<T>(&mut self, value: impl FnOnce(&mut Self) -> T) -> Option<T> {
+        if self.bool() { Some(value(self)) } else { None }
+    }
+}
@@ -0,0 +1,75 @@
+//! Collector → detector through the `DexGatherer` facade, over mock sources.
+
+use std::sync::Arc;
+use dex_gatherer::models::alert::ArbType;
+use dex_gatherer::sources::mock::{MockCex, MockSource};
+use dex_gatherer::sources::{CexSource, PoolSource, SourceError};
+use dex_gatherer::{Config, DexGatherer, PoolData};
+
+const CONFIG: &str = r#"
+    [arbitrage]
+    threshold = 0.01
+    update_interval = 1
+    [filter]
+    min_lp = 10000
+    min_volume = 0
+    [server]
+    host = "127.0.0.1"
+    port = 8080
+"#;
+
+fn pool(symbol: &str, dex: &str, address: &str, price: f64, lp: f64) -> PoolData {
+    PoolData::new(
+        symbol.to_string(), "ethereum".to_string(), dex.to_string(),
+        address.to_string(), format!("{symbol}/USDC"), price,
+        lp, 100_000.0, String::new(),
+    )
+}
+
+fn gatherer() -> DexGatherer {
+    let config: Config = toml::from_str(CONFIG).expect("config parses");
+    let sources: Vec<Arc<dyn PoolSource>> = vec![
+        Arc::new(MockSource::new("MockA")
+            .with_pools("ETH", vec![pool("ETH", "uniswap", "0xa1", 3000.0, 5_000_000.0)])
+            // Below min_lp: collected but filtered out
+            .with_pools("PEPE", vec![pool("PEPE", "uniswap", "0xa2", 0.00001, 500.0)])),
+        Arc::new(MockSource::new("MockB")
+            .with_pools("ETH", vec![pool("ETH", "sushiswap", "0xb1", 3300.0, 5_000_000.0)])),
+        Arc::new(MockSource::failing("Broken", SourceError::Http(500))),
+    ];
+    DexGatherer::with_sources(&config, sources)
+}
+
+#[tokio::test]
+async fn collected_pools_reach_the_cache_and_the_detector() {
+    let mut gatherer = gatherer();
+    let symbols = vec!["ETH".to_string(), "PEPE".to_string()];
+
+    let mut pools = gatherer.collect_once(&symbols).await;
+    pools.sort_by(|a, b| a.pool_address.cmp(&b.pool_address));
+    let addresses: Vec<&str> = pools.iter().map(|p| p.pool_address.as_str()).collect();
+    assert_eq!(addresses, ["0xa1", "0xb1"]);
+    assert_eq!(pools[0].source, "MockA");
+    assert_eq!(gatherer.cache().get_all().len(), 2);
+
+    // A failing source doesn't stop the others
+    let stats = gatherer.collector().get_stats();
+    assert!(stats.failed.load(std::sync::atomic::Ordering::Relaxed) > 0);
+
+    let alerts = gatherer.detect();
+    assert_eq!(alerts.len(), 1);
+    let alert = &alerts[0];
+    assert_eq!((alert.symbol.as_str(), alert.arb_type), ("ETH", ArbType::DexToDex));
+    assert_eq!((alert.low_price, alert.high_price), (3000.0, 3300.0));
+    assert!(alert.net_diff_pct > 1.0 && alert.net_diff_pct < alert.diff_pct);
+
+    // With an exchange configured the CEX leg is compared too
+    let exchanges: Vec<Arc<dyn CexSource>> = vec![Arc::new(MockCex::new("Upbit", &[("ETH", 3600.0)]))];
+    gatherer.set_exchanges(exchanges);
+    let cex: Vec<(f64, String)> = gatherer.detect().into_iter()
+        .filter(|a| a.arb_type == ArbType::DexToCex)
+        .map(|a| (a.low_price, a.high_source))
+        .collect();
+    assert_eq!(cex.len(), 2, "{cex:?}");
+    assert!(cex.iter().all(|(_, high)| high == "upbit"));
+}